@@ -6,7 +6,7 @@ iters = 1
 def benchmark_rusty(test):
     start = datetime.now()
     for i in range(iters):
-        subprocess.call(["./target/release/RustyPython.exe", test])
+        subprocess.call(["./target/release/RustyPython.exe", f"tests/{test}"])
     end = datetime.now()
 
     return (end - start).total_seconds()
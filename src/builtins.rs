@@ -2,5 +2,6 @@ pub mod function_utils;
 pub mod globals;
 pub mod structure;
 pub mod functions;
+pub mod modules;
 pub mod types;
 pub mod statics;
\ No newline at end of file
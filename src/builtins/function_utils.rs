@@ -1,43 +1,76 @@
 use std::rc::Rc;
 use crate::builtins::types::object::expect_class;
+use crate::builtins::functions::isinstance::is_instance;
 use crate::builtins::structure::magic_methods::PyMagicMethod;
 use crate::builtins::structure::pyclass::PyClass;
-use crate::builtins::structure::pyobject::{FuncReturnType, PyInternalFunction, PyInternalObject, PyMutableObject, PyObject};
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{FuncReturnType, PyInternalFunction, PyInternalObject, PyIteratorFlag, PyMutableObject, PyObject};
 use crate::pyarena::PyArena;
+use crate::vm::call_code;
 
 pub fn call_function(func: PyObject, args: &[PyObject], arena: &mut PyArena) -> FuncReturnType {
     match func {
         PyObject::Internal(inner) => {
             match inner {
                 PyInternalObject::InternalFunction(func) => eval_internal_func(&func, args, arena),
-                PyInternalObject::InternalClass(pyclass) => eval_obj_init(pyclass, args, arena)
+                PyInternalObject::InternalClass(pyclass) => call_class(pyclass, args, arena)
             }
         }
-        PyObject::Mutable(inner) => {
-            match &*inner.borrow() {
-                PyMutableObject::Function(_) => {todo!()}
-                _ => {panic!("Mutable object is not a function")}
+        PyObject::Mutable(ref inner) => {
+            let code = match &*inner.borrow() {
+                PyMutableObject::Function(function) => Some((function.code.clone(), function.class_cell.clone())),  // the borrow can't be held while the body runs
+                PyMutableObject::Instance(_) => None,
+            };
+
+            match code {
+                Some((code, class_cell)) => call_code(&code, class_cell.as_ref(), args, arena),
+                None => call_instance(&func, args, arena),
             }
         }
-        other => {panic!("{:?} is not a function", other)}
+        other => Err(not_callable(&other, arena)),
+    }
+}
+
+fn not_callable(pyobj: &PyObject, arena: &mut PyArena) -> PyException {
+    let message = format!("'{}' object is not callable", pyobj.clone_class(arena).get_name());
+    arena.exceptions.type_error.instantiate(message)
+}
+
+fn call_class(pyclass: Rc<PyClass>, args: &[PyObject], arena: &mut PyArena) -> FuncReturnType {  // a metaclass can take over instantiation with `__call__`
+    match pyclass.get_metaclass(arena).search_for_user_attribute("__call__") {
+        Some(call_func) => call_function_1_arg_min(&call_func, &PyObject::new_internal_class(pyclass), args, arena),
+        None => eval_obj_init(pyclass, args, arena),
     }
 }
 
+fn call_instance(pyself: &PyObject, args: &[PyObject], arena: &mut PyArena) -> FuncReturnType {  // instances are callable through `__call__` on their class
+    let Some(call_func) = pyself.get_method("__call__", arena) else {
+        return Err(not_callable(pyself, arena));
+    };
+
+    call_function_1_arg_min(&call_func, pyself, args, arena)
+}
+
 pub fn call_function_1_arg_min(func: &PyObject, first_arg: &PyObject, args: &[PyObject], arena: &mut PyArena) -> FuncReturnType {
     match func {
         PyObject::Internal(inner) => {
             match inner {
                 PyInternalObject::InternalFunction(func) => eval_internal_func_1_arg_min(func, first_arg, args, arena),
-                PyInternalObject::InternalClass(_pyclass) => panic!("This function should not be used to initialize a class")
+                PyInternalObject::InternalClass(pyclass) => call_class(pyclass.clone(), &[std::slice::from_ref(first_arg), args].concat(), arena)
             }
         }
         PyObject::Mutable(inner) => {
-            match &*inner.borrow() {
-                PyMutableObject::Function(_) => {todo!()}
-                _ => {panic!("Mutable object is not a function")}
+            let code = match &*inner.borrow() {
+                PyMutableObject::Function(function) => Some((function.code.clone(), function.class_cell.clone())),
+                PyMutableObject::Instance(_) => None,
+            };
+
+            match code {
+                Some((code, class_cell)) => call_code(&code, class_cell.as_ref(), &[std::slice::from_ref(first_arg), args].concat(), arena),
+                None => call_instance(func, &[std::slice::from_ref(first_arg), args].concat(), arena),
             }
         }
-        other => {panic!("{:?} is not a function", other)}
+        other => Err(not_callable(other, arena)),  // e.g. a class attribute shadowing a dunder with a value
     }
 }
 
@@ -56,6 +89,9 @@ pub(crate) fn eval_internal_func(func: &Rc<PyInternalFunction>, args: &[PyObject
         (PyInternalFunction::BivariateFunc(func), 2) => {
             func(arena, &args[0], &args[1])
         }
+        (PyInternalFunction::VariadicFunc(func), n) if n > 0 => {
+            func(arena, &args[0], &args[1..n])
+        }
         (PyInternalFunction::ManyArgFunc(func), _n) => {
             func(arena, args)
         }
@@ -80,6 +116,9 @@ pub(crate) fn eval_internal_func_1_arg_min(func: &Rc<PyInternalFunction>, first_
         (PyInternalFunction::BivariateFunc(func), 1) => {
             func(arena, first_arg, &args[0])
         }
+        (PyInternalFunction::VariadicFunc(func), _n) => {
+            func(arena, first_arg, args)
+        }
         (PyInternalFunction::ManyArgFunc(_func), _n) => {
             panic!("ManyArgFunc should be called with `eval_internal_func()`")
         }
@@ -103,7 +142,11 @@ pub(crate) fn eval_obj_init(pyclass: Rc<PyClass>, args: &[PyObject], arena: &mut
     let new_func = new_func.unwrap();
     let init_func = init_func.unwrap();
 
-    let new_object = call_function_1_arg_min(&new_func, &PyObject::new_internal_class(pyclass), args, arena)?;
+    let new_object = call_function_1_arg_min(&new_func, &PyObject::new_internal_class(pyclass.clone()), args, arena)?;
+
+    if !is_instance(&new_object, &pyclass, arena) {  // `__new__` returned something else, which is already initialized
+        return Ok(new_object);
+    }
 
     let _init_rtn = call_function_1_arg_min(&init_func, &new_object, args, arena)?; // TODO check if init_rtn is None
 
@@ -119,4 +162,42 @@ pub(crate) fn init_internal_class(pyclass: Rc<PyClass>, args: &[PyObject], arena
     eval_internal_func_1_arg_min(&init_func, &new_object, args, arena)?;
     
     Ok(new_object)
-}
\ No newline at end of file
+}
+
+pub(crate) fn get_next_method(iterator: &PyObject, arena: &mut PyArena) -> FuncReturnType {  // the `__next__` of whatever `__iter__` returned
+    match iterator.get_magic_method(&PyMagicMethod::Next, arena) {
+        Some(next_func) => Ok(next_func),
+        None => {
+            let message = format!("iter() returned non-iterator of type '{}'", iterator.clone_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+pub(crate) fn call_next(next_func: &PyObject, iterator: &PyObject, arena: &mut PyArena) -> FuncReturnType {  // python iterators finish by raising `StopIteration`, builtin ones return the flag
+    match call_function_1_arg_min(next_func, iterator, &[], arena) {
+        Err(err) if err.is_instance_of(&arena.exceptions.stop_iteration) => Ok(PyObject::IteratorFlag(PyIteratorFlag::StopIteration)),
+        result => result,
+    }
+}
+
+pub(crate) fn collect_iterable(iterable: &PyObject, arena: &mut PyArena) -> Result<Vec<PyObject>, PyException> {  // drains `iterable` through `__iter__`/`__next__`
+    let Some(iter_func) = iterable.get_magic_method(&PyMagicMethod::Iter, arena) else {
+        let message = format!("'{}' object is not iterable", iterable.clone_class(arena).get_name());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let iterator = call_function_1_arg_min(&iter_func, iterable, &[], arena)?;
+    let next_func = get_next_method(&iterator, arena)?;
+
+    let mut items = vec![];
+
+    loop {
+        match call_next(&next_func, &iterator, arena)? {
+            PyObject::IteratorFlag(PyIteratorFlag::StopIteration) => break,
+            item => items.push(item),
+        }
+    }
+
+    Ok(items)
+}
@@ -1,3 +1,9 @@
 pub mod print;
 pub mod math_op;
-pub mod compare;
\ No newline at end of file
+pub mod compare;
+pub mod abs;
+pub mod len;
+pub mod pow;
+pub mod hash;pub mod isinstance;
+pub mod exit;
+pub mod attributes;
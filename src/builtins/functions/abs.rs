@@ -0,0 +1,21 @@
+use crate::builtins::function_utils::call_function_1_arg_min;
+use crate::builtins::structure::magic_methods::PyMagicMethod;
+use crate::builtins::structure::pyobject::{FuncReturnType, PyObject};
+use crate::pyarena::PyArena;
+
+pub fn py_abs(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    if args.len() != 1 {
+        let message = format!("abs() takes exactly one argument ({} given)", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+    
+    let arg = &args[0];
+    let abs_func = arg.get_magic_method(&PyMagicMethod::Abs, arena);
+    
+    if let Some(abs_func) = abs_func {
+        return call_function_1_arg_min(&abs_func, arg, &[], arena);
+    }
+    
+    let message = format!("bad operand type for abs(): '{}'", arg.clone_class(arena).get_name());
+    Err(arena.exceptions.type_error.instantiate(message))
+}
@@ -0,0 +1,281 @@
+use std::rc::Rc;
+use crate::builtins::function_utils::{call_function, call_function_1_arg_min};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{EmptyFuncReturnType, FuncReturnType, PyImmutableObject, PyInternalFunction, PyInternalObject, PyMutableObject, PyObject};
+use crate::builtins::types::method::new_method;
+use crate::builtins::types::pysuper::{is_super, super_get_attribute};
+use crate::builtins::types::pytype::type_get_attribute;
+use crate::builtins::types::weakref::resolve_proxy;
+use crate::gc::note_finalizer_defined;
+use crate::pyarena::PyArena;
+
+pub fn is_plain_function(pyobj: &PyObject) -> bool {  // functions that bind `self` when looked up on an instance, builtins like `len` don't
+    match pyobj {
+        PyObject::Mutable(inner) => matches!(*inner.borrow(), PyMutableObject::Function(_)),
+        PyObject::Internal(PyInternalObject::InternalFunction(func)) => matches!(**func, PyInternalFunction::UnaryFunc(_) | PyInternalFunction::BivariateFunc(_) | PyInternalFunction::VariadicFunc(_)),
+        _ => false,
+    }
+}
+
+pub fn is_data_descriptor(pyobj: &PyObject, arena: &mut PyArena) -> bool {  // these take priority over the instance's own attributes
+    matches!(pyobj, PyObject::Mutable(_)) && (pyobj.get_method("__set__", arena).is_some() || pyobj.get_method("__delete__", arena).is_some())
+}
+
+pub fn descriptor_get(descriptor: PyObject, instance: Option<&PyObject>, owner: &Rc<PyClass>, arena: &mut PyArena) -> FuncReturnType {  // resolves a class attribute, `instance` is `None` when it was looked up on the class itself
+    if is_plain_function(&descriptor) {
+        return Ok(match instance {
+            Some(instance) => new_method(descriptor, instance.clone(), arena),
+            None => descriptor,
+        });
+    }
+
+    let Some(get_func) = descriptor.get_method("__get__", arena) else {
+        return Ok(descriptor);
+    };
+
+    let instance = instance.cloned().unwrap_or_else(|| arena.statics.none().clone());
+    call_function_1_arg_min(&get_func, &descriptor, &[instance, PyObject::new_internal_class(owner.clone())], arena)
+}
+
+pub const ATTRIBUTE_HOOKS: [&str; 4] = ["__getattribute__", "__getattr__", "__setattr__", "__delattr__"];
+
+fn find_hook(obj: &PyObject, hook_name: &str, arena: &PyArena) -> Option<(Rc<PyClass>, PyObject)> {  // only instances of user classes can override the attribute hooks
+    if !arena.attribute_hooks_defined {
+        return None;
+    }
+
+    let PyObject::Mutable(inner) = obj else {
+        return None;
+    };
+
+    let pyclass = inner.borrow().get_class().clone();
+    let hook = pyclass.search_for_user_attribute(hook_name)?;
+
+    Some((pyclass, hook))
+}
+
+fn call_hook(hook: PyObject, obj: &PyObject, owner: &Rc<PyClass>, args: &[PyObject], arena: &mut PyArena) -> FuncReturnType {
+    if is_plain_function(&hook) {  // skips creating a bound method
+        return call_function_1_arg_min(&hook, obj, args, arena);
+    }
+
+    let bound_hook = descriptor_get(hook, Some(obj), owner, arena)?;
+    call_function(bound_hook, args, arena)
+}
+
+pub fn uses_generic_getattribute(obj: &PyObject, arena: &PyArena) -> bool {  // lets method calls skip the lookup, which is only safe without `__getattribute__`, `super` objects have their own
+    find_hook(obj, "__getattribute__", arena).is_none() && !is_super(obj, arena)
+}
+
+pub fn get_attribute(obj: &PyObject, name: &str, arena: &mut PyArena) -> FuncReturnType {
+    let result = match find_hook(obj, "__getattribute__", arena) {
+        Some((owner, hook)) => call_hook(hook, obj, &owner, &[PyObject::new_string(name.to_string())], arena),
+        None => generic_get_attribute(obj, name, arena),
+    };
+
+    match result {
+        Err(err) if err.is_instance_of(&arena.exceptions.attribute_error) => match find_hook(obj, "__getattr__", arena) {  // only consulted once the normal lookup failed
+            Some((owner, hook)) => call_hook(hook, obj, &owner, &[PyObject::new_string(name.to_string())], arena),
+            None => Err(err),
+        },
+        result => result,
+    }
+}
+
+pub fn generic_get_attribute(obj: &PyObject, name: &str, arena: &mut PyArena) -> FuncReturnType {  // `object.__getattribute__`
+    if let Some(referent) = resolve_proxy(obj, arena)? {
+        return get_attribute(&referent, name, arena);
+    }
+
+    if let Some(attribute) = super_get_attribute(obj, name, arena)? {
+        return Ok(attribute);
+    }
+
+    let class_name = match obj {
+        PyObject::Internal(PyInternalObject::InternalClass(pyclass)) => return type_get_attribute(pyclass, name, arena),
+        PyObject::Internal(PyInternalObject::InternalFunction(_)) => "builtin_function_or_method".to_string(),
+        PyObject::IteratorFlag(_) => {panic!("IteratorFlag has no attributes")}
+        _ => {
+            let pyclass = obj.clone_class(arena);
+            let class_attribute = pyclass.search_for_attribute(name);
+
+            if let Some(ref descriptor) = class_attribute {  // data descriptors like `property` win over the instance's own fields
+                if is_data_descriptor(descriptor, arena) {
+                    return descriptor_get(descriptor.clone(), Some(obj), &pyclass, arena);
+                }
+            }
+
+            let field = match obj {
+                PyObject::Mutable(inner) => inner.borrow().get_field(name, arena),
+                immutable => immutable.expect_immutable().get_field(name, arena),
+            };
+
+            if let Some(field) = field {
+                return Ok(field);
+            }
+
+            if let Some(class_attribute) = class_attribute {
+                return descriptor_get(class_attribute, Some(obj), &pyclass, arena);
+            }
+
+            pyclass.get_name().clone()
+        }
+    };
+
+    Err(arena.exceptions.attribute_error.instantiate(format!("'{class_name}' object has no attribute '{name}'")))
+}
+
+pub fn set_attribute(obj: &PyObject, name: &str, value: PyObject, arena: &mut PyArena) -> EmptyFuncReturnType {
+    match find_hook(obj, "__setattr__", arena) {
+        Some((owner, hook)) => call_hook(hook, obj, &owner, &[PyObject::new_string(name.to_string()), value], arena).map(|_none| ()),
+        None => generic_set_attribute(obj, name, value, arena),
+    }
+}
+
+pub fn generic_set_attribute(obj: &PyObject, name: &str, value: PyObject, arena: &mut PyArena) -> EmptyFuncReturnType {  // `object.__setattr__`
+    if let Some(referent) = resolve_proxy(obj, arena)? {
+        return set_attribute(&referent, name, value, arena);
+    }
+
+    if let PyObject::Mutable(ref inner) = obj {
+        if matches!(*inner.borrow(), PyMutableObject::Instance(_)) {
+            if let Some(descriptor) = obj.clone_class(arena).search_for_attribute(name) {
+                if let Some(set_func) = descriptor.get_method("__set__", arena) {
+                    call_function_1_arg_min(&set_func, &descriptor, &[obj.clone(), value], arena)?;
+                    return Ok(());
+                }
+            }
+
+            return inner.borrow_mut().expect_instance_mut().set_field(name.to_string(), value, arena);
+        }
+    }
+
+    if let PyObject::Internal(PyInternalObject::InternalClass(ref pyclass)) = obj {
+        if pyclass.is_user_defined() {
+            arena.attribute_hooks_defined |= ATTRIBUTE_HOOKS.contains(&name);
+            if name == "__del__" {
+                note_finalizer_defined();
+            }
+            pyclass.set_attribute(name.to_string(), value, arena);
+            return Ok(());
+        }
+
+        let message = format!("cannot set '{name}' attribute of immutable type '{}'", pyclass.get_name());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let message = format!("'{}' object has no attribute '{name}'", obj.clone_class(arena).get_name());
+    Err(arena.exceptions.attribute_error.instantiate(message))
+}
+
+pub fn delete_attribute(obj: &PyObject, name: &str, arena: &mut PyArena) -> EmptyFuncReturnType {
+    match find_hook(obj, "__delattr__", arena) {
+        Some((owner, hook)) => call_hook(hook, obj, &owner, &[PyObject::new_string(name.to_string())], arena).map(|_none| ()),
+        None => generic_delete_attribute(obj, name, arena),
+    }
+}
+
+pub fn generic_delete_attribute(obj: &PyObject, name: &str, arena: &mut PyArena) -> EmptyFuncReturnType {  // `object.__delattr__`
+    if let Some(referent) = resolve_proxy(obj, arena)? {
+        return delete_attribute(&referent, name, arena);
+    }
+
+    match obj {
+        PyObject::Mutable(inner) if matches!(*inner.borrow(), PyMutableObject::Instance(_)) => {
+            if let Some(descriptor) = obj.clone_class(arena).search_for_attribute(name) {
+                if let Some(delete_func) = descriptor.get_method("__delete__", arena) {
+                    call_function_1_arg_min(&delete_func, &descriptor, std::slice::from_ref(obj), arena)?;
+                    return Ok(());
+                }
+            }
+
+            if inner.borrow_mut().expect_instance_mut().remove_field(name).is_some() {
+                return Ok(());
+            }
+        }
+        PyObject::Internal(PyInternalObject::InternalClass(pyclass)) => {
+            if !pyclass.is_user_defined() {
+                let message = format!("cannot delete '{name}' attribute of immutable type '{}'", pyclass.get_name());
+                return Err(arena.exceptions.type_error.instantiate(message));
+            }
+
+            if pyclass.remove_attribute(name, arena).is_some() {
+                return Ok(());
+            }
+
+            let message = format!("type object '{}' has no attribute '{name}'", pyclass.get_name());
+            return Err(arena.exceptions.attribute_error.instantiate(message));
+        }
+        _ => {}
+    }
+
+    let message = format!("'{}' object has no attribute '{name}'", obj.clone_class(arena).get_name());
+    Err(arena.exceptions.attribute_error.instantiate(message))
+}
+
+pub fn expect_attribute_name(pyobj: &PyObject, arena: &mut PyArena) -> Result<String, PyException> {
+    if let PyObject::Immutable(immutable) = pyobj {
+        if let PyImmutableObject::Str(ref name) = **immutable {
+            return Ok(name.clone());
+        }
+    }
+
+    let message = format!("attribute name must be string, not '{}'", pyobj.clone_class(arena).get_name());
+    Err(arena.exceptions.type_error.instantiate(message))
+}
+
+pub fn py_getattr(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    let (obj, name, default) = match args {
+        [obj, name] => (obj, name, None),
+        [obj, name, default] => (obj, name, Some(default)),
+        _ => {
+            let message = format!("getattr expected 2 or 3 arguments, got {}", args.len());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    };
+
+    let name = expect_attribute_name(name, arena)?;
+
+    match (get_attribute(obj, &name, arena), default) {
+        (Err(err), Some(default)) if err.is_instance_of(&arena.exceptions.attribute_error) => Ok(default.clone()),
+        (result, _) => result,
+    }
+}
+
+pub fn py_setattr(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    let [obj, name, value] = args else {
+        let message = format!("setattr expected 3 arguments, got {}", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let name = expect_attribute_name(name, arena)?;
+    set_attribute(obj, &name, value.clone(), arena)?;
+    Ok(arena.statics.none().clone())
+}
+
+pub fn py_hasattr(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    let [obj, name] = args else {
+        let message = format!("hasattr expected 2 arguments, got {}", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let name = expect_attribute_name(name, arena)?;
+
+    match get_attribute(obj, &name, arena) {
+        Ok(_attribute) => Ok(arena.statics.get_bool(true).clone()),
+        Err(err) if err.is_instance_of(&arena.exceptions.attribute_error) => Ok(arena.statics.get_bool(false).clone()),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn py_delattr(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    let [obj, name] = args else {
+        let message = format!("delattr expected 2 arguments, got {}", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let name = expect_attribute_name(name, arena)?;
+    delete_attribute(obj, &name, arena)?;
+    Ok(arena.statics.none().clone())
+}
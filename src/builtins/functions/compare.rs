@@ -1,7 +1,11 @@
-use crate::builtins::function_utils::call_function_1_arg_min;
+use crate::builtins::function_utils::{call_function_1_arg_min, call_next, get_next_method};
+use crate::builtins::functions::math_op::{binary_dispatch, call_binary_method};
 use crate::builtins::structure::magic_methods::PyMagicMethod;
-use crate::builtins::structure::pyobject::{FuncReturnType, PyObject};
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{FuncReturnType, PyIteratorFlag, PyObject};
+use crate::builtins::types::pybool::convert_pyobj_to_bool;
 use crate::parser::Comparator;
+use crate::inline_cache::OpCache;
 use crate::pyarena::PyArena;
 
 pub fn compare_op(left: &PyObject, right: &PyObject, comp: &Comparator, arena: &mut PyArena) -> FuncReturnType {
@@ -15,56 +19,107 @@ pub fn compare_op(left: &PyObject, right: &PyObject, comp: &Comparator, arena: &
 
         Comparator::Is => Ok(is_compare(false, left, right, arena)),
         Comparator::IsNot => Ok(is_compare(true, left, right, arena)),
-        Comparator::In => {todo!()}
-        Comparator::NotIn => {todo!()}
+        Comparator::In => contains_op(false, left, right, arena),
+        Comparator::NotIn => contains_op(true, left, right, arena),
+    }
+}
+
+pub fn cached_compare_op(left: &PyObject, right: &PyObject, comp: &Comparator, cache: &OpCache, arena: &mut PyArena) -> FuncReturnType {  // rich comparisons skip the method lookup while the operand classes stay the same
+    let op = match comp {
+        Comparator::Equal => PyMagicMethod::Eq,
+        Comparator::NotEqual => PyMagicMethod::Ne,
+        Comparator::LessThan => PyMagicMethod::Lt,
+        Comparator::LessThanOrEqual => PyMagicMethod::Le,
+        Comparator::GreaterThan => PyMagicMethod::Gt,
+        Comparator::GreaterThanOrEqual => PyMagicMethod::Ge,
+        _ => return compare_op(left, right, comp, arena),
+    };
+
+    let Some(left_compare_func) = cache.resolve(&op, left, right, arena) else {
+        return left_hand_compare_op(&op, left, right, arena);
+    };
+
+    match left_compare_func(arena, left, right) {
+        Err(err) if err.is_not_implemented_signal(arena) => right_hand_compare_op(&op, left, right, arena),
+        compare_result => compare_result,
+    }
+}
+
+pub fn eq_with_identity(left: &PyObject, right: &PyObject, arena: &mut PyArena) -> Result<bool, PyException> {  // like CPython's `PyObject_RichCompareBool`, identical objects are always equal
+    if left.is_same_object(right) {
+        return Ok(true);
+    }
+
+    let equal = left_hand_compare_op(&PyMagicMethod::Eq, left, right, arena)?;
+    convert_pyobj_to_bool(&equal, arena)
+}
+
+fn contains_op(not: bool, item: &PyObject, container: &PyObject, arena: &mut PyArena) -> FuncReturnType {
+    let contains = if let Some(contains_func) = container.get_magic_method(&PyMagicMethod::Contains, arena) {
+        let result = call_function_1_arg_min(&contains_func, container, std::slice::from_ref(item), arena)?;
+        convert_pyobj_to_bool(&result, arena)?
+    } else {
+        iter_contains(item, container, arena)?
+    };
+
+    Ok(arena.statics.get_bool(contains != not).clone())
+}
+
+fn iter_contains(item: &PyObject, container: &PyObject, arena: &mut PyArena) -> Result<bool, PyException> {  // fallback for containers without `__contains__`, stops at the first match
+    let Some(iter_func) = container.get_magic_method(&PyMagicMethod::Iter, arena) else {
+        let message = format!("argument of type '{}' is not iterable", container.clone_class(arena).get_name());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let iterator = call_function_1_arg_min(&iter_func, container, &[], arena)?;
+    let next_func = get_next_method(&iterator, arena)?;
+
+    loop {
+        match call_next(&next_func, &iterator, arena)? {
+            PyObject::IteratorFlag(PyIteratorFlag::StopIteration) => return Ok(false),
+            candidate => {
+                if eq_with_identity(&candidate, item, arena)? {
+                    return Ok(true);
+                }
+            }
+        }
     }
 }
 
 fn is_compare(not: bool, left: &PyObject, right: &PyObject, arena: &mut PyArena) -> PyObject {
-    let left_loc = left.get_memory_location();
-    let right_loc = right.get_memory_location();
-    
-    arena.statics.get_bool((left_loc == right_loc) != not).clone()
+    arena.statics.get_bool(left.is_same_object(right) != not).clone()
 }
 
 fn left_hand_compare_op(op: &PyMagicMethod, left: &PyObject, right: &PyObject, arena: &mut PyArena) -> FuncReturnType {
-    let left_compare_func = left.get_magic_method(op, arena);
-    
-    if let Some(left_compare_func) = left_compare_func {
-        let left_compare = call_function_1_arg_min(&left_compare_func, left, &[right.clone()], arena);
-        
-        return left_compare.or_else(|err| {
-            if err.is_same_type(&arena.exceptions.not_implemented_error) {
-                return right_hand_compare_op(op, left, right, arena);
-            }
-            Err(err)
-        })
+    match binary_dispatch(left, right, op, flip_to_right_hand_op(op), true, arena)? {
+        Some(result) => Ok(result),
+        None => fallback_compare_op(op, left, right, arena),
     }
-    
-    right_hand_compare_op(op, left, right, arena)
 }
 
 fn right_hand_compare_op(op: &PyMagicMethod, left: &PyObject, right: &PyObject, arena: &mut PyArena) -> FuncReturnType {
-    let right_op = flip_to_right_hand_op(op);
-    let right_compare_func = right.get_magic_method(right_op, arena);
-    
-    if let Some(right_compare_func) = right_compare_func {
-        let right_compare = call_function_1_arg_min(&right_compare_func, right, &[left.clone()], arena);
-        
-        return match right_compare {
-            Ok(result) => Ok(result),
-            Err(err) => {
-                if err.is_same_type(&arena.exceptions.not_implemented_error) {
-                    let message = format!("'{}' not supported between instances of '{}' and '{}'", op, left.clone_class(arena).get_name(), right.clone_class(arena).get_name());
-                    return Err(arena.exceptions.type_error.instantiate(message));
-                }
-                Err(err)
-            },
-        };
+    if let Some(right_compare_func) = right.get_magic_method(flip_to_right_hand_op(op), arena) {
+        if let Some(result) = call_binary_method(&right_compare_func, right, left, arena)? {
+            return Ok(result);
+        }
+    }
+
+    fallback_compare_op(op, left, right, arena)
+}
+
+fn fallback_compare_op(op: &PyMagicMethod, left: &PyObject, right: &PyObject, arena: &mut PyArena) -> FuncReturnType {  // neither side implements `op`, equality falls back to identity
+    match op {
+        PyMagicMethod::Eq => Ok(is_compare(false, left, right, arena)),
+        PyMagicMethod::Ne => {  // `object.__ne__` inverts whatever `__eq__` says
+            let equal = left_hand_compare_op(&PyMagicMethod::Eq, left, right, arena)?;
+            let not_equal = !convert_pyobj_to_bool(&equal, arena)?;
+            Ok(arena.statics.get_bool(not_equal).clone())
+        }
+        _ => {
+            let message = format!("'{}' not supported between instances of '{}' and '{}'", op.as_operator(), left.clone_class(arena).get_name(), right.clone_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
     }
-    
-    let message = format!("'{}' not supported between instances of '{}' and '{}'", op, left.clone_class(arena).get_name(), right.clone_class(arena).get_name());
-    Err(arena.exceptions.type_error.instantiate(message))
 }
 
 fn flip_to_right_hand_op(op: &PyMagicMethod) -> &PyMagicMethod {
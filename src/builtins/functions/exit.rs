@@ -0,0 +1,24 @@
+use crate::builtins::function_utils::eval_obj_init;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{FuncReturnType, PyObject};
+use crate::pyarena::PyArena;
+
+pub fn raise_system_exit(func_name: &str, args: &[PyObject], arena: &mut PyArena) -> FuncReturnType {
+    if args.len() > 1 {
+        let message = format!("{func_name}() takes at most 1 argument ({} given)", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let system_exit = arena.exceptions.system_exit.clone();
+    let exception = eval_obj_init(system_exit, args, arena)?;
+
+    Err(PyException::new(exception))
+}
+
+pub fn py_exit(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    raise_system_exit("exit", args, arena)
+}
+
+pub fn py_quit(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    raise_system_exit("quit", args, arena)
+}
@@ -0,0 +1,40 @@
+use crate::builtins::function_utils::call_function_1_arg_min;
+use crate::builtins::structure::magic_methods::PyMagicMethod;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{FuncReturnType, PyObject};
+use crate::builtins::types::object::{hash_pointer, unhashable__hash__};
+use crate::builtins::types::pyint::hash_int;
+use crate::pyarena::PyArena;
+
+pub fn hash_value(pyobj: &PyObject, arena: &mut PyArena) -> Result<i64, PyException> {
+    if let PyObject::Internal(_) = pyobj {  // builtin functions and classes hash by identity
+        return Ok(hash_pointer(pyobj.get_memory_location()));
+    }
+
+    let hash_func = pyobj.get_magic_method(&PyMagicMethod::Hash, arena).expect("object defines __hash__");
+
+    if hash_func.is_none() {  // `__hash__ = None` is how a class opts out of hashing
+        unhashable__hash__(arena, pyobj)?;
+    }
+
+    let is_builtin = matches!(hash_func, PyObject::Internal(_));
+    let hash = call_function_1_arg_min(&hash_func, pyobj, &[], arena)?;
+
+    match hash {
+        PyObject::Int(hash) if !is_builtin => return Ok(hash_int(hash)),  // reduced like an int of the same value, so a python `__hash__` can't return -1 or escape the modulus
+        PyObject::Int(hash) => return Ok(hash),
+        PyObject::Bool(hash) => return Ok(hash as i64),
+        _ => {}
+    }
+
+    Err(arena.exceptions.type_error.instantiate("__hash__ method should return an integer".to_string()))
+}
+
+pub fn py_hash(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    if args.len() != 1 {
+        let message = format!("hash() takes exactly one argument ({} given)", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    Ok(PyObject::new_int(hash_value(&args[0], arena)?))
+}
@@ -0,0 +1,56 @@
+use std::rc::Rc;
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{FuncReturnType, PyInternalObject, PyObject};
+use crate::builtins::types::tuple::try_tuple_items;
+use crate::pyarena::PyArena;
+
+pub fn expect_class_info(class_info: &PyObject, func_name: &str, arena: &mut PyArena) -> Result<Vec<Rc<PyClass>>, PyException> {  // a class or a (nested) tuple of classes
+    if let PyObject::Internal(PyInternalObject::InternalClass(pyclass)) = class_info {
+        return Ok(vec![pyclass.clone()]);
+    }
+
+    if let Some(items) = try_tuple_items(class_info) {
+        let mut classes = Vec::with_capacity(items.len());
+
+        for item in items.iter() {
+            classes.extend(expect_class_info(item, func_name, arena)?);
+        }
+
+        return Ok(classes);
+    }
+
+    let message = format!("{func_name}() arg 2 must be a type, a tuple of types, or a union");
+    Err(arena.exceptions.type_error.instantiate(message))
+}
+
+pub fn is_instance(pyobj: &PyObject, pyclass: &Rc<PyClass>, arena: &mut PyArena) -> bool {
+    pyobj.clone_class(arena).is_subclass_of(pyclass)
+}
+
+pub fn py_isinstance(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    if args.len() != 2 {
+        let message = format!("isinstance expected 2 arguments, got {}", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let classes = expect_class_info(&args[1], "isinstance", arena)?;
+    let result = classes.iter().any(|pyclass| is_instance(&args[0], pyclass, arena));
+
+    Ok(arena.statics.get_bool(result).clone())
+}
+
+pub fn py_issubclass(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    if args.len() != 2 {
+        let message = format!("issubclass expected 2 arguments, got {}", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let PyObject::Internal(PyInternalObject::InternalClass(subclass)) = &args[0] else {
+        return Err(arena.exceptions.type_error.instantiate("issubclass() arg 1 must be a class".to_string()));
+    };
+
+    let classes = expect_class_info(&args[1], "issubclass", arena)?;
+
+    Ok(arena.statics.get_bool(classes.iter().any(|pyclass| subclass.is_subclass_of(pyclass))).clone())
+}
@@ -0,0 +1,29 @@
+use crate::builtins::function_utils::call_function_1_arg_min;
+use crate::builtins::structure::magic_methods::PyMagicMethod;
+use crate::builtins::structure::pyobject::{FuncReturnType, PyObject};
+use crate::builtins::types::pyint::expect_int;
+use crate::pyarena::PyArena;
+
+pub fn py_len(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    if args.len() != 1 {
+        let message = format!("len() takes exactly one argument ({} given)", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+    
+    let arg = &args[0];
+    let len_func = arg.get_magic_method(&PyMagicMethod::Len, arena);
+    
+    if let Some(len_func) = len_func {
+        let len_rtn = call_function_1_arg_min(&len_func, arg, &[], arena)?;
+        let length = expect_int(&len_rtn, arena)?;
+
+        if length < 0 {
+            return Err(arena.exceptions.value_error.instantiate("__len__() should return >= 0".to_string()));
+        }
+
+        return Ok(PyObject::new_int(length));
+    }
+    
+    let message = format!("object of type '{}' has no len()", arg.clone_class(arena).get_name());
+    Err(arena.exceptions.type_error.instantiate(message))
+}
@@ -1,39 +1,93 @@
+use std::rc::Rc;
 use crate::builtins::function_utils::call_function_1_arg_min;
 use crate::builtins::structure::magic_methods::PyMagicMethod;
-use crate::builtins::structure::pyobject::{FuncReturnType, PyObject};
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{FuncReturnType, PyImmutableObject, PyObject};
+use crate::inline_cache::OpCache;
 use crate::pyarena::PyArena;
 
 pub fn math_op(left: PyObject, right: PyObject, py_magic_method: PyMagicMethod, arena: &mut PyArena) -> FuncReturnType {
-    let left_math_func = left.get_magic_method(&py_magic_method, arena);
-
-    if let Some(left_math_func) = left_math_func {
-        let math_result = call_function_1_arg_min(&left_math_func, &left, &[right.clone()], arena);
-
-        return match math_result {
-            Ok(result) => Ok(result),
-            Err(err) => {
-                if err.is_same_type(&arena.exceptions.not_implemented_error) {
-                    return right_hand_math_op(left, right, py_magic_method, arena)
-                }
-                Err(err)
-            },
-        };
+    let mut reflected = py_magic_method;
+    reflected.make_right_handed();
+
+    match binary_dispatch(&left, &right, &py_magic_method, &reflected, false, arena)? {
+        Some(result) => Ok(result),
+        None => Err(unsupported_operands(&left, &right, &py_magic_method, arena)),
     }
+}
+
+pub fn cached_math_op(left: PyObject, right: PyObject, py_magic_method: PyMagicMethod, cache: &OpCache, arena: &mut PyArena) -> FuncReturnType {  // skips the method lookup while the operand classes stay the same
+    let Some(left_math_func) = cache.resolve(&py_magic_method, &left, &right, arena) else {
+        return math_op(left, right, py_magic_method, arena);
+    };
 
-    right_hand_math_op(left, right, py_magic_method, arena)
+    match left_math_func(arena, &left, &right) {
+        Err(err) if err.is_not_implemented_signal(arena) => right_hand_math_op(left, right, py_magic_method, arena),
+        math_result => math_result,
+    }
 }
 
-fn right_hand_math_op(left: PyObject, right: PyObject, mut py_magic_method: PyMagicMethod,  arena: &mut PyArena) -> FuncReturnType {
+fn right_hand_math_op(left: PyObject, right: PyObject, mut py_magic_method: PyMagicMethod, arena: &mut PyArena) -> FuncReturnType {
     py_magic_method.make_right_handed();
-    
-    let right_math_func = right.get_magic_method(&py_magic_method, arena);
 
-    if let Some(right_math_fun) = right_math_func {
-        return call_function_1_arg_min(&right_math_fun, &right, &[left], arena);
+    if !Rc::ptr_eq(&left.clone_class(arena), &right.clone_class(arena)) {  // the reflected method is only for mixed operands
+        if let Some(right_math_func) = right.get_magic_method(&py_magic_method, arena) {
+            if let Some(result) = call_binary_method(&right_math_func, &right, &left, arena)? {
+                return Ok(result);
+            }
+        }
     }
-    
-    println!("{:?} {}", right_math_func, py_magic_method);
 
-    let error_msg = format!("unsupported operand type(s) for {}: '{}' and '{}'", py_magic_method, left.clone_class(arena).get_name(), right.clone_class(arena).get_name());
-    Err(arena.exceptions.not_implemented_error.instantiate(error_msg))
-}
\ No newline at end of file
+    Err(unsupported_operands(&left, &right, &py_magic_method, arena))
+}
+
+fn unsupported_operands(left: &PyObject, right: &PyObject, py_magic_method: &PyMagicMethod, arena: &mut PyArena) -> PyException {
+    let error_msg = format!("unsupported operand type(s) for {}: '{}' and '{}'", py_magic_method.as_operator(), left.clone_class(arena).get_name(), right.clone_class(arena).get_name());
+    arena.exceptions.type_error.instantiate(error_msg)
+}
+
+pub fn is_not_implemented(pyobj: &PyObject) -> bool {
+    matches!(pyobj, PyObject::Immutable(inner) if matches!(**inner, PyImmutableObject::NotImplemented))
+}
+
+pub(crate) fn call_binary_method(func: &PyObject, pyself: &PyObject, other: &PyObject, arena: &mut PyArena) -> Result<Option<PyObject>, PyException> {  // `None` when the method doesn't support `other`, python methods return `NotImplemented` and builtins raise a bare `NotImplementedError`
+    match call_function_1_arg_min(func, pyself, std::slice::from_ref(other), arena) {
+        Ok(result) if is_not_implemented(&result) => Ok(None),
+        Err(err) if err.is_not_implemented_signal(arena) => Ok(None),
+        result => result.map(Some),
+    }
+}
+
+pub(crate) fn binary_dispatch(left: &PyObject, right: &PyObject, py_magic_method: &PyMagicMethod, reflected: &PyMagicMethod, reflect_same_class: bool, arena: &mut PyArena) -> Result<Option<PyObject>, PyException> {  // tries `left.method(right)` and `right.reflected(left)` in CPython's order, `None` when neither side supports the other
+    let left_class = left.clone_class(arena);
+    let right_class = right.clone_class(arena);
+    let same_class = Rc::ptr_eq(&left_class, &right_class);
+
+    let mut right_func = match same_class && !reflect_same_class {
+        true => None,
+        false => right.get_magic_method(reflected, arena),
+    };
+
+    if let Some(ref reflected_func) = right_func {
+        let overrides = !left_class.search_for_magic_method(reflected).is_some_and(|inherited| inherited.is_same_object(reflected_func));
+
+        if !same_class && overrides && right_class.is_subclass_of(&left_class) {  // a subclass gets the first go, so it can override what its base does with it
+            if let Some(result) = call_binary_method(reflected_func, right, left, arena)? {
+                return Ok(Some(result));
+            }
+
+            right_func = None;
+        }
+    }
+
+    if let Some(left_func) = left.get_magic_method(py_magic_method, arena) {
+        if let Some(result) = call_binary_method(&left_func, left, right, arena)? {
+            return Ok(Some(result));
+        }
+    }
+
+    match right_func {
+        Some(right_func) => call_binary_method(&right_func, right, left, arena),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,32 @@
+use crate::builtins::function_utils::call_function_1_arg_min;
+use crate::builtins::functions::math_op::math_op;
+use crate::builtins::structure::magic_methods::PyMagicMethod;
+use crate::builtins::structure::pyobject::{FuncReturnType, PyObject};
+use crate::pyarena::PyArena;
+
+fn is_integer(pyobj: &PyObject) -> bool {
+    matches!(pyobj, PyObject::Int(_) | PyObject::Bool(_))
+}
+
+fn is_none(pyobj: &PyObject) -> bool {
+    pyobj.is_none()
+}
+
+pub fn py_pow(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    match args {
+        [base, exponent] => math_op(base.clone(), exponent.clone(), PyMagicMethod::Pow {right: false}, arena),
+        [base, exponent, modulus] if is_none(modulus) => math_op(base.clone(), exponent.clone(), PyMagicMethod::Pow {right: false}, arena),
+        [base, exponent, modulus] => {
+            if !(is_integer(base) && is_integer(exponent) && is_integer(modulus)) {
+                return Err(arena.exceptions.type_error.instantiate("pow() 3rd argument not allowed unless all arguments are integers".to_string()));
+            }
+
+            let pow_func = base.get_magic_method(&PyMagicMethod::Pow {right: false}, arena).expect("int always has __pow__");
+            call_function_1_arg_min(&pow_func, base, &[exponent.clone(), modulus.clone()], arena)
+        }
+        _ => {
+            let message = format!("pow expected 2 or 3 arguments, got {}", args.len());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::builtins::types::str::{py_repr};
+use crate::builtins::types::str::{py_str};
 use crate::builtins::structure::pyobject::{FuncReturnType, PyObject};
 use crate::pyarena::PyArena;
 
@@ -6,7 +6,7 @@ pub fn py_print(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
     let sep = " ";
     
     let str_fold = args.iter().try_fold(Vec::new(), |mut acc, arg| {
-        acc.push(py_repr(arg, arena)?.expect_immutable().expect_string());
+        acc.push(py_str(arg, arena)?.expect_immutable().expect_string());
         Ok(acc)
     })?;
     
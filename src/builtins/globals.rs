@@ -1,33 +1,100 @@
-use std::cell::{Cell, RefCell};
+use std::cell::Cell;
 use std::rc::Rc;
 use ahash::AHashMap;
 use crate::builtins::types::object::{get_object_class};
 use crate::builtins::functions::print::{py_print};
+use crate::builtins::functions::abs::py_abs;
+use crate::builtins::functions::len::py_len;
+use crate::builtins::functions::pow::py_pow;
+use crate::builtins::functions::hash::py_hash;
+use crate::builtins::functions::isinstance::{py_isinstance, py_issubclass};
+use crate::builtins::functions::exit::{py_exit, py_quit};
+use crate::builtins::functions::attributes::{py_delattr, py_getattr, py_hasattr, py_setattr};
 use crate::builtins::types::pybool::get_bool_class;
 use crate::builtins::types::pyfloat::get_float_class;
+use crate::builtins::types::pycomplex::get_complex_class;
 use crate::builtins::types::pyint::{get_int_class};
 use crate::builtins::types::range::{get_range_class, get_range_iterator_class};
+use crate::builtins::types::str::get_str_class;
+use crate::builtins::types::pybytes::{get_bytes_class, get_bytes_iterator_class};
+use crate::builtins::types::bytearray::get_bytearray_class;
+use crate::builtins::types::list::{get_list_class, get_list_iterator_class};
+use crate::builtins::types::tuple::{get_tuple_class, get_tuple_iterator_class};
+use crate::builtins::types::dict::get_dict_class;
+use crate::builtins::types::slice::get_slice_class;
+use crate::builtins::types::module::get_module_class;
+use crate::builtins::types::function::{get_builtin_function_class, get_function_class};
+use crate::builtins::types::method::get_method_class;
+use crate::builtins::types::property::get_property_class;
+use crate::builtins::types::classmethod::{get_classmethod_class, get_staticmethod_class};
+use crate::builtins::types::member::get_member_descriptor_class;
+use crate::builtins::types::pysuper::get_super_class;
+use crate::builtins::types::pytype::get_type_class;
+use crate::builtins::types::traceback::get_traceback_class;
+use crate::builtins::types::weakref::{get_weak_map_class, get_weakproxy_class, get_weakref_class};
 use crate::builtins::structure::pyclass::PyClass;
 use crate::builtins::structure::pyobject::{ManyArgFuncType, PyInternalFunction, PyObject};
-use crate::builtins::types::pynone::get_none_class;
+use crate::builtins::types::pynone::{get_none_class, get_not_implemented_class};
 
 #[derive(Debug)]
 pub struct Globals {
     pub object_class: Rc<PyClass>,
+    pub type_class: Rc<PyClass>,
     pub none_class: Rc<PyClass>,
+    pub not_implemented_class: Rc<PyClass>,
     pub int_class: Rc<PyClass>,
     pub bool_class: Rc<PyClass>,
     pub float_class: Rc<PyClass>,
+    pub complex_class: Rc<PyClass>,
     pub range_class: Rc<PyClass>,
     pub range_iterator_class: Rc<PyClass>,
+    pub str_class: Rc<PyClass>,
+    pub bytes_class: Rc<PyClass>,
+    pub bytes_iterator_class: Rc<PyClass>,
+    pub bytearray_class: Rc<PyClass>,
+    pub list_class: Rc<PyClass>,
+    pub list_iterator_class: Rc<PyClass>,
+    pub tuple_class: Rc<PyClass>,
+    pub tuple_iterator_class: Rc<PyClass>,
+    pub dict_class: Rc<PyClass>,
+    pub slice_class: Rc<PyClass>,
+    pub module_class: Rc<PyClass>,
+    pub function_class: Rc<PyClass>,
+    pub builtin_function_class: Rc<PyClass>,
+    pub method_class: Rc<PyClass>,
+    pub property_class: Rc<PyClass>,
+    pub classmethod_class: Rc<PyClass>,
+    pub staticmethod_class: Rc<PyClass>,
+    pub member_descriptor_class: Rc<PyClass>,
+    pub super_class: Rc<PyClass>,
+    pub weakref_class: Rc<PyClass>,
+    pub weakproxy_class: Rc<PyClass>,
+    pub weak_value_dictionary_class: Rc<PyClass>,
+    pub weak_key_dictionary_class: Rc<PyClass>,
+    pub traceback_class: Rc<PyClass>,
     pub print_func: Rc<PyInternalFunction>,
+    pub abs_func: Rc<PyInternalFunction>,
+    pub len_func: Rc<PyInternalFunction>,
+    pub pow_func: Rc<PyInternalFunction>,
+    pub hash_func: Rc<PyInternalFunction>,
+    pub isinstance_func: Rc<PyInternalFunction>,
+    pub issubclass_func: Rc<PyInternalFunction>,
+    pub exit_func: Rc<PyInternalFunction>,
+    pub quit_func: Rc<PyInternalFunction>,
+    pub getattr_func: Rc<PyInternalFunction>,
+    pub setattr_func: Rc<PyInternalFunction>,
+    pub hasattr_func: Rc<PyInternalFunction>,
+    pub delattr_func: Rc<PyInternalFunction>,
 }
 
 impl Globals {
     pub(crate) fn new() -> Globals {
         let object_class = Rc::new(get_object_class());
+        let type_class = Rc::new(get_type_class(object_class.clone()));
         let none_class = Rc::new(get_none_class(object_class.clone()));
+        let not_implemented_class = Rc::new(get_not_implemented_class(object_class.clone()));
         let float_class = Rc::new(get_float_class(object_class.clone()));
+        let complex_class = Rc::new(get_complex_class(object_class.clone()));
 
         let int_class = Rc::new(get_int_class(object_class.clone()));
         let bool_class = Rc::new(get_bool_class(int_class.clone()));
@@ -35,26 +102,115 @@ impl Globals {
         let range_class = Rc::new(get_range_class(object_class.clone()));
         let range_iterator_class = Rc::new(get_range_iterator_class(object_class.clone()));
         
+        let str_class = Rc::new(get_str_class(object_class.clone()));
+        let bytes_class = Rc::new(get_bytes_class(object_class.clone()));
+        let bytes_iterator_class = Rc::new(get_bytes_iterator_class(object_class.clone()));
+        let bytearray_class = Rc::new(get_bytearray_class(object_class.clone()));
+        let list_class = Rc::new(get_list_class(object_class.clone()));
+        let list_iterator_class = Rc::new(get_list_iterator_class(object_class.clone()));
+        let tuple_class = Rc::new(get_tuple_class(object_class.clone()));
+        let tuple_iterator_class = Rc::new(get_tuple_iterator_class(object_class.clone()));
+        let dict_class = Rc::new(get_dict_class(object_class.clone()));
+        let slice_class = Rc::new(get_slice_class(object_class.clone()));
+        let module_class = Rc::new(get_module_class(object_class.clone()));
+        let function_class = Rc::new(get_function_class(object_class.clone()));
+        let builtin_function_class = Rc::new(get_builtin_function_class(object_class.clone()));
+        let method_class = Rc::new(get_method_class(object_class.clone()));
+        let property_class = Rc::new(get_property_class(object_class.clone()));
+        let classmethod_class = Rc::new(get_classmethod_class(object_class.clone()));
+        let staticmethod_class = Rc::new(get_staticmethod_class(object_class.clone()));
+        let member_descriptor_class = Rc::new(get_member_descriptor_class(object_class.clone()));
+        let super_class = Rc::new(get_super_class(object_class.clone()));
+        let weakref_class = Rc::new(get_weakref_class(object_class.clone()));
+        let weakproxy_class = Rc::new(get_weakproxy_class(object_class.clone()));
+        let weak_value_dictionary_class = Rc::new(get_weak_map_class("WeakValueDictionary", object_class.clone()));
+        let weak_key_dictionary_class = Rc::new(get_weak_map_class("WeakKeyDictionary", object_class.clone()));
+        let traceback_class = Rc::new(get_traceback_class(object_class.clone()));
+        
         Globals {
             object_class,
+            type_class,
             none_class,
+            not_implemented_class,
             int_class,
             bool_class,
             float_class,
+            complex_class,
             range_class,
             range_iterator_class,
+            str_class,
+            bytes_class,
+            bytes_iterator_class,
+            bytearray_class,
+            list_class,
+            list_iterator_class,
+            tuple_class,
+            tuple_iterator_class,
+            dict_class,
+            slice_class,
+            module_class,
+            function_class,
+            builtin_function_class,
+            method_class,
+            property_class,
+            classmethod_class,
+            staticmethod_class,
+            member_descriptor_class,
+            super_class,
+            weakref_class,
+            weakproxy_class,
+            weak_value_dictionary_class,
+            weak_key_dictionary_class,
+            traceback_class,
             print_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_print as ManyArgFuncType))),
+            abs_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_abs as ManyArgFuncType))),
+            len_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_len as ManyArgFuncType))),
+            pow_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_pow as ManyArgFuncType))),
+            hash_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_hash as ManyArgFuncType))),
+            isinstance_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_isinstance as ManyArgFuncType))),
+            issubclass_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_issubclass as ManyArgFuncType))),
+            exit_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_exit as ManyArgFuncType))),
+            quit_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_quit as ManyArgFuncType))),
+            getattr_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_getattr as ManyArgFuncType))),
+            setattr_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_setattr as ManyArgFuncType))),
+            hasattr_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_hasattr as ManyArgFuncType))),
+            delattr_func: Rc::new(PyInternalFunction::ManyArgFunc(&(py_delattr as ManyArgFuncType))),
         }
     }
     
     pub fn create_exposed_globals(&self) -> AHashMap<String, Cell<PyObject>> {
         vec![
             ("object".to_string(), Cell::new(PyObject::new_internal_class(self.object_class.clone()))),
+            ("type".to_string(), Cell::new(PyObject::new_internal_class(self.type_class.clone()))),
             ("int".to_string(), Cell::new(PyObject::new_internal_class(self.int_class.clone()))),
             ("bool".to_string(), Cell::new(PyObject::new_internal_class(self.bool_class.clone()))),
             ("float".to_string(), Cell::new(PyObject::new_internal_class(self.float_class.clone()))),
+            ("complex".to_string(), Cell::new(PyObject::new_internal_class(self.complex_class.clone()))),
             ("range".to_string(), Cell::new(PyObject::new_internal_class(self.range_class.clone()))),
+            ("str".to_string(), Cell::new(PyObject::new_internal_class(self.str_class.clone()))),
+            ("bytes".to_string(), Cell::new(PyObject::new_internal_class(self.bytes_class.clone()))),
+            ("bytearray".to_string(), Cell::new(PyObject::new_internal_class(self.bytearray_class.clone()))),
+            ("list".to_string(), Cell::new(PyObject::new_internal_class(self.list_class.clone()))),
+            ("tuple".to_string(), Cell::new(PyObject::new_internal_class(self.tuple_class.clone()))),
+            ("dict".to_string(), Cell::new(PyObject::new_internal_class(self.dict_class.clone()))),
+            ("slice".to_string(), Cell::new(PyObject::new_internal_class(self.slice_class.clone()))),
+            ("property".to_string(), Cell::new(PyObject::new_internal_class(self.property_class.clone()))),
+            ("classmethod".to_string(), Cell::new(PyObject::new_internal_class(self.classmethod_class.clone()))),
+            ("staticmethod".to_string(), Cell::new(PyObject::new_internal_class(self.staticmethod_class.clone()))),
+            ("super".to_string(), Cell::new(PyObject::new_internal_class(self.super_class.clone()))),
             ("print".to_string(), Cell::new(PyObject::new_internal_func(self.print_func.clone()))),
+            ("abs".to_string(), Cell::new(PyObject::new_internal_func(self.abs_func.clone()))),
+            ("len".to_string(), Cell::new(PyObject::new_internal_func(self.len_func.clone()))),
+            ("pow".to_string(), Cell::new(PyObject::new_internal_func(self.pow_func.clone()))),
+            ("hash".to_string(), Cell::new(PyObject::new_internal_func(self.hash_func.clone()))),
+            ("isinstance".to_string(), Cell::new(PyObject::new_internal_func(self.isinstance_func.clone()))),
+            ("issubclass".to_string(), Cell::new(PyObject::new_internal_func(self.issubclass_func.clone()))),
+            ("exit".to_string(), Cell::new(PyObject::new_internal_func(self.exit_func.clone()))),
+            ("quit".to_string(), Cell::new(PyObject::new_internal_func(self.quit_func.clone()))),
+            ("getattr".to_string(), Cell::new(PyObject::new_internal_func(self.getattr_func.clone()))),
+            ("setattr".to_string(), Cell::new(PyObject::new_internal_func(self.setattr_func.clone()))),
+            ("hasattr".to_string(), Cell::new(PyObject::new_internal_func(self.hasattr_func.clone()))),
+            ("delattr".to_string(), Cell::new(PyObject::new_internal_func(self.delattr_func.clone()))),
         ].into_iter().collect()
     }
     
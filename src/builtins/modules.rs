@@ -0,0 +1,18 @@
+pub mod sys;
+pub mod gc;
+pub mod weakref;
+
+use ahash::AHashMap;
+use crate::builtins::globals::Globals;
+use crate::builtins::modules::gc::get_gc_module;
+use crate::builtins::modules::sys::get_sys_module;
+use crate::builtins::modules::weakref::get_weakref_module;
+use crate::builtins::structure::pyobject::PyObject;
+
+pub fn create_builtin_modules(globals: &Globals) -> AHashMap<String, PyObject> {  // modules that `import` finds without touching the filesystem
+    vec![
+        ("sys".to_string(), get_sys_module(globals.module_class.clone())),
+        ("gc".to_string(), get_gc_module(globals.module_class.clone())),
+        ("weakref".to_string(), get_weakref_module(globals)),
+    ].into_iter().collect()
+}
@@ -0,0 +1,114 @@
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{FuncReturnType, ManyArgFuncType, PyInternalFunction, PyObject};
+use crate::builtins::types::module::new_module;
+use crate::builtins::types::tuple::new_tuple;
+use crate::gc::{collect, get_counts, get_thresholds, is_enabled, set_enabled, set_thresholds, GENERATIONS};
+use crate::pyarena::PyArena;
+
+fn expect_no_args(name: &str, args: &[PyObject], arena: &mut PyArena) -> Result<(), PyException> {
+    if !args.is_empty() {
+        let message = format!("{name}() takes no arguments ({} given)", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    Ok(())
+}
+
+fn expect_integer(pyobj: &PyObject, arena: &mut PyArena) -> Result<i64, PyException> {
+    match *pyobj {
+        PyObject::Int(value) => Ok(value),
+        PyObject::Bool(value) => Ok(value as i64),
+        _ => {
+            let message = format!("'{}' object cannot be interpreted as an integer", pyobj.clone_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+fn new_int_tuple(values: [usize; GENERATIONS], arena: &mut PyArena) -> PyObject {
+    new_tuple(values.into_iter().map(|value| PyObject::new_int(value as i64)).collect(), arena)
+}
+
+pub fn gc_collect(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    if args.len() > 1 {
+        let message = format!("collect() takes at most 1 argument ({} given)", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let generation = match args.first() {
+        Some(generation) => expect_integer(generation, arena)?,
+        None => GENERATIONS as i64 - 1,
+    };
+
+    if !(0..GENERATIONS as i64).contains(&generation) {
+        return Err(arena.exceptions.value_error.instantiate("invalid generation".to_string()));
+    }
+
+    Ok(PyObject::new_int(collect(generation as usize, arena) as i64))
+}
+
+pub fn gc_enable(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    expect_no_args("enable", args, arena)?;
+    set_enabled(true);
+
+    Ok(arena.statics.none().clone())
+}
+
+pub fn gc_disable(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    expect_no_args("disable", args, arena)?;
+    set_enabled(false);
+
+    Ok(arena.statics.none().clone())
+}
+
+pub fn gc_isenabled(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    expect_no_args("isenabled", args, arena)?;
+
+    Ok(arena.statics.get_bool(is_enabled()).clone())
+}
+
+pub fn gc_get_count(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    expect_no_args("get_count", args, arena)?;
+
+    Ok(new_int_tuple(get_counts(), arena))
+}
+
+pub fn gc_get_threshold(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    expect_no_args("get_threshold", args, arena)?;
+
+    Ok(new_int_tuple(get_thresholds(), arena))
+}
+
+pub fn gc_set_threshold(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    if args.is_empty() || args.len() > GENERATIONS {
+        let message = format!("set_threshold() takes 1 to {GENERATIONS} arguments ({} given)", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let mut thresholds = Vec::with_capacity(args.len());
+
+    for arg in args {
+        thresholds.push(expect_integer(arg, arena)?.max(0) as usize);  // like CPython, a threshold of 0 turns automatic collection off
+    }
+
+    set_thresholds(&thresholds);
+
+    Ok(arena.statics.none().clone())
+}
+
+pub fn get_gc_module(module_class: Rc<PyClass>) -> PyObject {
+    let attributes = vec![
+        ("collect".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(gc_collect as ManyArgFuncType))))),
+        ("enable".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(gc_enable as ManyArgFuncType))))),
+        ("disable".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(gc_disable as ManyArgFuncType))))),
+        ("isenabled".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(gc_isenabled as ManyArgFuncType))))),
+        ("get_count".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(gc_get_count as ManyArgFuncType))))),
+        ("get_threshold".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(gc_get_threshold as ManyArgFuncType))))),
+        ("set_threshold".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(gc_set_threshold as ManyArgFuncType))))),
+    ].into_iter().collect::<AHashMap<_, _>>();
+
+    new_module("gc", attributes, module_class)
+}
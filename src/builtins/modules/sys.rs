@@ -0,0 +1,70 @@
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyobject::{FuncReturnType, ManyArgFuncType, PyInternalFunction, PyObject};
+use crate::builtins::functions::exit::raise_system_exit;
+use crate::builtins::types::list::new_list;
+use crate::builtins::types::module::new_module;
+use crate::pyarena::PyArena;
+
+pub fn sys_getrecursionlimit(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    if !args.is_empty() {
+        let message = format!("getrecursionlimit() takes no arguments ({} given)", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    Ok(PyObject::new_int(arena.recursion_limit as i64))
+}
+
+pub fn sys_setrecursionlimit(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    if args.len() != 1 {
+        let message = format!("setrecursionlimit() takes exactly one argument ({} given)", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let limit = match args[0] {
+        PyObject::Int(limit) => Some(limit),
+        PyObject::Bool(limit) => Some(limit as i64),
+        _ => None,
+    };
+
+    let Some(limit) = limit else {
+        let message = format!("'{}' object cannot be interpreted as an integer", args[0].clone_class(arena).get_name());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    if limit < 1 {
+        return Err(arena.exceptions.value_error.instantiate("recursion limit must be greater or equal than 1".to_string()));
+    }
+
+    if limit as usize <= arena.recursion_depth {
+        let message = format!("cannot set the recursion limit to {limit} at the recursion depth {}: the limit is too low", arena.recursion_depth);
+        return Err(arena.exceptions.recursion_error.instantiate(message));
+    }
+
+    arena.recursion_limit = limit as usize;
+
+    Ok(arena.statics.none().clone())
+}
+
+pub fn sys_exit(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    raise_system_exit("exit", args, arena)
+}
+
+pub fn set_sys_argv(argv: Vec<String>, arena: &mut PyArena) {
+    let argv = argv.into_iter().map(PyObject::new_string).collect();
+    let argv = new_list(argv, arena);
+    let sys_module = arena.modules.get("sys").expect("sys is always a builtin module").clone();
+
+    sys_module.expect_mutable().borrow_mut().expect_instance_mut().set_field("argv".to_string(), argv, arena).expect("modules accept any attribute");
+}
+
+pub fn get_sys_module(module_class: Rc<PyClass>) -> PyObject {
+    let attributes = vec![
+        ("exit".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(sys_exit as ManyArgFuncType))))),
+        ("getrecursionlimit".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(sys_getrecursionlimit as ManyArgFuncType))))),
+        ("setrecursionlimit".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(sys_setrecursionlimit as ManyArgFuncType))))),
+    ].into_iter().collect::<AHashMap<_, _>>();
+
+    new_module("sys", attributes, module_class)
+}
@@ -0,0 +1,24 @@
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::globals::Globals;
+use crate::builtins::structure::pyobject::{FuncReturnType, ManyArgFuncType, PyInternalFunction, PyObject};
+use crate::builtins::types::module::new_module;
+use crate::builtins::types::weakref::new_weakproxy;
+use crate::pyarena::PyArena;
+
+pub fn weakref_proxy(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    new_weakproxy(args, arena)
+}
+
+pub fn get_weakref_module(globals: &Globals) -> PyObject {
+    let attributes = vec![
+        ("ref".to_string(), PyObject::new_internal_class(globals.weakref_class.clone())),
+        ("ReferenceType".to_string(), PyObject::new_internal_class(globals.weakref_class.clone())),
+        ("proxy".to_string(), PyObject::new_internal_func(Rc::new(PyInternalFunction::ManyArgFunc(&(weakref_proxy as ManyArgFuncType))))),
+        ("ProxyType".to_string(), PyObject::new_internal_class(globals.weakproxy_class.clone())),
+        ("WeakValueDictionary".to_string(), PyObject::new_internal_class(globals.weak_value_dictionary_class.clone())),
+        ("WeakKeyDictionary".to_string(), PyObject::new_internal_class(globals.weak_key_dictionary_class.clone())),
+    ].into_iter().collect::<AHashMap<_, _>>();
+
+    new_module("weakref", attributes, globals.module_class.clone())
+}
@@ -9,17 +9,25 @@ pub enum PyMagicMethod {
     
     Str,
     Repr,
+    Hash,
     
     Add {right: bool},
     Sub {right: bool},
     Mul {right: bool},
     TrueDiv {right: bool},
+    FloorDiv {right: bool},
+    Mod {right: bool},
     Pow {right: bool},
     
+    Abs,
+    Neg,
+    Pos,
+    
     Int,
     Bytes,
     Bool,
     Float,
+    Complex,
     
     Iter,
     Next,
@@ -31,6 +39,10 @@ pub enum PyMagicMethod {
     Gt,
     Ge,
     Contains,
+    
+    Len,
+    GetItem,
+    SetItem,
 }
 
 impl PyMagicMethod {
@@ -40,15 +52,22 @@ impl PyMagicMethod {
             PyMagicMethod::Init => methods.__init__.clone(),
             PyMagicMethod::Str => methods.__str__.clone(),
             PyMagicMethod::Repr => methods.__repr__.clone(),
+            PyMagicMethod::Hash => methods.__hash__.clone(),
             PyMagicMethod::Add {right} => if *right {methods.__radd__.clone()} else { methods.__add__.clone() },
             PyMagicMethod::Sub {right} => if *right {methods.__rsub__.clone()} else { methods.__sub__.clone() },
             PyMagicMethod::Mul {right} => if *right {methods.__rmul__.clone()} else { methods.__mul__.clone() },
             PyMagicMethod::TrueDiv {right} => if *right {methods.__rtruediv__.clone()} else { methods.__truediv__.clone() },
+            PyMagicMethod::FloorDiv {right} => if *right {methods.__rfloordiv__.clone()} else { methods.__floordiv__.clone() },
+            PyMagicMethod::Mod {right} => if *right {methods.__rmod__.clone()} else { methods.__mod__.clone() },
             PyMagicMethod::Pow {right} => if *right {methods.__rpow__.clone()} else { methods.__pow__.clone() },
+            PyMagicMethod::Abs => methods.__abs__.clone(),
+            PyMagicMethod::Neg => methods.__neg__.clone(),
+            PyMagicMethod::Pos => methods.__pos__.clone(),
             PyMagicMethod::Int => methods.__int__.clone(),
             PyMagicMethod::Bool => methods.__bool__.clone(),
             PyMagicMethod::Bytes => methods.__bytes__.clone(),
             PyMagicMethod::Float => methods.__float__.clone(),
+            PyMagicMethod::Complex => methods.__complex__.clone(),
             PyMagicMethod::Iter => methods.__iter__.clone(),
             PyMagicMethod::Next => methods.__next__.clone(),
             PyMagicMethod::Eq => methods.__eq__.clone(),
@@ -58,6 +77,9 @@ impl PyMagicMethod {
             PyMagicMethod::Gt => methods.__gt__.clone(),
             PyMagicMethod::Ge => methods.__ge__.clone(),
             PyMagicMethod::Contains => methods.__contains__.clone(),
+            PyMagicMethod::Len => methods.__len__.clone(),
+            PyMagicMethod::GetItem => methods.__getitem__.clone(),
+            PyMagicMethod::SetItem => methods.__setitem__.clone(),
         }
     }
 
@@ -67,15 +89,22 @@ impl PyMagicMethod {
             PyMagicMethod::Init => &mut methods.__init__,
             PyMagicMethod::Str => &mut methods.__str__,
             PyMagicMethod::Repr => &mut methods.__repr__,
+            PyMagicMethod::Hash => &mut methods.__hash__,
             PyMagicMethod::Add {right} => if *right {&mut methods.__radd__} else { &mut methods.__add__ },
             PyMagicMethod::Sub {right} => if *right {&mut methods.__rsub__} else { &mut methods.__sub__ },
             PyMagicMethod::Mul {right} => if *right {&mut methods.__rmul__} else { &mut methods.__mul__ },
             PyMagicMethod::TrueDiv {right} => if *right {&mut methods.__rtruediv__} else { &mut methods.__truediv__ },
+            PyMagicMethod::FloorDiv {right} => if *right {&mut methods.__rfloordiv__} else { &mut methods.__floordiv__ },
+            PyMagicMethod::Mod {right} => if *right {&mut methods.__rmod__} else { &mut methods.__mod__ },
             PyMagicMethod::Pow {right} => if *right {&mut methods.__rpow__} else { &mut methods.__pow__ },
+            PyMagicMethod::Abs => &mut methods.__abs__,
+            PyMagicMethod::Neg => &mut methods.__neg__,
+            PyMagicMethod::Pos => &mut methods.__pos__,
             PyMagicMethod::Int => &mut methods.__int__,
             PyMagicMethod::Bool => &mut methods.__bool__,
             PyMagicMethod::Bytes => &mut methods.__bytes__,
             PyMagicMethod::Float => &mut methods.__float__,
+            PyMagicMethod::Complex => &mut methods.__complex__,
             PyMagicMethod::Iter => &mut methods.__iter__,
             PyMagicMethod::Next => &mut methods.__next__,
             PyMagicMethod::Eq => &mut methods.__eq__,
@@ -85,6 +114,9 @@ impl PyMagicMethod {
             PyMagicMethod::Gt => &mut methods.__gt__,
             PyMagicMethod::Ge => &mut methods.__ge__,
             PyMagicMethod::Contains => &mut methods.__contains__,
+            PyMagicMethod::Len => &mut methods.__len__,
+            PyMagicMethod::GetItem => &mut methods.__getitem__,
+            PyMagicMethod::SetItem => &mut methods.__setitem__,
         }
     }
 
@@ -94,15 +126,22 @@ impl PyMagicMethod {
             PyMagicMethod::Init => "__init__",
             PyMagicMethod::Str => "__str__",
             PyMagicMethod::Repr => "__repr__",
+            PyMagicMethod::Hash => "__hash__",
             PyMagicMethod::Add{right} => if *right {"__radd__"} else {"__add__"},
             PyMagicMethod::Sub{right} => if *right {"__rsub__"} else {"__sub__"},
             PyMagicMethod::Mul{right} => if *right {"__rmul__"} else {"__mul__"},
             PyMagicMethod::TrueDiv{right} => if *right {"__rtruediv__"} else {"__truediv__"},
+            PyMagicMethod::FloorDiv{right} => if *right {"__rfloordiv__"} else {"__floordiv__"},
+            PyMagicMethod::Mod{right} => if *right {"__rmod__"} else {"__mod__"},
             PyMagicMethod::Pow{right} => if *right {"__rpow__"} else {"__pow__"},
+            PyMagicMethod::Abs => "__abs__",
+            PyMagicMethod::Neg => "__neg__",
+            PyMagicMethod::Pos => "__pos__",
             PyMagicMethod::Int => "__int__",
             PyMagicMethod::Bool => "__bool__",
             PyMagicMethod::Bytes => "__bytes__",
             PyMagicMethod::Float => "__float__",
+            PyMagicMethod::Complex => "__complex__",
             PyMagicMethod::Iter => "__iter__",
             PyMagicMethod::Next => "__next__",
             PyMagicMethod::Eq => "__eq__",
@@ -112,6 +151,30 @@ impl PyMagicMethod {
             PyMagicMethod::Gt => "__gt__",
             PyMagicMethod::Ge => "__ge__",
             PyMagicMethod::Contains => "__contains__",
+            PyMagicMethod::Len => "__len__",
+            PyMagicMethod::GetItem => "__getitem__",
+            PyMagicMethod::SetItem => "__setitem__",
+        }
+    }
+
+    pub fn as_operator(&self) -> &str {  // used for error messages
+        match self {
+            PyMagicMethod::Add{..} => "+",
+            PyMagicMethod::Sub{..} => "-",
+            PyMagicMethod::Mul{..} => "*",
+            PyMagicMethod::TrueDiv{..} => "/",
+            PyMagicMethod::FloorDiv{..} => "//",
+            PyMagicMethod::Mod{..} => "%",
+            PyMagicMethod::Pow{..} => "** or pow()",
+            PyMagicMethod::Neg => "-",
+            PyMagicMethod::Pos => "+",
+            PyMagicMethod::Eq => "==",
+            PyMagicMethod::Ne => "!=",
+            PyMagicMethod::Lt => "<",
+            PyMagicMethod::Le => "<=",
+            PyMagicMethod::Gt => ">",
+            PyMagicMethod::Ge => ">=",
+            _ => self.as_str(),
         }
     }
 
@@ -122,20 +185,29 @@ impl PyMagicMethod {
             "__init__" => Some(PyMagicMethod::Init),
             "__str__" => Some(PyMagicMethod::Str),
             "__repr__" => Some(PyMagicMethod::Repr),
+            "__hash__" => Some(PyMagicMethod::Hash),
             "__add__" => Some(PyMagicMethod::Add{right: false}),
             "__sub__" => Some(PyMagicMethod::Sub{right: false}),
             "__mul__" => Some(PyMagicMethod::Mul{right: false}),
             "__truediv__" => Some(PyMagicMethod::TrueDiv{right: false}),
+            "__floordiv__" => Some(PyMagicMethod::FloorDiv{right: false}),
+            "__mod__" => Some(PyMagicMethod::Mod{right: false}),
             "__pow__" => Some(PyMagicMethod::Pow{right: false}),
             "__radd__" => Some(PyMagicMethod::Add{right: true}),
             "__rsub__" => Some(PyMagicMethod::Sub{right: true}),
             "__rmul__" => Some(PyMagicMethod::Mul{right: true}),
             "__rtruediv__" => Some(PyMagicMethod::TrueDiv{right: true}),
+            "__rfloordiv__" => Some(PyMagicMethod::FloorDiv{right: true}),
+            "__rmod__" => Some(PyMagicMethod::Mod{right: true}),
             "__rpow__" => Some(PyMagicMethod::Pow{right: true}),
+            "__abs__" => Some(PyMagicMethod::Abs),
+            "__neg__" => Some(PyMagicMethod::Neg),
+            "__pos__" => Some(PyMagicMethod::Pos),
             "__int__" => Some(PyMagicMethod::Int),
             "__bool__" => Some(PyMagicMethod::Bool),
             "__bytes__" => Some(PyMagicMethod::Bytes),
             "__float__" => Some(PyMagicMethod::Float),
+            "__complex__" => Some(PyMagicMethod::Complex),
             "__iter__" => Some(PyMagicMethod::Iter),
             "__next__" => Some(PyMagicMethod::Next),
             "__eq__" => Some(PyMagicMethod::Eq),
@@ -145,6 +217,9 @@ impl PyMagicMethod {
             "__gt__" => Some(PyMagicMethod::Gt),
             "__ge__" => Some(PyMagicMethod::Ge),
             "__contains__" => Some(PyMagicMethod::Contains),
+            "__len__" => Some(PyMagicMethod::Len),
+            "__getitem__" => Some(PyMagicMethod::GetItem),
+            "__setitem__" => Some(PyMagicMethod::SetItem),
             _ => None,
         }
     }
@@ -155,6 +230,8 @@ impl PyMagicMethod {
             PyMagicMethod::Sub{right} => *right = true,
             PyMagicMethod::Mul{right} => *right = true,
             PyMagicMethod::TrueDiv{right} => *right = true,
+            PyMagicMethod::FloorDiv{right} => *right = true,
+            PyMagicMethod::Mod{right} => *right = true,
             PyMagicMethod::Pow{right} => *right = true,
             _ => {panic!("Cannot make `{}` right handed", self.as_str())},
         }
@@ -171,23 +248,35 @@ pub struct PyMagicMethods {
     // String functions
     pub __str__: Option<Rc<PyInternalFunction>>,
     pub __repr__: Option<Rc<PyInternalFunction>>,
+    
+    pub __hash__: Option<Rc<PyInternalFunction>>,
 
     // Math functions
     pub __add__: Option<Rc<PyInternalFunction>>,
     pub __sub__: Option<Rc<PyInternalFunction>>,
     pub __mul__: Option<Rc<PyInternalFunction>>,
     pub __truediv__: Option<Rc<PyInternalFunction>>,
+    pub __floordiv__: Option<Rc<PyInternalFunction>>,
+    pub __mod__: Option<Rc<PyInternalFunction>>,
     pub __pow__: Option<Rc<PyInternalFunction>>,
     // Right-hand math functions
     pub __radd__: Option<Rc<PyInternalFunction>>,
     pub __rsub__: Option<Rc<PyInternalFunction>>,
     pub __rmul__: Option<Rc<PyInternalFunction>>,
     pub __rtruediv__: Option<Rc<PyInternalFunction>>,
+    pub __rfloordiv__: Option<Rc<PyInternalFunction>>,
+    pub __rmod__: Option<Rc<PyInternalFunction>>,
     pub __rpow__: Option<Rc<PyInternalFunction>>,
+    
+    // Unary math functions
+    pub __abs__: Option<Rc<PyInternalFunction>>,
+    pub __neg__: Option<Rc<PyInternalFunction>>,
+    pub __pos__: Option<Rc<PyInternalFunction>>,
 
     // Type conversion functions
     pub __int__: Option<Rc<PyInternalFunction>>,
     pub __float__: Option<Rc<PyInternalFunction>>,
+    pub __complex__: Option<Rc<PyInternalFunction>>,
     pub __bytes__: Option<Rc<PyInternalFunction>>,
     pub __bool__: Option<Rc<PyInternalFunction>>,
 
@@ -203,6 +292,11 @@ pub struct PyMagicMethods {
     pub __gt__: Option<Rc<PyInternalFunction>>,
     pub __ge__: Option<Rc<PyInternalFunction>>,
     pub __contains__: Option<Rc<PyInternalFunction>>,
+    
+    // Container functions
+    pub __len__: Option<Rc<PyInternalFunction>>,
+    pub __getitem__: Option<Rc<PyInternalFunction>>,
+    pub __setitem__: Option<Rc<PyInternalFunction>>,
 }
 
 pub const fn py_magic_methods_defaults() -> PyMagicMethods {
@@ -211,21 +305,31 @@ pub const fn py_magic_methods_defaults() -> PyMagicMethods {
         __init__: None,
         __str__: None,
         __repr__: None,
+        __hash__: None,
         
         __add__: None,
         __sub__: None,
         __mul__: None,
         __truediv__: None,
+        __floordiv__: None,
+        __mod__: None,
         __pow__: None,
         
         __radd__: None,
         __rsub__: None,
         __rmul__: None,
         __rtruediv__: None,
+        __rfloordiv__: None,
+        __rmod__: None,
         __rpow__: None,
         
+        __abs__: None,
+        __neg__: None,
+        __pos__: None,
+        
         __int__: None,
         __float__: None,
+        __complex__: None,
         __bytes__: None,
         __bool__: None,
         
@@ -239,6 +343,10 @@ pub const fn py_magic_methods_defaults() -> PyMagicMethods {
         __gt__: None,
         __ge__: None,
         __contains__: None,
+        
+        __len__: None,
+        __getitem__: None,
+        __setitem__: None,
     }
 }
 
@@ -1,15 +1,29 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::rc::Rc;
 use ahash::AHashMap;
 use strum::IntoEnumIterator;
 use crate::builtins::structure::magic_methods::{PyMagicMethod, PyMagicMethods};
-use crate::builtins::structure::pyobject::{PyInternalFunction, PyObject};
+use crate::builtins::structure::pyobject::{PyInternalFunction, PyObject, UnaryFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::UnaryFunc;
+use crate::builtins::types::object::unhashable__hash__;
+use crate::pyarena::PyArena;
+
+#[derive(Debug, Clone, Copy)]
+pub struct InstanceLayout {  // where the instances of a class keep their fields
+    pub slot_count: usize,  // `__slots__` of the class and all its bases, indexed by the member descriptors
+    pub has_dict: bool,
+}
 
 #[derive(Debug)]
 pub enum PyClass {
     UserDefined {
         name: String,
         super_classes: Vec<Rc<PyClass>>,
-        attributes: AHashMap<String, PyObject>,
+        attributes: RefCell<AHashMap<String, PyObject>>,  // classes are shared through `Rc`, assigning to one goes through `set_attribute`
+        layout: InstanceLayout,
+        mro: Vec<Rc<PyClass>>,  // the C3 linearization of the bases, without the class itself
+        metaclass: Rc<PyClass>,
     },
     Internal {
         name: String,
@@ -35,9 +49,85 @@ impl PyClass {
         }
     }
 
+    pub fn get_metaclass(&self, arena: &PyArena) -> Rc<PyClass> {  // the class of the class object
+        match self {
+            PyClass::UserDefined { metaclass, .. } => metaclass.clone(),
+            PyClass::Internal { .. } => arena.globals.type_class.clone(),
+        }
+    }
+
+    pub fn get_mro(&self) -> Vec<Rc<PyClass>> {  // every ancestor in lookup order, the class itself isn't included
+        match self {
+            PyClass::UserDefined { mro, .. } => mro.clone(),
+            PyClass::Internal { super_classes, .. } => PyClass::linearize(super_classes).expect("builtin class hierarchies are consistent"),
+        }
+    }
+
+    pub fn linearize(bases: &[Rc<PyClass>]) -> Option<Vec<Rc<PyClass>>> {  // C3, `None` when the bases can't be put in a consistent order
+        let mut sequences: Vec<VecDeque<Rc<PyClass>>> = bases.iter()
+            .map(|base_class| std::iter::once(base_class.clone()).chain(base_class.get_mro()).collect())
+            .collect();
+        sequences.push(bases.iter().cloned().collect());
+
+        let mut mro = Vec::new();
+
+        loop {
+            sequences.retain(|sequence| !sequence.is_empty());
+
+            if sequences.is_empty() {
+                return Some(mro);
+            }
+
+            let in_tail = |candidate: &Rc<PyClass>| sequences.iter().any(|sequence| sequence.iter().skip(1).any(|pyclass| Rc::ptr_eq(pyclass, candidate)));
+            let next = sequences.iter().map(|sequence| &sequence[0]).find(|candidate| !in_tail(candidate))?.clone();  // the first head no other sequence still has to come before
+
+            for sequence in &mut sequences {
+                if Rc::ptr_eq(&sequence[0], &next) {
+                    sequence.pop_front();
+                }
+            }
+
+            mro.push(next);
+        }
+    }
+
+    pub fn get_own_attribute(&self, name: &str) -> Option<PyObject> {  // without the inherited ones
+        match self {
+            PyClass::UserDefined { attributes, .. } => attributes.borrow().get(name).cloned(),
+            PyClass::Internal { attributes, .. } => attributes.get(name).cloned(),
+        }
+    }
+
+    pub fn get_own_magic_method(&self, magic_method: &PyMagicMethod) -> Option<PyObject> {  // builtin classes copy the methods of their bases in, those are skipped here
+        match self {
+            PyClass::UserDefined { attributes, .. } => attributes.borrow().get(magic_method.as_str()).cloned(),
+            PyClass::Internal { magic_methods: methods, super_classes, .. } => {
+                let method = methods.get_method(magic_method)?;
+                let is_inherited = super_classes.iter().any(|base_class| base_class.get_magic_method_internal(magic_method).is_some_and(|inherited| Rc::ptr_eq(&inherited, &method)));
+
+                (!is_inherited).then(|| PyObject::new_internal_func(method))
+            }
+        }
+    }
+
+    pub fn get_layout(&self) -> InstanceLayout {
+        match self {
+            PyClass::UserDefined { layout, .. } => *layout,
+            PyClass::Internal { .. } => InstanceLayout { slot_count: 0, has_dict: false },  // builtins keep their state in the instance's internal
+        }
+    }
+
+    pub fn is_user_defined(&self) -> bool {
+        matches!(self, PyClass::UserDefined {..})
+    }
+
+    pub fn is_subclass_of(&self, other: &Rc<PyClass>) -> bool {
+        std::ptr::eq(self, &**other) || self.get_super_classes().iter().any(|base_class| base_class.is_subclass_of(other))
+    }
+
     pub fn defines_attribute(&self, magic_method: PyMagicMethod) -> bool {
         match self {
-            PyClass::UserDefined { attributes, .. } => attributes.contains_key(&magic_method.to_string()),
+            PyClass::UserDefined { attributes, .. } => attributes.borrow().contains_key(magic_method.as_str()),
             PyClass::Internal {
                 magic_methods: methods, ..
             } => magic_method.get_method(methods).is_some(),
@@ -58,6 +148,11 @@ impl PyClass {
                     continue;
                 }
 
+                if matches!(magic_method_type, PyMagicMethod::Hash) && self.defines_attribute(PyMagicMethod::Eq) {  // overriding `__eq__` without `__hash__` makes a class unhashable, like CPython
+                    methods_to_set.push((magic_method_type, Rc::new(UnaryFunc(&(unhashable__hash__ as UnaryFuncType)))));
+                    continue;
+                }
+
                 if let Some(super_method) = self.search_for_magic_method_internal(&magic_method_type) {
                     methods_to_set.push((magic_method_type, super_method));
                 }
@@ -80,28 +175,16 @@ impl PyClass {
     }
 
     pub fn search_for_magic_method_internal(&self, magic_method: &PyMagicMethod) -> Option<Rc<PyInternalFunction>> {
-        let search_result = match self {
-            PyClass::UserDefined { .. } => {
-                panic!("UserDefined classes will not have internal methods")
-            },
-
-            PyClass::Internal { magic_methods: methods, .. } => {
-                methods.get_method(magic_method)
-            }
-
+        let PyClass::Internal { magic_methods: methods, super_classes, .. } = self else {
+            panic!("UserDefined classes will not have internal methods")
         };
 
-        if search_result.is_none() {
-            for base_class in self.get_super_classes() {
-                let attr = base_class.search_for_magic_method_internal(magic_method);
-
-                if attr.is_some() {
-                    return attr.clone();
-                }
-            }
+        if let Some(method) = methods.get_method(magic_method) {
+            return Some(method);
         }
 
-        search_result
+        let mro = PyClass::linearize(super_classes).expect("builtin class hierarchies are consistent");
+        mro.iter().find_map(|base_class| base_class.get_magic_method_internal(magic_method))  // only runs while the class is created
     }
 
     pub fn get_magic_method_internal(&self, magic_method: &PyMagicMethod) -> Option<Rc<PyInternalFunction>> {
@@ -110,25 +193,73 @@ impl PyClass {
                 panic!("UserDefined classes will not have internal methods")
             },
 
-            PyClass::Internal { magic_methods: methods, ..} => {
-                let attr = methods.get_method(magic_method);
-                attr
-            }
+            PyClass::Internal { magic_methods: methods, ..} => methods.get_method(magic_method),
         }
     }
 
     pub fn search_for_magic_method(&self, magic_method: &PyMagicMethod) -> Option<PyObject> {
         match self {
-            PyClass::UserDefined { attributes, .. } => {
-                attributes.get(&magic_method.to_string()).cloned()
+            PyClass::UserDefined { attributes, mro, .. } => {
+                if let Some(method) = attributes.borrow().get(magic_method.as_str()) {
+                    return Some(method.clone());
+                }
+
+                mro.iter().find_map(|base_class| base_class.get_own_magic_method(magic_method))
             },
 
             PyClass::Internal { magic_methods: methods, .. } => {
-                Some(PyObject::new_internal_func(methods.get_method(&magic_method)?))
+                Some(PyObject::new_internal_func(methods.get_method(magic_method)?))
             }
         }
     }
 
+    pub fn search_for_attribute(&self, name: &str) -> Option<PyObject> {  // runs on every attribute lookup, so the MRO is borrowed rather than cloned
+        match self {
+            PyClass::UserDefined { attributes, mro, .. } => {
+                attributes.borrow().get(name).cloned().or_else(|| mro.iter().find_map(|base_class| base_class.get_own_attribute(name)))
+            }
+            PyClass::Internal { attributes, super_classes, .. } => {  // builtin classes only ever have a single base, so a depth-first walk already is their MRO
+                if let Some(attribute) = attributes.get(name) {
+                    return Some(attribute.clone());
+                }
+
+                super_classes.iter().find_map(|base_class| base_class.search_for_attribute(name))
+            }
+        }
+    }
+
+    pub fn search_for_user_attribute(&self, name: &str) -> Option<PyObject> {  // like `search_for_attribute`, but skips builtin classes
+        let PyClass::UserDefined { attributes, mro, .. } = self else {
+            return None;
+        };
+
+        attributes.borrow().get(name).cloned()
+            .or_else(|| mro.iter().filter(|base_class| base_class.is_user_defined()).find_map(|base_class| base_class.get_own_attribute(name)))
+    }
+
+    pub fn set_attribute(&self, name: String, value: PyObject, arena: &mut PyArena) {  // only user classes can be changed after creation, the new type version tells the inline caches
+        let PyClass::UserDefined { attributes, .. } = self else {
+            panic!("builtin classes are immutable")
+        };
+
+        let replaced = attributes.borrow_mut().insert(name, value);  // dropped after the borrow ends, freeing it may look at this class
+        arena.type_version += 1;
+        drop(replaced);
+    }
+
+    pub fn remove_attribute(&self, name: &str, arena: &mut PyArena) -> Option<PyObject> {
+        let PyClass::UserDefined { attributes, .. } = self else {
+            panic!("builtin classes are immutable")
+        };
+
+        let removed = attributes.borrow_mut().remove(name);
+        if removed.is_some() {
+            arena.type_version += 1;
+        }
+
+        removed
+    }
+
     // pub fn search_for_method(&self, method_name: &str) -> Option<PyObject> {
     //     match self {
     //         PyClass::UserDefined { attributes, .. } => {
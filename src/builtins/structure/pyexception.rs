@@ -1,139 +1,220 @@
-use std::fmt;
-use std::fmt::Display;
+use std::cell::Cell;
 use std::rc::Rc;
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyobject::PyObject;
+use crate::builtins::types::exception::{get_base_exception_class, get_exception_subclass, get_system_exit_class, new_exception, with_exception_mut};
+use crate::builtins::types::str::py_str;
+use crate::builtins::types::traceback::{new_traceback, traceback_entries};
+use crate::pyarena::PyArena;
 
 #[derive(Clone, Debug)]
 pub struct PyException {
-    name: &'static str,
-    message: Option<String>,
-    traceback: Option<Vec<String>>,
-    super_exceptions: Vec<Rc<PyException>>
+    pub value: PyObject,  // always an instance of a `BaseException` subclass
 }
 
 impl PyException {
-    fn new(name: &'static str, super_exception: Vec<Rc<PyException>>) -> Rc<PyException> {
-        Rc::new(PyException {
-            name,
-            message: None,
-            traceback: None,
-            super_exceptions: super_exception
-        })
-    }
-    
-    pub fn instantiate(&self, message: String) -> PyException {
-        let mut new_exception = self.clone();
-        new_exception.message = Some(message);
-        new_exception
-    }
-    
-    pub fn empty(&self) -> PyException {
-        self.clone()
-    }
-
-    pub fn add_trace(&mut self, trace: Box<dyn Display>) {
-        if self.traceback.is_none() {
-            self.traceback = Some(vec![]);
+    pub fn new(value: PyObject) -> PyException {
+        PyException { value }
+    }
+
+    pub fn get_class(&self) -> Rc<PyClass> {
+        self.value.expect_mutable().borrow().get_class().clone()
+    }
+
+    pub fn is_same_type(&self, exception_class: &Rc<PyClass>) -> bool {
+        Rc::ptr_eq(&self.get_class(), exception_class)
+    }
+
+    pub fn is_instance_of(&self, exception_class: &Rc<PyClass>) -> bool {
+        self.get_class().is_subclass_of(exception_class)
+    }
+
+    pub fn set_cause(&self, cause: Option<PyObject>) {  // `raise ... from None` passes `None`, which still suppresses the context
+        with_exception_mut(&self.value, |exception| {
+            exception.cause = cause;
+            exception.suppress_context = true;
+        });
+    }
+
+    pub fn chain_context(&self, handled: &PyException) {  // called when `self` escapes while `handled` is being handled
+        if self.is_same_object(handled) || with_exception_mut(&self.value, |exception| exception.context.is_some()) {
+            return;
         }
-        self.traceback.as_mut().unwrap().push(trace.to_string());
+
+        let mut link = handled.value.clone();  // like CPython, break the chain where it loops back to `self` to avoid a cycle
+
+        while let Some(context) = with_exception_mut(&link, |exception| exception.context.clone()) {
+            if context.get_memory_location() == self.value.get_memory_location() {
+                with_exception_mut(&link, |exception| exception.context = None);
+                break;
+            }
+
+            link = context;
+        }
+
+        with_exception_mut(&self.value, |exception| exception.context = Some(handled.value.clone()));
     }
-    
-    pub fn is_same_type(&self, exception: &PyException) -> bool {
-        self.name == exception.name
+
+    pub fn add_traceback(&self, name: &str, line: usize, arena: &mut PyArena) {  // called for every frame the exception unwinds through, innermost first
+        let next = with_exception_mut(&self.value, |exception| exception.traceback.take());
+        let traceback = new_traceback(name.to_string(), line, next, arena);
+
+        with_exception_mut(&self.value, |exception| exception.traceback = Some(traceback));
+    }
+
+    pub fn has_traceback(&self) -> bool {
+        with_exception_mut(&self.value, |exception| exception.traceback.is_some())
+    }
+
+    pub fn is_not_implemented_signal(&self, arena: &PyArena) -> bool {  // builtins raise a bare `NotImplementedError` when they don't support the other operand, one raised by python code went through a frame and carries a traceback
+        self.is_same_type(&arena.exceptions.not_implemented_error) && !self.has_traceback()
     }
-}
 
-impl Display for PyException {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Traceback (most recent call last):")?;
-        if let Some(traceback) = &self.traceback {
-            for trace in traceback {
-                write!(f, "\n\t{}", trace)?;
+    fn is_same_object(&self, other: &PyException) -> bool {
+        self.value.get_memory_location() == other.value.get_memory_location()
+    }
+
+    pub fn format(&self, arena: &mut PyArena) -> String {  // needs the arena since `__str__` may be arbitrary python
+        let mut seen = vec![];
+        self.format_chain(&mut seen, arena)
+    }
+
+    fn format_chain(&self, seen: &mut Vec<usize>, arena: &mut PyArena) -> String {
+        seen.push(self.value.get_memory_location());
+
+        let (cause, context, suppress_context, traceback) = with_exception_mut(&self.value, |exception| {
+            (exception.cause.clone(), exception.context.clone(), exception.suppress_context, exception.traceback.clone())
+        });
+
+        let chained = match (cause, context) {
+            (Some(cause), _) => Some((cause, "The above exception was the direct cause of the following exception:")),
+            (None, Some(context)) if !suppress_context => Some((context, "During handling of the above exception, another exception occurred:")),
+            _ => None,
+        };
+
+        let mut formatted = String::new();
+
+        if let Some((chained, separator)) = chained {
+            if !seen.contains(&chained.get_memory_location()) {
+                formatted.push_str(&PyException::new(chained).format_chain(seen, arena));
+                formatted.push_str(&format!("\n\n{separator}\n\n"));
+            }
+        }
+
+        if let Some(traceback) = traceback {
+            formatted.push_str("Traceback (most recent call last):\n");
+
+            for (name, line) in traceback_entries(&traceback) {
+                formatted.push_str(&format!("  File \"{}\", line {line}, in {name}\n", arena.filename));
             }
         }
 
-        if let Some(message) = &self.message {
-            write!(f, "{}: {}", self.name, message)?;
+        let name = self.get_class().get_name().clone();
+        let message = match py_str(&self.value, arena) {
+            Ok(message) => message.expect_immutable().expect_string(),
+            Err(_) => "<exception str() failed>".to_string(),
+        };
+
+        if message.is_empty() {
+            formatted.push_str(&name);
         } else {
-            write!(f, "{}", self.name)?;
+            formatted.push_str(&format!("{name}: {message}"));
         }
 
-        Ok(())
+        formatted
+    }
+}
+
+impl PyClass {  // constructors used by the interpreter to raise builtin exceptions
+    pub fn instantiate(self: &Rc<Self>, message: String) -> PyException {
+        PyException::new(new_exception(self.clone(), vec![PyObject::new_string(message)]))
+    }
+
+    pub fn empty(self: &Rc<Self>) -> PyException {
+        PyException::new(new_exception(self.clone(), vec![]))
     }
 }
 
-#[warn(dead_code)]
 #[derive(Debug)]
 pub struct Exceptions {
     // hierarchy based on https://docs.python.org/3/library/exceptions.html#exception-hierarchy
-    pub base_exception: Rc<PyException>,
-
-    pub generator_exit: Rc<PyException>,
-    pub system_exit: Rc<PyException>,
-    pub keyboard_interrupt: Rc<PyException>,
-    pub exception: Rc<PyException>,
-
-    pub arithmatic_error: Rc<PyException>,
-        pub overflow_error: Rc<PyException>,
-        pub zero_division_error: Rc<PyException>,
-    pub assertion_error: Rc<PyException>,
-    pub attribute_error: Rc<PyException>,
-    pub buffer_error: Rc<PyException>,
-    pub eof_error: Rc<PyException>,
-    pub import_error: Rc<PyException>,
-    pub lookup_error: Rc<PyException>,
-        pub index_error: Rc<PyException>,
-        pub key_error: Rc<PyException>,
-    pub memory_error: Rc<PyException>,
-    pub name_error: Rc<PyException>,
-        pub unbound_local_error: Rc<PyException>,
-    pub os_error: Rc<PyException>,
-    pub reference_error: Rc<PyException>,
-    pub runtime_error: Rc<PyException>,
-        pub not_implemented_error: Rc<PyException>,
-        pub recursion_error: Rc<PyException>,
-    pub stop_async_iteration: Rc<PyException>,
-    pub stop_iteration: Rc<PyException>,
-    pub syntax_error: Rc<PyException>,
-    pub system_error: Rc<PyException>,
-    pub type_error: Rc<PyException>,
-    pub value_error: Rc<PyException>,
+    pub base_exception: Rc<PyClass>,
+
+    pub generator_exit: Rc<PyClass>,
+    pub system_exit: Rc<PyClass>,
+    pub keyboard_interrupt: Rc<PyClass>,
+    pub exception: Rc<PyClass>,
+
+    pub arithmatic_error: Rc<PyClass>,
+        pub overflow_error: Rc<PyClass>,
+        pub zero_division_error: Rc<PyClass>,
+    pub assertion_error: Rc<PyClass>,
+    pub attribute_error: Rc<PyClass>,
+    pub buffer_error: Rc<PyClass>,
+    pub eof_error: Rc<PyClass>,
+    pub import_error: Rc<PyClass>,
+        pub module_not_found_error: Rc<PyClass>,
+    pub lookup_error: Rc<PyClass>,
+        pub index_error: Rc<PyClass>,
+        pub key_error: Rc<PyClass>,
+    pub memory_error: Rc<PyClass>,
+    pub name_error: Rc<PyClass>,
+        pub unbound_local_error: Rc<PyClass>,
+    pub os_error: Rc<PyClass>,
+    pub reference_error: Rc<PyClass>,
+    pub runtime_error: Rc<PyClass>,
+        pub not_implemented_error: Rc<PyClass>,
+        pub recursion_error: Rc<PyClass>,
+    pub stop_async_iteration: Rc<PyClass>,
+    pub stop_iteration: Rc<PyClass>,
+    pub syntax_error: Rc<PyClass>,
+    pub system_error: Rc<PyClass>,
+    pub type_error: Rc<PyClass>,
+    pub value_error: Rc<PyClass>,
+        pub unicode_error: Rc<PyClass>,
+            pub unicode_decode_error: Rc<PyClass>,
+            pub unicode_encode_error: Rc<PyClass>,
 }
 
 impl Exceptions {
-    pub fn new() -> Self {
-        let base_exception = PyException::new("BaseException", vec![]);
+    pub fn new(object_class: Rc<PyClass>) -> Self {
+        let base_exception = Rc::new(get_base_exception_class(object_class));
         
-        let generator_exit = PyException::new("GeneratorExit", vec![base_exception.clone()]);
-        let system_exit = PyException::new("SystemExit", vec![base_exception.clone()]);
-        let keyboard_interrupt = PyException::new("KeyboardInterrupt", vec![base_exception.clone()]);
-        let exception = PyException::new("Exception", vec![base_exception.clone()]);
+        let generator_exit = Rc::new(get_exception_subclass("GeneratorExit", base_exception.clone()));
+        let system_exit = Rc::new(get_system_exit_class(base_exception.clone()));
+        let keyboard_interrupt = Rc::new(get_exception_subclass("KeyboardInterrupt", base_exception.clone()));
+        let exception = Rc::new(get_exception_subclass("Exception", base_exception.clone()));
         
-        let arithmatic_error = PyException::new("ArithmaticError", vec![exception.clone()]);
-            let overflow_error = PyException::new("OverflowError", vec![arithmatic_error.clone()]);
-            let zero_division_error = PyException::new("ZeroDivisionError", vec![arithmatic_error.clone()]);
+        let arithmatic_error = Rc::new(get_exception_subclass("ArithmeticError", exception.clone()));
+            let overflow_error = Rc::new(get_exception_subclass("OverflowError", arithmatic_error.clone()));
+            let zero_division_error = Rc::new(get_exception_subclass("ZeroDivisionError", arithmatic_error.clone()));
         
-        let assertion_error = PyException::new("AssertionError", vec![exception.clone()]);
-        let attribute_error = PyException::new("AttributeError", vec![exception.clone()]);
-        let buffer_error = PyException::new("BufferError", vec![exception.clone()]);
-        let eof_error = PyException::new("EOFError", vec![exception.clone()]);
-        let import_error = PyException::new("ImportError", vec![exception.clone()]);
-        let lookup_error = PyException::new("LookupError", vec![exception.clone()]);
-            let index_error = PyException::new("IndexError", vec![lookup_error.clone()]);
-            let key_error = PyException::new("KeyError", vec![lookup_error.clone()]);
-        let memory_error = PyException::new("MemoryError", vec![exception.clone()]);
-        let name_error = PyException::new("NameError", vec![exception.clone()]);
-            let unbound_local_error = PyException::new("UnboundLocalError", vec![name_error.clone()]);
-        let os_error = PyException::new("OSError", vec![exception.clone()]);
-        let reference_error = PyException::new("ReferenceError", vec![exception.clone()]);
-        let runtime_error = PyException::new("RuntimeError", vec![exception.clone()]);
-            let not_implemented_error = PyException::new("NotImplementedError", vec![runtime_error.clone()]);
-            let recursion_error = PyException::new("RecursionError", vec![runtime_error.clone()]);
-        let stop_async_iteration = PyException::new("StopAsyncIteration", vec![exception.clone()]);
-        let stop_iteration = PyException::new("StopIteration", vec![exception.clone()]);
-        let syntax_error = PyException::new("SyntaxError", vec![exception.clone()]);
-        let system_error = PyException::new("SystemError", vec![exception.clone()]);
-        let type_error = PyException::new("TypeError", vec![exception.clone()]);
-        let value_error = PyException::new("ValueError", vec![exception.clone()]);
+        let assertion_error = Rc::new(get_exception_subclass("AssertionError", exception.clone()));
+        let attribute_error = Rc::new(get_exception_subclass("AttributeError", exception.clone()));
+        let buffer_error = Rc::new(get_exception_subclass("BufferError", exception.clone()));
+        let eof_error = Rc::new(get_exception_subclass("EOFError", exception.clone()));
+        let import_error = Rc::new(get_exception_subclass("ImportError", exception.clone()));
+            let module_not_found_error = Rc::new(get_exception_subclass("ModuleNotFoundError", import_error.clone()));
+        let lookup_error = Rc::new(get_exception_subclass("LookupError", exception.clone()));
+            let index_error = Rc::new(get_exception_subclass("IndexError", lookup_error.clone()));
+            let key_error = Rc::new(get_exception_subclass("KeyError", lookup_error.clone()));
+        let memory_error = Rc::new(get_exception_subclass("MemoryError", exception.clone()));
+        let name_error = Rc::new(get_exception_subclass("NameError", exception.clone()));
+            let unbound_local_error = Rc::new(get_exception_subclass("UnboundLocalError", name_error.clone()));
+        let os_error = Rc::new(get_exception_subclass("OSError", exception.clone()));
+        let reference_error = Rc::new(get_exception_subclass("ReferenceError", exception.clone()));
+        let runtime_error = Rc::new(get_exception_subclass("RuntimeError", exception.clone()));
+            let not_implemented_error = Rc::new(get_exception_subclass("NotImplementedError", runtime_error.clone()));
+            let recursion_error = Rc::new(get_exception_subclass("RecursionError", runtime_error.clone()));
+        let stop_async_iteration = Rc::new(get_exception_subclass("StopAsyncIteration", exception.clone()));
+        let stop_iteration = Rc::new(get_exception_subclass("StopIteration", exception.clone()));
+        let syntax_error = Rc::new(get_exception_subclass("SyntaxError", exception.clone()));
+        let system_error = Rc::new(get_exception_subclass("SystemError", exception.clone()));
+        let type_error = Rc::new(get_exception_subclass("TypeError", exception.clone()));
+        let value_error = Rc::new(get_exception_subclass("ValueError", exception.clone()));
+            let unicode_error = Rc::new(get_exception_subclass("UnicodeError", value_error.clone()));
+                let unicode_decode_error = Rc::new(get_exception_subclass("UnicodeDecodeError", unicode_error.clone()));
+                let unicode_encode_error = Rc::new(get_exception_subclass("UnicodeEncodeError", unicode_error.clone()));
         
         Exceptions {
             base_exception,
@@ -149,6 +230,7 @@ impl Exceptions {
             buffer_error,
             eof_error,
             import_error,
+            module_not_found_error,
             lookup_error,
             index_error,
             key_error,
@@ -166,7 +248,48 @@ impl Exceptions {
             system_error,
             type_error,
             value_error,
+            unicode_error,
+            unicode_decode_error,
+            unicode_encode_error,
         }
     }
-}
 
+    pub fn create_exposed_exceptions(&self) -> Vec<(String, Cell<PyObject>)> {
+        [
+            &self.base_exception,
+            &self.generator_exit,
+            &self.system_exit,
+            &self.keyboard_interrupt,
+            &self.exception,
+            &self.arithmatic_error,
+            &self.overflow_error,
+            &self.zero_division_error,
+            &self.assertion_error,
+            &self.attribute_error,
+            &self.buffer_error,
+            &self.eof_error,
+            &self.import_error,
+            &self.module_not_found_error,
+            &self.lookup_error,
+            &self.index_error,
+            &self.key_error,
+            &self.memory_error,
+            &self.name_error,
+            &self.unbound_local_error,
+            &self.os_error,
+            &self.reference_error,
+            &self.runtime_error,
+            &self.not_implemented_error,
+            &self.recursion_error,
+            &self.stop_async_iteration,
+            &self.stop_iteration,
+            &self.syntax_error,
+            &self.system_error,
+            &self.type_error,
+            &self.value_error,
+            &self.unicode_error,
+            &self.unicode_decode_error,
+            &self.unicode_encode_error,
+        ].into_iter().map(|exception_class| (exception_class.get_name().clone(), Cell::new(PyObject::new_internal_class(exception_class.clone())))).collect()
+    }
+}
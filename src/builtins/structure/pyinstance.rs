@@ -2,59 +2,113 @@ use std::fmt::Debug;
 use std::rc::Rc;
 use ahash::AHashMap;
 use crate::builtins::structure::pyclass::PyClass;
-use crate::builtins::structure::pyobject::{EmptyFuncReturnType, FuncReturnType, PyObject};
+use crate::builtins::structure::pyobject::{EmptyFuncReturnType, PyMutableObject, PyObject, PyPointer, PyWeakPointer};
+use crate::gc::{finalizers_defined, schedule_finalizer, schedule_weakref_callbacks};
 use crate::pyarena::PyArena;
 
 #[derive(Debug)]
 pub struct PyInstance {
     pub class: Rc<PyClass>,
     attributes: Option<AHashMap<String, PyObject>>,
-    pub internal: Box<dyn PyInstanceInternal>
+    pub slots: Box<[Option<PyObject>]>,  // backs `__slots__`, `None` until the slot is assigned
+    pub internal: Box<dyn PyInstanceInternal>,
+    pub finalized: bool,  // `__del__` runs at most once, even if the finalizer resurrects the object
+    pub weakrefs: Vec<PyWeakPointer<PyMutableObject>>,  // weak references with a callback, notified once this instance is freed
 }
 
 impl PyInstance {
     pub fn new_empty_attrs(class: Rc<PyClass>, internal: Box<dyn PyInstanceInternal>) -> PyInstance {
+        let layout = class.get_layout();
+
         PyInstance {
+            attributes: layout.has_dict.then(AHashMap::new),  // instances of user-defined classes get a `__dict__` unless `__slots__` leaves it out, builtins don't
+            slots: vec![None; layout.slot_count].into_boxed_slice(),
             class,
-            attributes: None,
-            internal
+            internal,
+            finalized: false,
+            weakrefs: vec![],
         }
     }
-    pub fn new_empty(class: Rc<PyClass>) -> PyInstance{
+    pub fn new_with_attrs(class: Rc<PyClass>, internal: Box<dyn PyInstanceInternal>) -> PyInstance {
         PyInstance {
             class,
-            attributes: None,
-            internal: Box::new(EmptyInternal {})
+            attributes: Some(AHashMap::new()),
+            slots: Box::new([]),
+            internal,
+            finalized: false,
+            weakrefs: vec![],
         }
     }
+    pub fn new_empty(class: Rc<PyClass>) -> PyInstance{
+        Self::new_empty_attrs(class, Box::new(EmptyInternal {}))
+    }
     pub(crate) fn set_field(&mut self, key: String, value: PyObject, pyarena: &mut PyArena) -> EmptyFuncReturnType {  // returns if an variable was overwritten (false means a new variable was set)
+        if let Some(set_result) = self.internal.set_field(key.clone(), value.clone(), pyarena) {  // the internal fields take priority, so they can't be shadowed
+            return set_result;
+        }
+
         if let Some(ref mut attributes) = self.attributes {
             let _result = attributes.insert(key, value);  // TODO this shouldn't be used outside of object methods because it will allow for the setting of new attributes
             return Ok(());
         }
 
-        let set_result = self.internal.set_field(key.clone(), value, pyarena);
-        
-        set_result.unwrap_or_else(|| Err(pyarena.exceptions.attribute_error.instantiate(format!("'{}' object has no attribute `{}`", self.class.get_name(), &key))))
+        Err(pyarena.exceptions.attribute_error.instantiate(format!("'{}' object has no attribute '{}'", self.class.get_name(), key)))
     }
 
-    pub(crate) fn get_field(&self, key: &str, pyarena: &mut PyArena) -> FuncReturnType {
-        let mut attribute = self.internal.get_field(key, pyarena);
+    pub(crate) fn get_field(&self, key: &str, pyarena: &mut PyArena) -> Option<PyObject> {  // only the instance's own fields, class attributes are looked up by the caller
+        let attribute = self.internal.get_field(key, pyarena);
 
         if attribute.is_none() {
             if let Some(ref attributes) = self.attributes {
-                attribute = attributes.get(key).cloned();
+                return attributes.get(key).cloned();
             }
         }
 
-        attribute.ok_or_else(|| pyarena.exceptions.attribute_error.instantiate(format!("'{}' object has no attribute `{key}`", self.class.get_name())))
+        attribute
+    }
+
+    pub(crate) fn remove_field(&mut self, key: &str) -> Option<PyObject> {  // the internal fields can't be removed
+        self.attributes.as_mut()?.remove(key)
     }
 
     pub fn get_class(&self) -> &Rc<PyClass> {
         &self.class
     }
+
+    pub fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        self.attributes.iter().flat_map(|attributes| attributes.values()).for_each(&mut *visit);
+        self.slots.iter().flatten().for_each(&mut *visit);
+        self.internal.traverse(visit);
+    }
+
+    pub fn clear(&mut self) -> Vec<PyObject> {
+        let mut released: Vec<_> = self.attributes.as_mut().map(|attributes| attributes.drain().map(|(_key, value)| value).collect()).unwrap_or_default();
+        released.extend(self.slots.iter_mut().filter_map(Option::take));
+        released.extend(self.internal.clear());
+        released
+    }
 }
 
+impl Drop for PyInstance {
+    fn drop(&mut self) {
+        if !self.finalized && finalizers_defined() && self.class.search_for_attribute("__del__").is_some() {  // the contents move to a new instance that lives until `__del__` has run
+            let instance = PyInstance {
+                class: self.class.clone(),
+                attributes: self.attributes.take(),
+                slots: std::mem::take(&mut self.slots),
+                internal: std::mem::replace(&mut self.internal, Box::new(EmptyInternal {})),
+                finalized: true,
+                weakrefs: vec![],
+            };
+
+            schedule_finalizer(PyPointer::new(PyMutableObject::Instance(instance)));
+        }
+
+        if !self.weakrefs.is_empty() {
+            schedule_weakref_callbacks(std::mem::take(&mut self.weakrefs));
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct EmptyInternal {}
@@ -62,9 +116,18 @@ pub struct EmptyInternal {}
 pub trait PyInstanceInternal: mopa::Any + Debug {
     fn set_field(&mut self, key: String, value: PyObject, pyarena: &mut PyArena) -> Option<EmptyFuncReturnType>;  // return field name is successful else exception
     fn get_field(&self, key: &str, pyarena: &mut PyArena) -> Option<PyObject>;
+
+    fn traverse(&self, _visit: &mut dyn FnMut(&PyObject)) {}  // reports every object the internal state holds, so the cycle collector can see the references
+    fn clear(&mut self) -> Vec<PyObject> {  // hands those objects over so the collector can break a cycle, only called on garbage
+        vec![]
+    }
 }
 
-mopafy!(PyInstanceInternal);
+#[allow(clippy::transmute_ptr_to_ref)]  // the downcasts mopa generates
+mod downcast {
+    use super::PyInstanceInternal;
+    mopafy!(PyInstanceInternal);
+}
 
 impl PyInstanceInternal for EmptyInternal {
     fn set_field(&mut self, _key: String, _value: PyObject, _pyarena: &mut PyArena) -> Option<EmptyFuncReturnType> {
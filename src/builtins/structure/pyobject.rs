@@ -1,16 +1,23 @@
-use std::cell::{Ref, RefCell, RefMut};
+use std::borrow::Cow;
+use std::cell::{BorrowError, Ref, RefCell, RefMut};
 use std::fmt::Debug;
-use std::rc::Rc;
-use crate::parser::CodeBlock;
+use std::rc::{Rc, Weak};
+use crate::compiler::CodeObject;
+use crate::gc::track;
 use crate::pyarena::PyArena;
 use crate::builtins::structure::magic_methods::{PyMagicMethod};
 use crate::builtins::structure::pyclass::PyClass;
 use crate::builtins::structure::pyexception::PyException;
 use crate::builtins::structure::pyinstance::PyInstance;
+use crate::builtins::functions::attributes::get_attribute;
 
 #[derive(Clone, Debug)]
 pub enum PyObject {
-    Immutable(Rc<PyImmutableObject>),  // TODO, consider using Cow's here
+    None,  // small values are stored inline so they never touch the allocator
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Immutable(Rc<PyImmutableObject>),
     Mutable(PyPointer<PyMutableObject>),
     Internal(PyInternalObject),
     IteratorFlag(PyIteratorFlag)
@@ -22,10 +29,22 @@ impl PyObject {
     }
     
     pub fn new_int(value: i64) -> Self {
-        Self::new_immutable(PyImmutableObject::Int(value))
+        PyObject::Int(value)
     }
     pub fn new_float(value: f64) -> Self {
-        Self::new_immutable(PyImmutableObject::Float(value))
+        if value.is_nan() {  // every NaN is its own object, so `nan in [float("nan")]` stays false
+            return PyObject::Immutable(Rc::new(PyImmutableObject::Float(value)));
+        }
+        PyObject::Float(value)
+    }
+    pub fn new_complex(real: f64, imag: f64) -> Self {
+        Self::new_immutable(PyImmutableObject::Complex {real, imag})
+    }
+    pub fn new_bytes(value: Vec<u8>) -> Self {
+        Self::new_immutable(PyImmutableObject::Bytes(value))
+    }
+    pub fn new_slice(start: Option<i64>, stop: Option<i64>, step: Option<i64>) -> Self {
+        Self::new_immutable(PyImmutableObject::Slice {start, stop, step})
     }
     pub fn new_internal_class(value: Rc<PyClass>) -> Self {
         Self::new_internal(PyInternalObject::InternalClass(value))
@@ -36,11 +55,23 @@ impl PyObject {
     }
     
     pub fn new_mutable(value: PyMutableObject) -> Self {
-        PyObject::Mutable(PyPointer::new(value))
+        let pointer = PyPointer::new(value);
+
+        if matches!(*pointer.borrow(), PyMutableObject::Instance(_)) {  // functions can't hold other objects, so they never form a cycle
+            track(&pointer);
+        }
+
+        PyObject::Mutable(pointer)
     }
     
     pub fn new_immutable(value: PyImmutableObject) -> Self {
-        PyObject::Immutable(Rc::new(value))
+        match value {
+            PyImmutableObject::None => PyObject::None,
+            PyImmutableObject::Bool(value) => PyObject::Bool(value),
+            PyImmutableObject::Int(value) => PyObject::Int(value),
+            PyImmutableObject::Float(value) => PyObject::new_float(value),
+            value => PyObject::Immutable(Rc::new(value)),
+        }
     }
     
     pub fn new_internal(value: PyInternalObject) -> Self {
@@ -48,16 +79,8 @@ impl PyObject {
     }
     
     
-    pub fn break_() -> Self {  // TODO prob should be moved out of the pyobject class (currently in here for legacy reasons)
-        PyObject::IteratorFlag(PyIteratorFlag::Break)
-    }
-    
-    pub fn continue_() -> Self {  // TODO prob should be moved out of the pyobject class (currently in here for legacy reasons)
-        PyObject::IteratorFlag(PyIteratorFlag::Continue)
-    }
-    
     pub fn create_new_none() -> Self {
-        PyObject::Immutable(Rc::new(PyImmutableObject::None))
+        PyObject::None
     }
     
     pub fn create_new_not_implemented() -> Self {
@@ -65,17 +88,32 @@ impl PyObject {
     }
     
     pub fn create_new_bool(value: bool) -> Self {
-        Self::new_immutable(PyImmutableObject::Bool(value))
+        PyObject::Bool(value)
     }
     
     pub fn stop_iteration() -> Self {  // TODO prob should be moved out of the pyobject class (currently in here for legacy reasons)
         PyObject::IteratorFlag(PyIteratorFlag::StopIteration)
     }
     
-    pub fn expect_immutable(&self) -> &Rc<PyImmutableObject> {
+    pub fn is_none(&self) -> bool {
+        matches!(self, PyObject::None)
+    }
+
+    pub fn as_immutable(&self) -> Option<Cow<'_, PyImmutableObject>> {  // inline values are widened into an owned view
         match self {
-            PyObject::Immutable(inner) => inner,
-            _ => panic!("Expected immutable object"), // TODO make python error
+            PyObject::None => Some(Cow::Owned(PyImmutableObject::None)),
+            PyObject::Bool(value) => Some(Cow::Owned(PyImmutableObject::Bool(*value))),
+            PyObject::Int(value) => Some(Cow::Owned(PyImmutableObject::Int(*value))),
+            PyObject::Float(value) => Some(Cow::Owned(PyImmutableObject::Float(*value))),
+            PyObject::Immutable(inner) => Some(Cow::Borrowed(&**inner)),
+            _ => None,
+        }
+    }
+
+    pub fn expect_immutable(&self) -> Cow<'_, PyImmutableObject> {
+        match self.as_immutable() {
+            Some(inner) => inner,
+            None => panic!("Expected immutable object"), // TODO make python error
         }
     }
     
@@ -95,41 +133,73 @@ impl PyObject {
     
     pub fn get_magic_method(&self, py_magic_method: &PyMagicMethod, arena: &mut PyArena) -> Option<PyObject> {
         match self {
-            PyObject::Immutable(inner) => inner.get_magic_method(py_magic_method, arena),
             PyObject::Mutable(inner) => inner.borrow().get_magic_method(py_magic_method, arena),
             PyObject::IteratorFlag(_) => {panic!("IteratorFlag has no magic methods")}
-            PyObject::Internal(_) => {todo!()}
+            PyObject::Internal(_) => self.clone_class(arena).search_for_magic_method(py_magic_method),
+            immutable => immutable.expect_immutable().get_magic_method(py_magic_method, arena),
+        }
+    }
+    
+    pub fn get_attribute(&self, name: &str, arena: &mut PyArena) -> FuncReturnType {  // goes through `__getattribute__` and `__getattr__` when a user class defines them
+        get_attribute(self, name, arena)
+    }
+    
+    pub fn get_method(&self, name: &str, arena: &mut PyArena) -> Option<PyObject> {  // looks up a method on the object's class, the caller passes `self`
+        match self {
+            PyObject::Mutable(inner) => inner.borrow().get_class().search_for_attribute(name),
+            PyObject::Internal(_) => None,
+            PyObject::IteratorFlag(_) => {panic!("IteratorFlag has no methods")}
+            immutable => immutable.expect_immutable().get_class(arena).search_for_attribute(name),
         }
     }
     
     pub fn clone_class(&self, arena: &mut PyArena) -> Rc<PyClass> {
         match *self {
-            PyObject::Immutable(ref inner) => inner.get_class(arena).clone(),
             PyObject::Mutable(ref inner) => inner.borrow().get_class().clone(),
             PyObject::IteratorFlag(_) => {panic!("IteratorFlag has no class")}
-            PyObject::Internal(_) => {todo!()}
+            PyObject::Internal(PyInternalObject::InternalClass(ref pyclass)) => pyclass.get_metaclass(arena),
+            PyObject::Internal(PyInternalObject::InternalFunction(_)) => arena.globals.builtin_function_class.clone(),
+            ref immutable => immutable.expect_immutable().get_class(arena).clone(),
         }
     }
 
-    pub fn get_memory_location(&self) -> usize {
+    pub fn get_memory_location(&self) -> usize {  // inline values get odd tagged ids, heap addresses are always aligned
         match self {
+            PyObject::None => 0x1,
+            PyObject::Bool(value) => 0x3 | (*value as usize) << 3,
+            PyObject::Int(value) => 0x5 | (*value as usize) << 3,
+            PyObject::Float(value) => 0x7 | (value.to_bits() as usize) << 3,
             PyObject::Immutable(immutable) => &*immutable.clone() as *const PyImmutableObject as usize,
             PyObject::Mutable(mutable) => &*mutable.clone().borrow() as *const PyMutableObject as usize,
             PyObject::Internal(internal) => internal.get_memory_location(),
             PyObject::IteratorFlag(flag) => flag as *const PyIteratorFlag as usize
         }
     }
+
+    pub fn is_same_object(&self, other: &PyObject) -> bool {  // tagged ids can collide once shifted, so inline values compare by type and value
+        match (self, other) {
+            (PyObject::None, PyObject::None) => true,
+            (PyObject::Bool(left), PyObject::Bool(right)) => left == right,
+            (PyObject::Int(left), PyObject::Int(right)) => left == right,
+            (PyObject::Float(left), PyObject::Float(right)) => left.to_bits() == right.to_bits(),
+            (PyObject::None | PyObject::Bool(_) | PyObject::Int(_) | PyObject::Float(_), _) | (_, PyObject::None | PyObject::Bool(_) | PyObject::Int(_) | PyObject::Float(_)) => false,
+            _ => self.get_memory_location() == other.get_memory_location(),
+        }
+    }
 }
 
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum PyImmutableObject {
     None,
     NotImplemented,
     Int(i64),
     Float(f64),
+    Complex {real: f64, imag: f64},
     Bool(bool),
     Str(String),  // TODO, maybe use immutable string type here
+    Bytes(Vec<u8>),
+    Slice {start: Option<i64>, stop: Option<i64>, step: Option<i64>},  // only integer bounds are supported
 }
 
 impl PyImmutableObject {
@@ -138,9 +208,12 @@ impl PyImmutableObject {
             PyImmutableObject::None => {&arena.globals.none_class}
             PyImmutableObject::Int(_) => {&arena.globals.int_class}
             PyImmutableObject::Float(_) => {&arena.globals.float_class}
+            PyImmutableObject::Complex {..} => {&arena.globals.complex_class}
             PyImmutableObject::Bool(_) => {&arena.globals.bool_class}
-            PyImmutableObject::Str(_) => {todo!()}
-            PyImmutableObject::NotImplemented => {todo!()}
+            PyImmutableObject::Str(_) => {&arena.globals.str_class}
+            PyImmutableObject::Bytes(_) => {&arena.globals.bytes_class}
+            PyImmutableObject::Slice {..} => {&arena.globals.slice_class}
+            PyImmutableObject::NotImplemented => {&arena.globals.not_implemented_class}
         }
     }
     
@@ -154,6 +227,24 @@ impl PyImmutableObject {
     pub fn get_magic_method(&self, py_magic_method: &PyMagicMethod, arena: &mut PyArena) -> Option<PyObject> {
         self.get_class(arena).search_for_magic_method(py_magic_method)
     }
+    
+    pub fn get_field(&self, key: &str, arena: &mut PyArena) -> Option<PyObject> {  // read-only data attributes of the builtin immutable types
+        let slice_bound = |bound: &Option<i64>, arena: &mut PyArena| bound.map(PyObject::new_int).unwrap_or_else(|| arena.statics.none().clone());
+        
+        match (self, key) {
+            (PyImmutableObject::Complex {real, ..}, "real") => Some(PyObject::new_float(*real)),
+            (PyImmutableObject::Complex {imag, ..}, "imag") => Some(PyObject::new_float(*imag)),
+            (PyImmutableObject::Float(value), "real") => Some(PyObject::new_float(*value)),
+            (PyImmutableObject::Float(_), "imag") => Some(PyObject::new_float(0.0)),
+            (PyImmutableObject::Int(value), "real") => Some(PyObject::new_int(*value)),
+            (PyImmutableObject::Bool(value), "real") => Some(PyObject::new_int(*value as i64)),
+            (PyImmutableObject::Int(_) | PyImmutableObject::Bool(_), "imag") => Some(PyObject::new_int(0)),
+            (PyImmutableObject::Slice {start, ..}, "start") => Some(slice_bound(start, arena)),
+            (PyImmutableObject::Slice {stop, ..}, "stop") => Some(slice_bound(stop, arena)),
+            (PyImmutableObject::Slice {step, ..}, "step") => Some(slice_bound(step, arena)),
+            _ => None
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -163,20 +254,6 @@ pub enum PyInternalObject {
 }
 
 impl PyInternalObject {
-    pub fn expect_internal_slot(&self) -> Rc<PyInternalFunction> {
-        match self {
-            PyInternalObject::InternalFunction(slot) => slot.clone(),
-            _ => panic!("Expected internal slot"), // TODO make python error
-        }
-    }
-    
-    pub fn expect_internal_function(&self) -> Rc<PyInternalFunction> {
-        match self {
-            PyInternalObject::InternalFunction(slot) => slot.clone(),
-            _ => panic!("Expected internal slot"), // TODO make python error
-        }
-    }
-
     pub fn get_memory_location(&self) -> usize {
         match self {
             PyInternalObject::InternalFunction(func) => &**func as *const PyInternalFunction as usize,
@@ -199,15 +276,18 @@ impl PyMutableObject {
         match self {
             PyMutableObject::Instance(py_instance) => py_instance.get_class(),
             // PyMutableObject::Class(py_class) => py_class,
-            PyMutableObject::Function(_py_function) => todo!(),
+            PyMutableObject::Function(py_function) => &py_function.class,
         }
     }
 
-    pub fn get_field(&self, name: &str, arena: &mut PyArena) -> FuncReturnType {
+    pub fn get_field(&self, name: &str, arena: &mut PyArena) -> Option<PyObject> {
         match self {
             PyMutableObject::Instance(instance) => instance.get_field(name, arena),
             // PyMutableObject::Class(py_class) => todo!(),
-            PyMutableObject::Function(_py_function) => todo!(),
+            PyMutableObject::Function(py_function) => match name {
+                "__name__" => Some(PyObject::new_string(py_function.code.name.clone())),
+                _ => None,
+            },
         }
     }
     
@@ -226,22 +306,37 @@ impl PyMutableObject {
     }
 
 
+    pub fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        if let PyMutableObject::Instance(instance) = self {
+            instance.traverse(visit);
+        }
+    }
+
+    pub fn clear(&mut self) -> Vec<PyObject> {
+        match self {
+            PyMutableObject::Instance(instance) => instance.clear(),
+            PyMutableObject::Function(_) => vec![],
+        }
+    }
+
     pub fn get_magic_method(&self, py_magic_method: &PyMagicMethod, _arena: &mut PyArena) -> Option<PyObject> {
         match self {
             // PyMutableObject::Class(_) => {todo!()}
             PyMutableObject::Instance(instance) => { instance.get_class().search_for_magic_method(py_magic_method) }
-            PyMutableObject::Function(_) => {todo!()}
+            PyMutableObject::Function(function) => function.class.search_for_magic_method(py_magic_method),
         }
     }
 }
 
 #[derive(Debug)]
 pub struct PyFunction {
-    name: String,
-    args: Vec<String>,
-    body: Vec<CodeBlock>,
+    pub class: Rc<PyClass>,
+    pub code: Rc<CodeObject>,
+    pub class_cell: Option<ClassCell>,  // the class the function was defined in, only kept when it uses zero-argument `super()`
 }
 
+pub type ClassCell = Rc<RefCell<Weak<PyClass>>>;  // filled once the class body has run, weak so the class and its methods don't keep each other alive
+
 pub type FuncReturnType = Result<PyObject, PyException>;
 pub type EmptyFuncReturnType = Result<(), PyException>;
 
@@ -253,6 +348,7 @@ pub type VariadicFuncType = fn(&mut PyArena, &PyObject, &[PyObject]) -> FuncRetu
 pub type ManyArgFuncType = fn(&mut PyArena, &[PyObject]) -> FuncReturnType;
 
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum PyInternalFunction {
     NewFunc(&'static NewFuncType),
     InitFunc(&'static InitFuncType),
@@ -284,18 +380,57 @@ impl<T> PyPointer<T> {
         }
     }
 
-    pub fn borrow(&self) -> Ref<T> {
+    pub fn borrow(&self) -> Ref<'_, T> {
         self.inner.borrow()
     }
 
-    pub fn borrow_mut(&self) -> RefMut<T> {
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
         self.inner.borrow_mut()
     }
+
+    pub fn try_borrow(&self) -> Result<Ref<'_, T>, BorrowError> {
+        self.inner.try_borrow()
+    }
+
+    pub fn downgrade(&self) -> PyWeakPointer<T> {
+        PyWeakPointer {
+            inner: Rc::downgrade(&self.inner)
+        }
+    }
+
+    pub fn strong_count(&self) -> usize {
+        Rc::strong_count(&self.inner)
+    }
+
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.inner) as *const () as usize
+    }
+}
+
+#[derive(Debug)]
+pub struct PyWeakPointer<T> {  // doesn't keep the object alive
+    inner: Weak<RefCell<T>>
+}
+
+impl<T> Clone for PyWeakPointer<T> {
+    fn clone(&self) -> Self {
+        PyWeakPointer {
+            inner: self.inner.clone()
+        }
+    }
+}
+
+impl<T> PyWeakPointer<T> {
+    pub fn upgrade(&self) -> Option<PyPointer<T>> {
+        self.inner.upgrade().map(|inner| PyPointer { inner })
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.inner.strong_count() > 0
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum PyIteratorFlag {
-    Break,
-    Continue,
     StopIteration
 }
\ No newline at end of file
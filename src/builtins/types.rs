@@ -1,7 +1,24 @@
 pub mod pybool;
 pub mod pyfloat;
+pub mod pycomplex;
 pub mod pyint;
 pub mod range;
 pub mod str;
+pub mod pybytes;
+pub mod bytearray;
+pub mod list;
+pub mod tuple;
+pub mod dict;
+pub mod slice;
 pub mod pynone;
-pub mod object;
\ No newline at end of file
+pub mod object;
+pub mod exception;pub mod module;
+pub mod function;
+pub mod weakref;
+pub mod method;
+pub mod property;
+pub mod classmethod;
+pub mod member;
+pub mod pysuper;
+pub mod pytype;
+pub mod traceback;
@@ -0,0 +1,176 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::collect_iterable;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{BivariateFuncType, EmptyFuncReturnType, FuncReturnType, ManyArgFuncType, NewFuncType, PyImmutableObject, PyMutableObject, PyObject, UnaryFuncType, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, ManyArgFunc, NewFunc, UnaryFunc, VariadicFunc};
+use crate::builtins::types::pybytes::{bytes__add__, bytes__contains__, bytes__eq__, bytes__ge__, bytes__getitem__, bytes__gt__, bytes__iter__, bytes__le__, bytes__len__, bytes__lt__, bytes__mul__, bytes__ne__, bytes_from_args, bytes_methods, expect_bytes_like, format_bytes_literal, parse_fromhex, try_bytes_like};
+use crate::builtins::types::pyint::expect_int;
+use crate::builtins::types::slice::{adjust_slice_indices, normalize_index};
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+pub struct ByteArrayInstance {
+    pub value: Vec<u8>,
+}
+
+impl PyInstanceInternal for ByteArrayInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, _key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        None
+    }
+}
+
+pub fn new_bytearray(value: Vec<u8>, arena: &mut PyArena) -> PyObject {
+    PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.bytearray_class.clone(),
+        Box::new(ByteArrayInstance { value })
+    )))
+}
+
+fn with_bytearray_mut<T>(pyself: &PyObject, func: impl FnOnce(&mut Vec<u8>) -> T) -> T {
+    let mut pyself = pyself.expect_mutable().borrow_mut();
+    let instance = pyself.expect_instance_mut();
+
+    if let Some(bytearray_internal) = instance.internal.downcast_mut::<ByteArrayInstance>() {
+        func(&mut bytearray_internal.value)
+    } else {
+        panic!("instance is not ByteArrayInstance, its {:?}", instance)
+    }
+}
+
+fn expect_byte_value(pyobj: &PyObject, arena: &mut PyArena) -> Result<u8, PyException> {
+    let value = expect_int(pyobj, arena)?;
+
+    u8::try_from(value).map_err(|_error| arena.exceptions.value_error.instantiate("byte must be in range(0, 256)".to_string()))
+}
+
+pub fn bytearray__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    let value = bytes_from_args(pyargs, arena)?;
+    Ok(new_bytearray(value, arena))
+}
+
+pub fn bytearray__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+    Ok(PyObject::new_string(format!("bytearray({})", format_bytes_literal(&value))))
+}
+
+pub fn bytearray__setitem__(arena: &mut PyArena, pyself: &PyObject, args: &[PyObject]) -> FuncReturnType {
+    let [ref key, ref value] = args[..] else {
+        panic!("__setitem__ expects a key and a value, got {:?}", args)
+    };
+
+    let length = expect_bytes_like(pyself, arena)?.len();
+
+    match *key.expect_immutable() {
+        PyImmutableObject::Int(index) => {
+            let index = normalize_index(index, length).ok_or_else(|| arena.exceptions.index_error.instantiate("bytearray index out of range".to_string()))?;
+            let byte = expect_byte_value(value, arena)?;
+
+            with_bytearray_mut(pyself, |bytearray| bytearray[index] = byte);
+        }
+        PyImmutableObject::Slice {start, stop, step} => {
+            let replacement = match try_bytes_like(value) {
+                Some(replacement) => replacement,
+                None => {
+                    let items = collect_iterable(value, arena)?;
+                    items.iter().map(|item| expect_byte_value(item, arena)).collect::<Result<Vec<_>, _>>()?
+                }
+            };
+
+            let (start, stop, step, slice_length) = adjust_slice_indices(start, stop, step, length, arena)?;
+
+            if step == 1 {
+                let stop = stop.max(start);
+                with_bytearray_mut(pyself, |bytearray| { bytearray.splice(start as usize..stop as usize, replacement); });
+            } else if replacement.len() == slice_length {
+                with_bytearray_mut(pyself, |bytearray| {
+                    for (i, byte) in replacement.into_iter().enumerate() {
+                        bytearray[(start + i as i64 * step) as usize] = byte;
+                    }
+                });
+            } else {
+                let message = format!("attempt to assign bytes of size {} to extended slice of size {slice_length}", replacement.len());
+                return Err(arena.exceptions.value_error.instantiate(message));
+            }
+        }
+        ref other => {
+            let message = format!("bytearray indices must be integers or slices, not {}", other.get_class(arena).get_name());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    }
+
+    Ok(arena.statics.none().clone())
+}
+
+pub fn bytearray_append(arena: &mut PyArena, pyself: &PyObject, item: &PyObject) -> FuncReturnType {
+    let byte = expect_byte_value(item, arena)?;
+
+    with_bytearray_mut(pyself, |bytearray| bytearray.push(byte));
+
+    Ok(arena.statics.none().clone())
+}
+
+pub fn bytearray_extend(arena: &mut PyArena, pyself: &PyObject, iterable: &PyObject) -> FuncReturnType {
+    let items = match try_bytes_like(iterable) {
+        Some(items) => items,
+        None => {
+            let items = collect_iterable(iterable, arena)?;
+            items.iter().map(|item| expect_byte_value(item, arena)).collect::<Result<Vec<_>, _>>()?
+        }
+    };
+
+    with_bytearray_mut(pyself, |bytearray| bytearray.extend(items));
+
+    Ok(arena.statics.none().clone())
+}
+
+pub fn bytearray_fromhex(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    let value = parse_fromhex(arena, args)?;
+    Ok(new_bytearray(value, arena))
+}
+
+pub fn get_bytearray_class(object_class: Rc<PyClass>) -> PyClass {
+    let mut attributes: AHashMap<String, PyObject> = bytes_methods().into_iter().collect();
+    attributes.insert("fromhex".to_string(), PyObject::new_internal_func(Rc::new(ManyArgFunc(&(bytearray_fromhex as ManyArgFuncType)))));
+    attributes.insert("append".to_string(), PyObject::new_internal_func(Rc::new(BivariateFunc(&(bytearray_append as BivariateFuncType)))));
+    attributes.insert("extend".to_string(), PyObject::new_internal_func(Rc::new(BivariateFunc(&(bytearray_extend as BivariateFuncType)))));
+
+    PyClass::Internal {
+        name: "bytearray".to_string(),
+        super_classes: vec![object_class],
+        attributes,
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(bytearray__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(bytearray__repr__ as UnaryFuncType)))),
+
+            __add__: Some(Rc::new(BivariateFunc(&(bytes__add__ as BivariateFuncType)))),
+            __mul__: Some(Rc::new(BivariateFunc(&(bytes__mul__ as BivariateFuncType)))),
+            __rmul__: Some(Rc::new(BivariateFunc(&(bytes__mul__ as BivariateFuncType)))),
+
+            __iter__: Some(Rc::new(UnaryFunc(&(bytes__iter__ as UnaryFuncType)))),
+
+            __eq__: Some(Rc::new(BivariateFunc(&(bytes__eq__ as BivariateFuncType)))),
+            __ne__: Some(Rc::new(BivariateFunc(&(bytes__ne__ as BivariateFuncType)))),
+            __lt__: Some(Rc::new(BivariateFunc(&(bytes__lt__ as BivariateFuncType)))),
+            __le__: Some(Rc::new(BivariateFunc(&(bytes__le__ as BivariateFuncType)))),
+            __gt__: Some(Rc::new(BivariateFunc(&(bytes__gt__ as BivariateFuncType)))),
+            __ge__: Some(Rc::new(BivariateFunc(&(bytes__ge__ as BivariateFuncType)))),
+            __contains__: Some(Rc::new(BivariateFunc(&(bytes__contains__ as BivariateFuncType)))),
+
+            __len__: Some(Rc::new(UnaryFunc(&(bytes__len__ as UnaryFuncType)))),
+            __getitem__: Some(Rc::new(BivariateFunc(&(bytes__getitem__ as BivariateFuncType)))),
+            __setitem__: Some(Rc::new(VariadicFunc(&(bytearray__setitem__ as VariadicFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
@@ -0,0 +1,93 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{EmptyFuncReturnType, FuncReturnType, NewFuncType, PyMutableObject, PyObject, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{NewFunc, VariadicFunc};
+use crate::builtins::types::method::new_method;
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+pub struct FunctionWrapperInstance {  // backs both `classmethod` and `staticmethod`
+    function: PyObject,
+}
+
+impl PyInstanceInternal for FunctionWrapperInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        match key {
+            "__func__" => Some(self.function.clone()),
+            _ => None,
+        }
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        visit(&self.function);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        vec![std::mem::replace(&mut self.function, PyObject::None)]
+    }
+}
+
+fn expect_wrapped_function(pyself: &PyObject) -> PyObject {
+    let pyself = pyself.expect_mutable().borrow();
+    pyself.expect_instance().internal.downcast_ref::<FunctionWrapperInstance>().expect("instance wraps a function").function.clone()
+}
+
+pub fn function_wrapper__new__(arena: &mut PyArena, pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    let [function] = pyargs else {
+        let message = format!("{} expected 1 argument, got {}", pyclass.get_name(), pyargs.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    Ok(PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        pyclass,
+        Box::new(FunctionWrapperInstance { function: function.clone() })
+    ))))
+}
+
+pub fn classmethod__get__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {  // binds the class instead of the instance
+    let [instance, owner] = pyargs else {
+        panic!("__get__ takes an instance and its class")
+    };
+
+    let owner = match owner {
+        PyObject::None => PyObject::new_internal_class(instance.clone_class(arena)),
+        owner => owner.clone(),
+    };
+
+    Ok(new_method(expect_wrapped_function(pyself), owner, arena))
+}
+
+pub fn staticmethod__get__(_arena: &mut PyArena, pyself: &PyObject, _pyargs: &[PyObject]) -> FuncReturnType {  // the function is returned as is, nothing gets bound
+    Ok(expect_wrapped_function(pyself))
+}
+
+fn get_function_wrapper_class(name: &str, get_func: &'static VariadicFuncType, object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: name.to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("__get__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(get_func)))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(function_wrapper__new__ as NewFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
+
+pub fn get_classmethod_class(object_class: Rc<PyClass>) -> PyClass {
+    get_function_wrapper_class("classmethod", &(classmethod__get__ as VariadicFuncType), object_class)
+}
+
+pub fn get_staticmethod_class(object_class: Rc<PyClass>) -> PyClass {
+    get_function_wrapper_class("staticmethod", &(staticmethod__get__ as VariadicFuncType), object_class)
+}
@@ -0,0 +1,317 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::collect_iterable;
+use crate::builtins::functions::compare::eq_with_identity;
+use crate::builtins::functions::hash::hash_value;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{BivariateFuncType, EmptyFuncReturnType, FuncReturnType, NewFuncType, PyMutableObject, PyObject, UnaryFuncType, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, UnaryFunc, VariadicFunc};
+use crate::builtins::types::list::{list__iter__, new_list};
+use crate::builtins::types::object::unhashable__hash__;
+use crate::builtins::types::str::py_repr;
+use crate::builtins::types::tuple::{new_tuple, try_tuple_items};
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+struct DictEntry {
+    hash: i64,
+    key: PyObject,
+    value: PyObject,
+}
+
+#[derive(Debug)]
+pub struct DictInstance {
+    entries: Vec<DictEntry>,  // in insertion order
+    buckets: AHashMap<i64, Vec<usize>>,  // positions in `entries` by the hash of the key
+}
+
+impl DictInstance {
+    fn bucket_keys(&self, hash: i64) -> Vec<(usize, PyObject)> {
+        self.buckets.get(&hash).into_iter().flatten().map(|&position| (position, self.entries[position].key.clone())).collect()
+    }
+
+    fn insert(&mut self, hash: i64, key: PyObject, value: PyObject) {
+        self.buckets.entry(hash).or_default().push(self.entries.len());
+        self.entries.push(DictEntry { hash, key, value });
+    }
+
+    fn remove(&mut self, position: usize) -> DictEntry {
+        let entry = self.entries.remove(position);
+
+        self.buckets.clear();  // every later entry moved down by one
+        for (position, entry) in self.entries.iter().enumerate() {
+            self.buckets.entry(entry.hash).or_default().push(position);
+        }
+
+        entry
+    }
+
+    fn pairs(&self) -> Vec<(PyObject, PyObject)> {
+        self.entries.iter().map(|entry| (entry.key.clone(), entry.value.clone())).collect()
+    }
+}
+
+impl PyInstanceInternal for DictInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, _key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        None
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        for entry in &self.entries {
+            visit(&entry.key);
+            visit(&entry.value);
+        }
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        self.buckets.clear();
+        self.entries.drain(..).flat_map(|entry| [entry.key, entry.value]).collect()
+    }
+}
+
+pub fn new_dict(pairs: Vec<(PyObject, PyObject)>, arena: &mut PyArena) -> FuncReturnType {  // fails if a key is unhashable
+    let dict = PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.dict_class.clone(),
+        Box::new(DictInstance { entries: vec![], buckets: AHashMap::new() })
+    )));
+
+    for (key, value) in pairs {
+        set_entry(&dict, key, value, arena)?;
+    }
+
+    Ok(dict)
+}
+
+pub fn try_dict_items(pyobj: &PyObject) -> Option<Vec<(PyObject, PyObject)>> {  // clones the entries so they can be used while calling back into python
+    let PyObject::Mutable(inner) = pyobj else {
+        return None;
+    };
+
+    let inner = inner.borrow();
+
+    match *inner {
+        PyMutableObject::Instance(ref instance) => instance.internal.downcast_ref::<DictInstance>().map(DictInstance::pairs),
+        _ => None,
+    }
+}
+
+fn with_dict<T>(pyself: &PyObject, func: impl FnOnce(&mut DictInstance) -> T) -> T {
+    let mut pyself = pyself.expect_mutable().borrow_mut();
+    let dict = pyself.expect_instance_mut().internal.downcast_mut::<DictInstance>().expect("instance is a dict");
+
+    func(dict)
+}
+
+fn find_key(pyself: &PyObject, key: &PyObject, arena: &mut PyArena) -> Result<(i64, Option<usize>), PyException> {  // the hash of `key` and the position of the equal key that is stored
+    let hash = hash_value(key, arena)?;
+
+    for (position, candidate) in with_dict(pyself, |dict| dict.bucket_keys(hash)) {  // the borrow is released, `__eq__` may run python code
+        if eq_with_identity(&candidate, key, arena)? {
+            return Ok((hash, Some(position)));
+        }
+    }
+
+    Ok((hash, None))
+}
+
+fn lookup(pyself: &PyObject, key: &PyObject, arena: &mut PyArena) -> Result<Option<PyObject>, PyException> {
+    let (_hash, position) = find_key(pyself, key, arena)?;
+    Ok(position.and_then(|position| with_dict(pyself, |dict| dict.entries.get(position).map(|entry| entry.value.clone()))))
+}
+
+fn set_entry(pyself: &PyObject, key: PyObject, value: PyObject, arena: &mut PyArena) -> EmptyFuncReturnType {
+    let (hash, position) = find_key(pyself, &key, arena)?;
+
+    with_dict(pyself, |dict| match position.and_then(|position| dict.entries.get_mut(position)) {
+        Some(entry) => entry.value = value,  // the first key is kept
+        None => dict.insert(hash, key, value),
+    });
+
+    Ok(())
+}
+
+pub fn key_error(key: &PyObject, arena: &mut PyArena) -> PyException {
+    match py_repr(key, arena) {
+        Ok(repr) => arena.exceptions.key_error.instantiate(repr.expect_immutable().expect_string()),
+        Err(err) => err,
+    }
+}
+
+pub fn dict__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    match pyargs {
+        [] => new_dict(vec![], arena),
+        [source] => {
+            if let Some(pairs) = try_dict_items(source) {
+                return new_dict(pairs, arena);
+            }
+
+            let mut pairs = vec![];
+
+            for pair in collect_iterable(source, arena)? {
+                let Some([key, value]) = try_tuple_items(&pair).and_then(|items| <[PyObject; 2]>::try_from(items).ok()) else {
+                    return Err(arena.exceptions.type_error.instantiate("dict() expects a dict or an iterable of (key, value) tuples".to_string()));
+                };
+
+                pairs.push((key, value));
+            }
+
+            new_dict(pairs, arena)
+        }
+        _ => {
+            let message = format!("dict expected at most 1 argument, got {}", pyargs.len());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+pub fn dict__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let mut reprs = vec![];
+
+    for (key, value) in with_dict(pyself, |dict| dict.pairs()) {
+        let key = py_repr(&key, arena)?.expect_immutable().expect_string();
+        let value = py_repr(&value, arena)?.expect_immutable().expect_string();
+        reprs.push(format!("{key}: {value}"));
+    }
+
+    Ok(PyObject::new_string(format!("{{{}}}", reprs.join(", "))))
+}
+
+pub fn dict__len__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_int(with_dict(pyself, |dict| dict.entries.len()) as i64))
+}
+
+pub fn dict__getitem__(arena: &mut PyArena, pyself: &PyObject, key: &PyObject) -> FuncReturnType {
+    match lookup(pyself, key, arena)? {
+        Some(value) => Ok(value),
+        None => Err(key_error(key, arena)),
+    }
+}
+
+pub fn dict__setitem__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let [key, value] = pyargs else {
+        panic!("__setitem__ takes a key and a value")
+    };
+
+    set_entry(pyself, key.clone(), value.clone(), arena)?;
+    Ok(arena.statics.none().clone())
+}
+
+pub fn dict__contains__(arena: &mut PyArena, pyself: &PyObject, key: &PyObject) -> FuncReturnType {
+    let (_hash, position) = find_key(pyself, key, arena)?;
+    Ok(arena.statics.get_bool(position.is_some()).clone())
+}
+
+pub fn dict__eq__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let Some(other_pairs) = try_dict_items(other) else {
+        return Err(arena.exceptions.not_implemented_error.empty());
+    };
+
+    if other_pairs.len() != with_dict(pyself, |dict| dict.entries.len()) {
+        return Ok(arena.statics.get_bool(false).clone());
+    }
+
+    for (key, value) in other_pairs {
+        let equal = match lookup(pyself, &key, arena)? {
+            Some(own_value) => eq_with_identity(&own_value, &value, arena)?,
+            None => false,
+        };
+
+        if !equal {
+            return Ok(arena.statics.get_bool(false).clone());
+        }
+    }
+
+    Ok(arena.statics.get_bool(true).clone())
+}
+
+pub fn dict__iter__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {  // iterates over a snapshot of the keys
+    let keys = dict_keys(arena, pyself, &[])?;
+    list__iter__(arena, &keys)
+}
+
+pub fn dict_get(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let (key, default) = match pyargs {
+        [key] => (key, arena.statics.none().clone()),
+        [key, default] => (key, default.clone()),
+        _ => {
+            let message = format!("get expected 1 or 2 arguments, got {}", pyargs.len());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    };
+
+    Ok(lookup(pyself, key, arena)?.unwrap_or(default))
+}
+
+pub fn dict_pop(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let (key, default) = match pyargs {
+        [key] => (key, None),
+        [key, default] => (key, Some(default.clone())),
+        _ => {
+            let message = format!("pop expected 1 or 2 arguments, got {}", pyargs.len());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    };
+
+    let (_hash, position) = find_key(pyself, key, arena)?;
+
+    match (position, default) {
+        (Some(position), _) => Ok(with_dict(pyself, |dict| dict.remove(position)).value),
+        (None, Some(default)) => Ok(default),
+        (None, None) => Err(key_error(key, arena)),
+    }
+}
+
+pub fn dict_keys(arena: &mut PyArena, pyself: &PyObject, _pyargs: &[PyObject]) -> FuncReturnType {  // lists rather than views
+    let keys = with_dict(pyself, |dict| dict.entries.iter().map(|entry| entry.key.clone()).collect());
+    Ok(new_list(keys, arena))
+}
+
+pub fn dict_values(arena: &mut PyArena, pyself: &PyObject, _pyargs: &[PyObject]) -> FuncReturnType {
+    let values = with_dict(pyself, |dict| dict.entries.iter().map(|entry| entry.value.clone()).collect());
+    Ok(new_list(values, arena))
+}
+
+pub fn dict_items(arena: &mut PyArena, pyself: &PyObject, _pyargs: &[PyObject]) -> FuncReturnType {
+    let pairs = with_dict(pyself, |dict| dict.pairs());
+    let items = pairs.into_iter().map(|(key, value)| new_tuple(vec![key, value], arena)).collect();
+    Ok(new_list(items, arena))
+}
+
+pub fn get_dict_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "dict".to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("get".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(dict_get as VariadicFuncType))))),
+            ("pop".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(dict_pop as VariadicFuncType))))),
+            ("keys".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(dict_keys as VariadicFuncType))))),
+            ("values".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(dict_values as VariadicFuncType))))),
+            ("items".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(dict_items as VariadicFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(dict__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(dict__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(unhashable__hash__ as UnaryFuncType)))),
+
+            __iter__: Some(Rc::new(UnaryFunc(&(dict__iter__ as UnaryFuncType)))),
+
+            __eq__: Some(Rc::new(BivariateFunc(&(dict__eq__ as BivariateFuncType)))),
+            __contains__: Some(Rc::new(BivariateFunc(&(dict__contains__ as BivariateFuncType)))),
+
+            __len__: Some(Rc::new(UnaryFunc(&(dict__len__ as UnaryFuncType)))),
+            __getitem__: Some(Rc::new(BivariateFunc(&(dict__getitem__ as BivariateFuncType)))),
+            __setitem__: Some(Rc::new(VariadicFunc(&(dict__setitem__ as VariadicFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
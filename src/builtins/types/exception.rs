@@ -0,0 +1,223 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::collect_iterable;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{BivariateFuncType, EmptyFuncReturnType, FuncReturnType, InitFuncType, NewFuncType, PyMutableObject, PyObject, UnaryFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, InitFunc, NewFunc, UnaryFunc};
+use crate::builtins::types::pybool::convert_pyobj_to_bool;
+use crate::builtins::types::str::{py_repr, py_str};
+use crate::builtins::types::traceback::is_traceback;
+use crate::builtins::types::tuple::new_tuple;
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+pub struct ExceptionInstance {
+    pub args: Vec<PyObject>,
+    pub traceback: Option<PyObject>,  // the frames it unwound through, see `PyException::add_traceback`
+    pub cause: Option<PyObject>,  // set by `raise ... from ...`
+    pub context: Option<PyObject>,  // the exception being handled when this one was raised
+    pub suppress_context: bool,
+}
+
+impl ExceptionInstance {
+    pub fn new(args: Vec<PyObject>) -> ExceptionInstance {
+        ExceptionInstance {
+            args,
+            traceback: None,
+            cause: None,
+            context: None,
+            suppress_context: false,
+        }
+    }
+}
+
+impl PyInstanceInternal for ExceptionInstance {
+    fn set_field(&mut self, key: String, value: PyObject, arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        match key.as_str() {
+            "args" => Some(collect_iterable(&value, arena).map(|args| self.args = args)),
+            "__traceback__" => Some(expect_optional_traceback(value, arena).map(|traceback| self.traceback = traceback)),
+            "__cause__" => Some(expect_optional_exception(value, "exception cause", arena).map(|cause| {
+                self.cause = cause;
+                self.suppress_context = true;  // same as CPython, setting a cause hides the context
+            })),
+            "__context__" => Some(expect_optional_exception(value, "exception context", arena).map(|context| self.context = context)),
+            "__suppress_context__" => Some(convert_pyobj_to_bool(&value, arena).map(|suppress_context| self.suppress_context = suppress_context)),
+            _ => None,
+        }
+    }
+
+    fn get_field(&self, key: &str, arena: &mut PyArena) -> Option<PyObject> {
+        match key {
+            "args" => Some(new_tuple(self.args.clone(), arena)),
+            "__traceback__" => Some(self.traceback.clone().unwrap_or_else(|| arena.statics.none().clone())),
+            "__cause__" => Some(self.cause.clone().unwrap_or_else(|| arena.statics.none().clone())),
+            "__context__" => Some(self.context.clone().unwrap_or_else(|| arena.statics.none().clone())),
+            "__suppress_context__" => Some(arena.statics.get_bool(self.suppress_context).clone()),
+            _ => None,
+        }
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        self.args.iter().chain(&self.traceback).chain(&self.cause).chain(&self.context).for_each(visit);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        let mut released = std::mem::take(&mut self.args);
+        released.extend([self.traceback.take(), self.cause.take(), self.context.take()].into_iter().flatten());
+        released
+    }
+}
+
+pub fn new_exception(pyclass: Rc<PyClass>, args: Vec<PyObject>) -> PyObject {
+    PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_with_attrs(
+        pyclass,
+        Box::new(ExceptionInstance::new(args))
+    )))
+}
+
+pub fn with_exception_mut<T>(pyself: &PyObject, func: impl FnOnce(&mut ExceptionInstance) -> T) -> T {
+    let mut pyself = pyself.expect_mutable().borrow_mut();
+    let instance = pyself.expect_instance_mut();
+
+    if let Some(exception_internal) = instance.internal.downcast_mut::<ExceptionInstance>() {
+        func(exception_internal)
+    } else {
+        panic!("instance is not ExceptionInstance, its {:?}", instance)
+    }
+}
+
+pub fn is_exception_instance(pyobj: &PyObject) -> bool {
+    match pyobj {
+        PyObject::Mutable(inner) => match inner.try_borrow() {
+            Ok(inner) => matches!(&*inner, PyMutableObject::Instance(instance) if instance.internal.downcast_ref::<ExceptionInstance>().is_some()),
+            Err(_) => false,  // being mutated, so it can't be inspected
+        },
+        _ => false,
+    }
+}
+
+fn expect_optional_exception(value: PyObject, role: &str, arena: &mut PyArena) -> Result<Option<PyObject>, PyException> {  // `None` clears the link
+    if value.is_none() {
+        return Ok(None);
+    }
+
+    if is_exception_instance(&value) {
+        return Ok(Some(value));
+    }
+
+    Err(arena.exceptions.type_error.instantiate(format!("{role} must be None or derive from BaseException")))
+}
+
+fn expect_optional_traceback(value: PyObject, arena: &mut PyArena) -> Result<Option<PyObject>, PyException> {
+    if value.is_none() {
+        return Ok(None);
+    }
+
+    if is_traceback(&value) {
+        return Ok(Some(value));
+    }
+
+    Err(arena.exceptions.type_error.instantiate("__traceback__ must be a traceback or None".to_string()))
+}
+
+fn expect_exception_args(pyself: &PyObject) -> Vec<PyObject> {
+    with_exception_mut(pyself, |exception| exception.args.clone())
+}
+
+fn reprs_of(items: &[PyObject], arena: &mut PyArena) -> Result<Vec<String>, PyException> {
+    items.iter().map(|item| Ok(py_repr(item, arena)?.expect_immutable().expect_string())).collect()
+}
+
+pub fn base_exception__new__(_arena: &mut PyArena, pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    Ok(new_exception(pyclass, pyargs.to_vec()))
+}
+
+pub fn base_exception__init__(_arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> EmptyFuncReturnType {
+    with_exception_mut(pyself, |exception| exception.args = pyargs.to_vec());
+
+    Ok(())
+}
+
+pub fn system_exit__init__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> EmptyFuncReturnType {
+    base_exception__init__(arena, pyself, pyargs)?;
+
+    let code = match pyargs {
+        [] => arena.statics.none().clone(),
+        [code] => code.clone(),
+        args => new_tuple(args.to_vec(), arena),
+    };
+
+    pyself.expect_mutable().borrow_mut().expect_instance_mut().set_field("code".to_string(), code, arena)
+}
+
+pub fn base_exception__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let args = expect_exception_args(pyself);
+    let reprs = reprs_of(&args, arena)?;
+
+    Ok(PyObject::new_string(format!("{}({})", pyself.clone_class(arena).get_name(), reprs.join(", "))))
+}
+
+pub fn base_exception__str__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let args = expect_exception_args(pyself);
+
+    match args.as_slice() {
+        [] => Ok(PyObject::new_string(String::new())),
+        [arg] => py_str(arg, arena),
+        args => {
+            let reprs = reprs_of(args, arena)?;
+            Ok(PyObject::new_string(format!("({})", reprs.join(", "))))
+        }
+    }
+}
+
+pub fn base_exception_with_traceback(arena: &mut PyArena, pyself: &PyObject, traceback: &PyObject) -> FuncReturnType {
+    let traceback = expect_optional_traceback(traceback.clone(), arena)?;
+    with_exception_mut(pyself, |exception| exception.traceback = traceback);
+
+    Ok(pyself.clone())
+}
+
+pub fn get_base_exception_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "BaseException".to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("with_traceback".to_string(), PyObject::new_internal_func(Rc::new(BivariateFunc(&(base_exception_with_traceback as BivariateFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(base_exception__new__ as NewFuncType)))),
+            __init__: Some(Rc::new(InitFunc(&(base_exception__init__ as InitFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(base_exception__repr__ as UnaryFuncType)))),
+            __str__: Some(Rc::new(UnaryFunc(&(base_exception__str__ as UnaryFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
+
+pub fn get_system_exit_class(base_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "SystemExit".to_string(),
+        super_classes: vec![base_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __init__: Some(Rc::new(InitFunc(&(system_exit__init__ as InitFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
+
+pub fn get_exception_subclass(name: &str, base_class: Rc<PyClass>) -> PyClass {  // every builtin exception below `BaseException` only differs by name and base
+    PyClass::Internal {
+        name: name.to_string(),
+        super_classes: vec![base_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(py_magic_methods_defaults()),
+    }.create()
+}
@@ -0,0 +1,42 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyobject::{ClassCell, FuncReturnType, PyFunction, PyMutableObject, PyObject, UnaryFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::UnaryFunc;
+use crate::compiler::CodeObject;
+use crate::pyarena::PyArena;
+
+pub fn new_function(code: Rc<CodeObject>, class_cell: Option<ClassCell>, function_class: Rc<PyClass>) -> PyObject {
+    PyObject::new_mutable(PyMutableObject::Function(PyFunction { class: function_class, code, class_cell }))
+}
+
+pub fn function__repr__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    match &*pyself.expect_mutable().borrow() {
+        PyMutableObject::Function(function) => Ok(PyObject::new_string(format!("<function {} at {:#x}>", function.code.name, pyself.get_memory_location()))),
+        other => panic!("object is not a function, its {:?}", other),
+    }
+}
+
+pub fn get_function_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "function".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __repr__: Some(Rc::new(UnaryFunc(&(function__repr__ as UnaryFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
+
+pub fn get_builtin_function_class(object_class: Rc<PyClass>) -> PyClass {  // the class of the functions implemented in rust
+    PyClass::Internal {
+        name: "builtin_function_or_method".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(py_magic_methods_defaults()),
+    }.create()
+}
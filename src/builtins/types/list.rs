@@ -0,0 +1,307 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::collect_iterable;
+use crate::builtins::functions::compare::eq_with_identity;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{BivariateFuncType, EmptyFuncReturnType, FuncReturnType, NewFuncType, PyImmutableObject, PyMutableObject, PyObject, UnaryFuncType, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, UnaryFunc, VariadicFunc};
+use crate::builtins::types::pyint::expect_int;
+use crate::builtins::types::slice::{normalize_index, slice_indices};
+use crate::builtins::types::str::py_repr;
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+pub struct ListInstance {
+    items: Vec<PyObject>,
+}
+
+impl PyInstanceInternal for ListInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, _key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        None
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        self.items.iter().for_each(visit);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        std::mem::take(&mut self.items)
+    }
+}
+
+pub fn new_list(items: Vec<PyObject>, arena: &mut PyArena) -> PyObject {
+    PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.list_class.clone(),
+        Box::new(ListInstance { items })
+    )))
+}
+
+pub fn try_list_items(pyobj: &PyObject) -> Option<Vec<PyObject>> {  // clones the items so they can be used while calling back into python
+    let PyObject::Mutable(inner) = pyobj else {
+        return None;
+    };
+
+    let inner = inner.borrow();
+
+    match *inner {
+        PyMutableObject::Instance(ref instance) => instance.internal.downcast_ref::<ListInstance>().map(|list| list.items.clone()),
+        _ => None,
+    }
+}
+
+fn expect_list_items(pyobj: &PyObject) -> Vec<PyObject> {
+    try_list_items(pyobj).unwrap_or_else(|| panic!("Expected list, got {:?}", pyobj))
+}
+
+fn expect_list_items_promotion(pyobj: &PyObject, arena: &mut PyArena) -> Result<Vec<PyObject>, PyException> {
+    try_list_items(pyobj).ok_or_else(|| arena.exceptions.not_implemented_error.empty())
+}
+
+fn with_list_mut<T>(pyself: &PyObject, func: impl FnOnce(&mut Vec<PyObject>) -> T) -> T {
+    let mut pyself = pyself.expect_mutable().borrow_mut();
+    let instance = pyself.expect_instance_mut();
+
+    if let Some(list_internal) = instance.internal.downcast_mut::<ListInstance>() {
+        func(&mut list_internal.items)
+    } else {
+        panic!("instance is not ListInstance, its {:?}", instance)
+    }
+}
+
+fn items_equal(left: &[PyObject], right: &[PyObject], arena: &mut PyArena) -> Result<bool, PyException> {
+    if left.len() != right.len() {
+        return Ok(false);
+    }
+
+    for (left, right) in left.iter().zip(right) {
+        if !eq_with_identity(left, right, arena)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+pub fn list__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    let items = match pyargs {
+        [] => vec![],
+        [iterable] => collect_iterable(iterable, arena)?,
+        _ => {
+            let message = format!("list expected at most 1 argument, got {}", pyargs.len());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    };
+
+    Ok(new_list(items, arena))
+}
+
+pub fn list__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let items = expect_list_items(pyself);
+
+    let reprs = items.iter().map(|item| Ok(py_repr(item, arena)?.expect_immutable().expect_string())).collect::<Result<Vec<_>, PyException>>()?;
+
+    Ok(PyObject::new_string(format!("[{}]", reprs.join(", "))))
+}
+
+pub fn list__len__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_int(expect_list_items(pyself).len() as i64))
+}
+
+pub fn list__getitem__(arena: &mut PyArena, pyself: &PyObject, key: &PyObject) -> FuncReturnType {
+    let items = expect_list_items(pyself);
+
+    match *key.expect_immutable() {
+        PyImmutableObject::Int(index) => {
+            let index = normalize_index(index, items.len()).ok_or_else(|| arena.exceptions.index_error.instantiate("list index out of range".to_string()))?;
+            Ok(items[index].clone())
+        }
+        ref slice @ PyImmutableObject::Slice {..} => {
+            let sliced = slice_indices(slice, items.len(), arena)?.into_iter().map(|i| items[i].clone()).collect();
+            Ok(new_list(sliced, arena))
+        }
+        ref other => {
+            let message = format!("list indices must be integers or slices, not {}", other.get_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+pub fn list__setitem__(arena: &mut PyArena, pyself: &PyObject, args: &[PyObject]) -> FuncReturnType {
+    let [ref key, ref value] = args[..] else {
+        panic!("__setitem__ expects a key and a value, got {:?}", args)
+    };
+
+    let length = expect_list_items(pyself).len();
+
+    match *key.expect_immutable() {
+        PyImmutableObject::Int(index) => {
+            let index = normalize_index(index, length).ok_or_else(|| arena.exceptions.index_error.instantiate("list assignment index out of range".to_string()))?;
+            with_list_mut(pyself, |items| items[index] = value.clone());
+        }
+        ref other => {
+            let message = format!("list indices must be integers or slices, not {}", other.get_class(arena).get_name());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    }
+
+    Ok(arena.statics.none().clone())
+}
+
+pub fn list__add__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let mut items = expect_list_items(pyself);
+    items.extend(expect_list_items_promotion(other, arena)?);
+
+    Ok(new_list(items, arena))
+}
+
+pub fn list__eq__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let items = expect_list_items(pyself);
+    let other_items = expect_list_items_promotion(other, arena)?;
+
+    let equal = items_equal(&items, &other_items, arena)?;
+    Ok(arena.statics.get_bool(equal).clone())
+}
+
+pub fn list__ne__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let items = expect_list_items(pyself);
+    let other_items = expect_list_items_promotion(other, arena)?;
+
+    let equal = items_equal(&items, &other_items, arena)?;
+    Ok(arena.statics.get_bool(!equal).clone())
+}
+
+pub fn list__contains__(arena: &mut PyArena, pyself: &PyObject, item: &PyObject) -> FuncReturnType {
+    let items = expect_list_items(pyself);
+
+    for candidate in items.iter() {
+        if eq_with_identity(candidate, item, arena)? {
+            return Ok(arena.statics.get_bool(true).clone());
+        }
+    }
+
+    Ok(arena.statics.get_bool(false).clone())
+}
+
+pub fn list__iter__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.list_iterator_class.clone(),
+        Box::new(ListIteratorInstance { list: pyself.clone(), index: 0 })
+    ))))
+}
+
+pub fn list_append(arena: &mut PyArena, pyself: &PyObject, item: &PyObject) -> FuncReturnType {
+    with_list_mut(pyself, |items| items.push(item.clone()));
+
+    Ok(arena.statics.none().clone())
+}
+
+pub fn list_pop(arena: &mut PyArena, pyself: &PyObject, args: &[PyObject]) -> FuncReturnType {
+    let length = expect_list_items(pyself).len();
+
+    let index = match args.first() {
+        Some(index) => expect_int(index, arena)?,
+        None => -1,
+    };
+
+    if length == 0 {
+        return Err(arena.exceptions.index_error.instantiate("pop from empty list".to_string()));
+    }
+
+    let index = normalize_index(index, length).ok_or_else(|| arena.exceptions.index_error.instantiate("pop index out of range".to_string()))?;
+
+    Ok(with_list_mut(pyself, |items| items.remove(index)))
+}
+
+pub fn get_list_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "list".to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("append".to_string(), PyObject::new_internal_func(Rc::new(BivariateFunc(&(list_append as BivariateFuncType))))),
+            ("pop".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(list_pop as VariadicFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(list__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(list__repr__ as UnaryFuncType)))),
+
+            __add__: Some(Rc::new(BivariateFunc(&(list__add__ as BivariateFuncType)))),
+
+            __iter__: Some(Rc::new(UnaryFunc(&(list__iter__ as UnaryFuncType)))),
+
+            __eq__: Some(Rc::new(BivariateFunc(&(list__eq__ as BivariateFuncType)))),
+            __ne__: Some(Rc::new(BivariateFunc(&(list__ne__ as BivariateFuncType)))),
+            __contains__: Some(Rc::new(BivariateFunc(&(list__contains__ as BivariateFuncType)))),
+
+            __len__: Some(Rc::new(UnaryFunc(&(list__len__ as UnaryFuncType)))),
+            __getitem__: Some(Rc::new(BivariateFunc(&(list__getitem__ as BivariateFuncType)))),
+            __setitem__: Some(Rc::new(VariadicFunc(&(list__setitem__ as VariadicFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
+
+#[derive(Debug)]
+struct ListIteratorInstance {
+    list: PyObject,
+    index: usize,
+}
+
+impl PyInstanceInternal for ListIteratorInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, _key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        None
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        visit(&self.list);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        vec![std::mem::replace(&mut self.list, PyObject::None)]
+    }
+}
+
+pub fn list_iterator__next__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let mut pyself = pyself.expect_mutable().borrow_mut();
+    let instance = pyself.expect_instance_mut();
+
+    if let Some(list_iterator_internal) = instance.internal.downcast_mut::<ListIteratorInstance>() {
+        let index = list_iterator_internal.index;
+        let item = with_list_mut(&list_iterator_internal.list, |items| items.get(index).cloned());  // re-read each step so appends during iteration are seen
+
+        let Some(item) = item else {
+            return Ok(PyObject::stop_iteration());
+        };
+
+        list_iterator_internal.index += 1;
+
+        Ok(item)
+    } else {
+        panic!("instance is not ListIteratorInstance, its {:?}", instance)
+    }
+}
+
+pub fn get_list_iterator_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {  // Hidden class
+        name: "list_iterator".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __next__: Some(Rc::new(UnaryFunc(&(list_iterator__next__ as UnaryFuncType)))),
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
@@ -0,0 +1,122 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{EmptyFuncReturnType, FuncReturnType, PyMutableObject, PyObject, UnaryFuncType, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{UnaryFunc, VariadicFunc};
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+pub struct MemberInstance {  // the descriptor `__slots__` creates for each of its names
+    name: String,
+    class_name: String,
+    index: usize,  // into `PyInstance::slots`
+}
+
+impl PyInstanceInternal for MemberInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        match key {
+            "__name__" => Some(PyObject::new_string(self.name.clone())),
+            _ => None,
+        }
+    }
+}
+
+pub fn new_member(name: String, class_name: String, index: usize, arena: &mut PyArena) -> PyObject {
+    PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.member_descriptor_class.clone(),
+        Box::new(MemberInstance { name, class_name, index })
+    )))
+}
+
+fn expect_member(pyself: &PyObject) -> (String, String, usize) {
+    let pyself = pyself.expect_mutable().borrow();
+    let member = pyself.expect_instance().internal.downcast_ref::<MemberInstance>().expect("instance is a member descriptor");
+
+    (member.name.clone(), member.class_name.clone(), member.index)
+}
+
+fn access_slot<T>(pyself: &PyObject, instance: &PyObject, arena: &mut PyArena, access: impl FnOnce(&mut Option<PyObject>) -> T) -> Result<T, PyException> {
+    let (name, class_name, index) = expect_member(pyself);
+
+    if let PyObject::Mutable(inner) = instance {
+        if let PyMutableObject::Instance(ref mut instance) = *inner.borrow_mut() {
+            if let Some(slot) = instance.slots.get_mut(index) {
+                return Ok(access(slot));
+            }
+        }
+    }
+
+    let message = format!("descriptor '{name}' for '{class_name}' objects doesn't apply to a '{}' object", instance.clone_class(arena).get_name());
+    Err(arena.exceptions.type_error.instantiate(message))
+}
+
+fn missing_slot(pyself: &PyObject, instance: &PyObject, arena: &mut PyArena) -> PyException {
+    let (name, _class_name, _index) = expect_member(pyself);
+    let message = format!("'{}' object has no attribute '{name}'", instance.clone_class(arena).get_name());
+    arena.exceptions.attribute_error.instantiate(message)
+}
+
+pub fn member__get__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let [instance, _owner] = pyargs else {
+        panic!("__get__ takes an instance and its class")
+    };
+
+    if instance.is_none() {  // looked up on the class
+        return Ok(pyself.clone());
+    }
+
+    match access_slot(pyself, instance, arena, |slot| slot.clone())? {
+        Some(value) => Ok(value),
+        None => Err(missing_slot(pyself, instance, arena)),
+    }
+}
+
+pub fn member__set__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let [instance, value] = pyargs else {
+        panic!("__set__ takes an instance and a value")
+    };
+
+    let _old_value = access_slot(pyself, instance, arena, |slot| slot.replace(value.clone()))?;  // dropped once the instance is no longer borrowed
+    Ok(arena.statics.none().clone())
+}
+
+pub fn member__delete__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let [instance] = pyargs else {
+        panic!("__delete__ takes an instance")
+    };
+
+    match access_slot(pyself, instance, arena, Option::take)? {
+        Some(_old_value) => Ok(arena.statics.none().clone()),
+        None => Err(missing_slot(pyself, instance, arena)),
+    }
+}
+
+pub fn member__repr__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let (name, class_name, _index) = expect_member(pyself);
+    Ok(PyObject::new_string(format!("<member '{name}' of '{class_name}' objects>")))
+}
+
+pub fn get_member_descriptor_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "member_descriptor".to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("__get__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(member__get__ as VariadicFuncType))))),
+            ("__set__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(member__set__ as VariadicFuncType))))),
+            ("__delete__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(member__delete__ as VariadicFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __repr__: Some(Rc::new(UnaryFunc(&(member__repr__ as UnaryFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
@@ -0,0 +1,93 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::call_function_1_arg_min;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{EmptyFuncReturnType, FuncReturnType, PyMutableObject, PyObject, UnaryFuncType, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{UnaryFunc, VariadicFunc};
+use crate::builtins::types::str::py_repr;
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+pub struct MethodInstance {  // a function bound to the object it was looked up on
+    function: PyObject,
+    pyself: PyObject,
+}
+
+impl PyInstanceInternal for MethodInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        match key {
+            "__func__" => Some(self.function.clone()),
+            "__self__" => Some(self.pyself.clone()),
+            _ => None,
+        }
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        visit(&self.function);
+        visit(&self.pyself);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        vec![std::mem::replace(&mut self.function, PyObject::None), std::mem::replace(&mut self.pyself, PyObject::None)]
+    }
+}
+
+pub fn new_method(function: PyObject, pyself: PyObject, arena: &mut PyArena) -> PyObject {
+    PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.method_class.clone(),
+        Box::new(MethodInstance { function, pyself })
+    )))
+}
+
+fn expect_method(pyself: &PyObject) -> (PyObject, PyObject) {  // cloned out, so the borrow isn't held while the function runs
+    let pyself = pyself.expect_mutable().borrow();
+    let method = pyself.expect_instance().internal.downcast_ref::<MethodInstance>().expect("instance is a bound method");
+
+    (method.function.clone(), method.pyself.clone())
+}
+
+pub fn method__call__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let (function, bound_self) = expect_method(pyself);
+    call_function_1_arg_min(&function, &bound_self, pyargs, arena)
+}
+
+pub fn method__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let (function, bound_self) = expect_method(pyself);
+
+    let function_name = match function {
+        PyObject::Mutable(ref inner) => match *inner.borrow() {
+            PyMutableObject::Function(ref function) => Some(function.code.name.clone()),
+            PyMutableObject::Instance(_) => None,
+        },
+        _ => None,
+    };
+
+    let repr = match function_name {
+        Some(name) => format!("<bound method {name} of {}>", py_repr(&bound_self, arena)?.expect_immutable().expect_string()),
+        None => format!("<built-in method of {} object at {:#x}>", bound_self.clone_class(arena).get_name(), bound_self.get_memory_location()),
+    };
+
+    Ok(PyObject::new_string(repr))
+}
+
+pub fn get_method_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "method".to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("__call__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(method__call__ as VariadicFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __repr__: Some(Rc::new(UnaryFunc(&(method__repr__ as UnaryFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
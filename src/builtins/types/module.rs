@@ -0,0 +1,68 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{EmptyFuncReturnType, FuncReturnType, PyMutableObject, PyObject, UnaryFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::UnaryFunc;
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+pub struct ModuleInstance {
+    name: String,
+    attributes: AHashMap<String, PyObject>,
+}
+
+impl PyInstanceInternal for ModuleInstance {
+    fn set_field(&mut self, key: String, value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        self.attributes.insert(key, value);
+        Some(Ok(()))
+    }
+
+    fn get_field(&self, key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        match key {
+            "__name__" => Some(PyObject::new_string(self.name.clone())),
+            _ => self.attributes.get(key).cloned(),
+        }
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        self.attributes.values().for_each(visit);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        self.attributes.drain().map(|(_key, value)| value).collect()
+    }
+}
+
+pub fn new_module(name: &str, attributes: AHashMap<String, PyObject>, module_class: Rc<PyClass>) -> PyObject {
+    PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        module_class,
+        Box::new(ModuleInstance { name: name.to_string(), attributes })
+    )))
+}
+
+pub fn module__repr__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let pyself = pyself.expect_mutable().borrow();
+    let instance = pyself.expect_instance();
+
+    if let Some(module) = instance.internal.downcast_ref::<ModuleInstance>() {
+        Ok(PyObject::new_string(format!("<module '{}' (built-in)>", module.name)))
+    } else {
+        panic!("instance is not ModuleInstance, its {:?}", instance)
+    }
+}
+
+pub fn get_module_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "module".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __repr__: Some(Rc::new(UnaryFunc(&(module__repr__ as UnaryFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
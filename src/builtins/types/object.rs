@@ -2,12 +2,12 @@
 use std::rc::Rc;
 use ahash::AHashMap;
 use crate::builtins::function_utils::call_function;
-use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethod, PyMagicMethods};
+use crate::builtins::functions::attributes::{expect_attribute_name, generic_delete_attribute, generic_get_attribute, generic_set_attribute};
+use crate::builtins::structure::magic_methods::{PyMagicMethod, PyMagicMethods};
 use crate::builtins::structure::pyclass::PyClass;
 use crate::builtins::structure::pyinstance::PyInstance;
-use crate::builtins::structure::pyobject::{InitFuncType, EmptyFuncReturnType, FuncReturnType, NewFuncType, UnaryFuncType, PyObject, PyMutableObject, PyImmutableObject, PyInternalObject};
-use crate::builtins::structure::pyobject::PyInternalFunction::{InitFunc, NewFunc, UnaryFunc};
-use crate::parser::Define;
+use crate::builtins::structure::pyobject::{InitFuncType, EmptyFuncReturnType, FuncReturnType, ManyArgFuncType, NewFuncType, UnaryFuncType, VariadicFuncType, PyObject, PyMutableObject, PyImmutableObject, PyInternalObject};
+use crate::builtins::structure::pyobject::PyInternalFunction::{InitFunc, ManyArgFunc, NewFunc, UnaryFunc, VariadicFunc};
 use crate::pyarena::PyArena;
 
 
@@ -18,8 +18,18 @@ pub fn expect_class(pyobj: &PyObject) -> Rc<PyClass> {
     }
 }
 
+fn overrides_object_method(pyclass: &PyClass, magic_method: PyMagicMethod, arena: &PyArena) -> bool {  // also true when a base other than `object` defines it
+    let object_method = arena.globals.object_class.get_magic_method_internal(&magic_method);
+
+    match pyclass.search_for_magic_method(&magic_method) {
+        Some(PyObject::Internal(PyInternalObject::InternalFunction(method))) => !object_method.is_some_and(|object_method| Rc::ptr_eq(&object_method, &method)),
+        Some(_) => true,
+        None => false,
+    }
+}
+
 pub fn object__new__(arena: &mut PyArena, pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
-    if !pyclass.defines_attribute(PyMagicMethod::Init) && pyargs.len() > 0 {
+    if !pyargs.is_empty() && !overrides_object_method(&pyclass, PyMagicMethod::Init, arena) {
         return Err(arena.exceptions.type_error.instantiate("object() takes no arguments".to_string()));
     } 
 
@@ -31,7 +41,7 @@ pub fn object__new__(arena: &mut PyArena, pyclass: Rc<PyClass>, pyargs: &[PyObje
 pub fn object__init__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> EmptyFuncReturnType {
     let pyclass = pyself.clone_class(arena);
 
-    if !pyclass.defines_attribute(PyMagicMethod::New) && pyargs.len() > 0 {
+    if !pyargs.is_empty() && !overrides_object_method(&pyclass, PyMagicMethod::New, arena) {
         return Err(arena.exceptions.type_error.instantiate("object.__init__() takes exactly one argument (the instance to initialize)".to_string()));
     }
 
@@ -44,20 +54,80 @@ pub fn object__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType
 
 pub fn object__str__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
     let str_func = pyself.get_magic_method(&PyMagicMethod::Repr, arena).expect("__repr__ should always be defined");
-    call_function(str_func, &[pyself.clone()], arena)
+    call_function(str_func, std::slice::from_ref(pyself), arena)
+}
+
+pub fn hash_pointer(memory_location: usize) -> i64 {  // same as CPython's `_Py_HashPointer`, the low bits are always zero due to alignment
+    let hash = memory_location.rotate_right(4) as i64;
+
+    if hash == -1 { -2 } else { hash }
+}
+
+pub fn object__hash__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_int(hash_pointer(pyself.get_memory_location())))
+}
+
+pub fn unhashable__hash__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {  // stands in for `__hash__ = None`, which blocks inheriting `object.__hash__`
+    let message = format!("unhashable type: '{}'", pyself.clone_class(arena).get_name());
+    Err(arena.exceptions.type_error.instantiate(message))
+}
+
+pub fn object__getattribute__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {  // the default lookup, so overrides can fall back to it
+    let [name] = pyargs else {
+        let message = format!("expected 1 argument, got {}", pyargs.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let name = expect_attribute_name(name, arena)?;
+    generic_get_attribute(pyself, &name, arena)
+}
+
+pub fn object__setattr__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let [name, value] = pyargs else {
+        let message = format!("expected 2 arguments, got {}", pyargs.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let name = expect_attribute_name(name, arena)?;
+    generic_set_attribute(pyself, &name, value.clone(), arena)?;
+    Ok(arena.statics.none().clone())
+}
+
+pub fn object__delattr__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let [name] = pyargs else {
+        let message = format!("expected 1 argument, got {}", pyargs.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let name = expect_attribute_name(name, arena)?;
+    generic_delete_attribute(pyself, &name, arena)?;
+    Ok(arena.statics.none().clone())
+}
+
+pub fn object__init_subclass__(arena: &mut PyArena, pyargs: &[PyObject]) -> FuncReturnType {  // the end of the `__init_subclass__` chain, already bound to the new class
+    match pyargs.len() {
+        0 => Ok(arena.statics.none().clone()),
+        _ => Err(arena.exceptions.type_error.instantiate("object.__init_subclass__() takes no arguments".to_string())),
+    }
 }
 
 pub fn get_object_class() -> PyClass {
     PyClass::Internal {
         name: "object".to_string(),
         super_classes: vec![],
-        attributes: AHashMap::new(),
+        attributes: vec![
+            ("__getattribute__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(object__getattribute__ as VariadicFuncType))))),
+            ("__setattr__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(object__setattr__ as VariadicFuncType))))),
+            ("__delattr__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(object__delattr__ as VariadicFuncType))))),
+            ("__init_subclass__".to_string(), PyObject::new_internal_func(Rc::new(ManyArgFunc(&(object__init_subclass__ as ManyArgFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
         magic_methods: Box::new(PyMagicMethods {
             __new__: Some(Rc::new(NewFunc(&(object__new__ as NewFuncType)))),
             __init__: Some(Rc::new(InitFunc(&(object__init__ as InitFuncType)))),
 
             __str__: Some(Rc::new(UnaryFunc(&(object__str__ as UnaryFuncType)))),
             __repr__: Some(Rc::new(UnaryFunc(&(object__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(object__hash__ as UnaryFuncType)))),
 
             ..Default::default()
         })
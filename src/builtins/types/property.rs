@@ -0,0 +1,161 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::call_function;
+use crate::builtins::functions::attributes::expect_attribute_name;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{BivariateFuncType, EmptyFuncReturnType, FuncReturnType, NewFuncType, PyMutableObject, PyObject, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, VariadicFunc};
+use crate::pyarena::PyArena;
+
+#[derive(Debug, Clone, Default)]
+pub struct PropertyInstance {
+    fget: Option<PyObject>,
+    fset: Option<PyObject>,
+    fdel: Option<PyObject>,
+    name: Option<String>,  // set by `__set_name__`, only used in error messages
+}
+
+impl PyInstanceInternal for PropertyInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, key: &str, arena: &mut PyArena) -> Option<PyObject> {
+        let accessor = match key {
+            "fget" => &self.fget,
+            "fset" => &self.fset,
+            "fdel" => &self.fdel,
+            _ => return None,
+        };
+
+        Some(accessor.clone().unwrap_or_else(|| arena.statics.none().clone()))
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        [&self.fget, &self.fset, &self.fdel].into_iter().flatten().for_each(visit);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        [self.fget.take(), self.fset.take(), self.fdel.take()].into_iter().flatten().collect()
+    }
+}
+
+fn new_property(property: PropertyInstance, arena: &mut PyArena) -> PyObject {
+    PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.property_class.clone(),
+        Box::new(property)
+    )))
+}
+
+fn expect_property(pyself: &PyObject) -> PropertyInstance {  // cloned out, so the borrow isn't held while an accessor runs
+    let pyself = pyself.expect_mutable().borrow();
+    pyself.expect_instance().internal.downcast_ref::<PropertyInstance>().expect("instance is a property").clone()
+}
+
+fn missing_accessor(property: &PropertyInstance, instance: &PyObject, accessor: &str, arena: &mut PyArena) -> PyException {
+    let class_name = instance.clone_class(arena).get_name().clone();
+    let message = match property.name {
+        Some(ref name) => format!("property '{name}' of '{class_name}' object has no {accessor}"),
+        None => format!("property of '{class_name}' object has no {accessor}"),
+    };
+
+    arena.exceptions.attribute_error.instantiate(message)
+}
+
+pub fn property__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    if pyargs.len() > 3 {
+        let message = format!("property() takes at most 3 arguments ({} given)", pyargs.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let accessor = |index: usize| pyargs.get(index).filter(|accessor| !accessor.is_none()).cloned();
+    let property = PropertyInstance { fget: accessor(0), fset: accessor(1), fdel: accessor(2), name: None };
+
+    Ok(new_property(property, arena))
+}
+
+pub fn property__get__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let [instance, _owner] = pyargs else {
+        panic!("__get__ takes an instance and its class")
+    };
+
+    if instance.is_none() {  // looked up on the class
+        return Ok(pyself.clone());
+    }
+
+    let property = expect_property(pyself);
+    match property.fget {
+        Some(ref fget) => call_function(fget.clone(), std::slice::from_ref(instance), arena),
+        None => Err(missing_accessor(&property, instance, "getter", arena)),
+    }
+}
+
+pub fn property__set__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let [instance, value] = pyargs else {
+        panic!("__set__ takes an instance and a value")
+    };
+
+    let property = expect_property(pyself);
+    match property.fset {
+        Some(ref fset) => call_function(fset.clone(), &[instance.clone(), value.clone()], arena),
+        None => Err(missing_accessor(&property, instance, "setter", arena)),
+    }
+}
+
+pub fn property__delete__(arena: &mut PyArena, pyself: &PyObject, instance: &PyObject) -> FuncReturnType {
+    let property = expect_property(pyself);
+    match property.fdel {
+        Some(ref fdel) => call_function(fdel.clone(), std::slice::from_ref(instance), arena),
+        None => Err(missing_accessor(&property, instance, "deleter", arena)),
+    }
+}
+
+pub fn property__set_name__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let [_owner, name] = pyargs else {
+        panic!("__set_name__ takes the class and the attribute name")
+    };
+
+    let name = expect_attribute_name(name, arena)?;
+
+    let mut pyself = pyself.expect_mutable().borrow_mut();
+    pyself.expect_instance_mut().internal.downcast_mut::<PropertyInstance>().expect("instance is a property").name = Some(name);
+
+    Ok(arena.statics.none().clone())
+}
+
+pub fn property_getter(arena: &mut PyArena, pyself: &PyObject, fget: &PyObject) -> FuncReturnType {  // the decorators return a copy, like CPython
+    Ok(new_property(PropertyInstance { fget: Some(fget.clone()), ..expect_property(pyself) }, arena))
+}
+
+pub fn property_setter(arena: &mut PyArena, pyself: &PyObject, fset: &PyObject) -> FuncReturnType {
+    Ok(new_property(PropertyInstance { fset: Some(fset.clone()), ..expect_property(pyself) }, arena))
+}
+
+pub fn property_deleter(arena: &mut PyArena, pyself: &PyObject, fdel: &PyObject) -> FuncReturnType {
+    Ok(new_property(PropertyInstance { fdel: Some(fdel.clone()), ..expect_property(pyself) }, arena))
+}
+
+pub fn get_property_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "property".to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("__get__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(property__get__ as VariadicFuncType))))),
+            ("__set__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(property__set__ as VariadicFuncType))))),
+            ("__delete__".to_string(), PyObject::new_internal_func(Rc::new(BivariateFunc(&(property__delete__ as BivariateFuncType))))),
+            ("__set_name__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(property__set_name__ as VariadicFuncType))))),
+            ("getter".to_string(), PyObject::new_internal_func(Rc::new(BivariateFunc(&(property_getter as BivariateFuncType))))),
+            ("setter".to_string(), PyObject::new_internal_func(Rc::new(BivariateFunc(&(property_setter as BivariateFuncType))))),
+            ("deleter".to_string(), PyObject::new_internal_func(Rc::new(BivariateFunc(&(property_deleter as BivariateFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(property__new__ as NewFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
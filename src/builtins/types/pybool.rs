@@ -5,24 +5,25 @@ use crate::builtins::function_utils::call_function_1_arg_min;
 use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethod, PyMagicMethods};
 use crate::builtins::structure::pyclass::PyClass;
 use crate::builtins::structure::pyexception::PyException;
-use crate::builtins::structure::pyobject::{FuncReturnType, NewFuncType, PyImmutableObject, PyMutableObject, PyObject, UnaryFuncType};
+use crate::builtins::types::pyint::expect_int;
+use crate::builtins::structure::pyobject::{FuncReturnType, NewFuncType, PyImmutableObject, PyObject, UnaryFuncType};
 use crate::builtins::structure::pyobject::PyInternalFunction::{NewFunc, UnaryFunc};
 use crate::pyarena::PyArena;
 
 
 pub fn expect_bool(pyobj: &PyObject, arena: &mut PyArena) -> Result<bool, PyException> {
-    match **pyobj.expect_immutable() {
-        PyImmutableObject::Bool(ref value) => {Ok(*value)}
-        ref value => {
-            let message = format!("'{}' object cannot be interpreted as a boolean", value.get_class(arena).get_name());
+    match pyobj.as_immutable().as_deref() {
+        Some(PyImmutableObject::Bool(value)) => {Ok(*value)}
+        _ => {
+            let message = format!("'{}' object cannot be interpreted as a boolean", pyobj.clone_class(arena).get_name());
             Err(arena.exceptions.type_error.instantiate(message))
         },
     }
 }
 
 
-fn convert_mutable_to_bool(pyobj: &PyObject, mutable_obj: &PyMutableObject, arena: &mut PyArena ) -> Result<bool, PyException> {
-    let bool_func = mutable_obj.get_magic_method(&PyMagicMethod::Bool, arena);
+fn convert_mutable_to_bool(pyobj: &PyObject, arena: &mut PyArena) -> Result<bool, PyException> {
+    let bool_func = pyobj.get_magic_method(&PyMagicMethod::Bool, arena);
 
     if let Some(bool_func) = bool_func {
         let func_result = call_function_1_arg_min(&bool_func, pyobj, &[], arena)?;
@@ -30,13 +31,20 @@ fn convert_mutable_to_bool(pyobj: &PyObject, mutable_obj: &PyMutableObject, aren
         let bool_result = expect_bool(&func_result, arena);
 
         return bool_result.map_err(|_error| {
-            let message = format!("{}.__bool__ should return bool, returned (type {{<other type>}})", pyobj.clone_class(arena).get_name());  // TODO inconsistent python error messaging?
+            let message = format!("{}.__bool__ should return bool, returned {}", pyobj.clone_class(arena).get_name(), func_result.clone_class(arena).get_name());
             arena.exceptions.type_error.instantiate(message)
         });
     }
 
-    let message = format!("bool() argument must be a string, a bytes-like object or a real number, not '{}'", pyobj.clone_class(arena).get_name());
-    Err(arena.exceptions.type_error.instantiate(message))
+    let len_func = pyobj.get_magic_method(&PyMagicMethod::Len, arena);
+
+    if let Some(len_func) = len_func {  // containers without `__bool__` are truthy when non-empty
+        let func_result = call_function_1_arg_min(&len_func, pyobj, &[], arena)?;
+
+        return Ok(expect_int(&func_result, arena)? != 0);
+    }
+
+    Ok(true)
 }
 
 fn convert_immutable_to_bool(immutable_obj: &PyImmutableObject, arena: &mut PyArena) -> Result<bool, PyException> {
@@ -44,7 +52,10 @@ fn convert_immutable_to_bool(immutable_obj: &PyImmutableObject, arena: &mut PyAr
         PyImmutableObject::Bool(ref value) => Ok(*value),
         PyImmutableObject::Int(ref value) => Ok(*value != 0),  // copy the value
         PyImmutableObject::Float(ref value) => Ok(*value != 0.0),
+        PyImmutableObject::Complex {real, imag} => Ok(real != 0.0 || imag != 0.0),
         PyImmutableObject::Str(ref value) => Ok(!value.is_empty()),
+        PyImmutableObject::Bytes(ref value) => Ok(!value.is_empty()),
+        PyImmutableObject::Slice {..} => Ok(true),
         PyImmutableObject::None => Ok(false),
         PyImmutableObject::NotImplemented => Err(arena.exceptions.type_error.instantiate("Evaluating NotImplemented in a boolean context is not possible".to_string())),
     }
@@ -52,9 +63,9 @@ fn convert_immutable_to_bool(immutable_obj: &PyImmutableObject, arena: &mut PyAr
 
 pub fn convert_pyobj_to_bool(pyobj: &PyObject, arena: &mut PyArena) -> Result<bool, PyException> {
     match *pyobj {
-        PyObject::Immutable(ref immutable) => convert_immutable_to_bool(immutable, arena),
-        PyObject::Mutable(ref mutable) => convert_mutable_to_bool(pyobj, &mutable.borrow(), arena),
-        PyObject::Internal(_) => {todo!()}
+        PyObject::None | PyObject::Bool(_) | PyObject::Int(_) | PyObject::Float(_) | PyObject::Immutable(_) => convert_immutable_to_bool(&pyobj.expect_immutable(), arena),
+        PyObject::Mutable(_) => convert_mutable_to_bool(pyobj, arena),
+        PyObject::Internal(_) => Ok(true),  // classes and builtin functions
         PyObject::IteratorFlag(_) => {panic!()}
     }
 }
@@ -75,17 +86,17 @@ pub fn bool__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObjec
 
 
 pub fn bool__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
-    let value = expect_bool(&pyself, arena)?;
+    let value = expect_bool(pyself, arena)?;
     Ok(PyObject::new_string(if value { "True".to_string() } else {"False".to_string()}))
 }
 
 pub fn bool__bool__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
-    let value = expect_bool(&pyself, arena)?;
+    let value = expect_bool(pyself, arena)?;
     Ok(arena.statics.get_bool(value).clone())
 }
 
 pub fn bool__int__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
-    let value = expect_bool(&pyself, arena)?;
+    let value = expect_bool(pyself, arena)?;
     Ok(PyObject::new_int(value as i64))
 }
 
@@ -0,0 +1,714 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::collect_iterable;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{BivariateFuncType, EmptyFuncReturnType, FuncReturnType, ManyArgFuncType, NewFuncType, PyImmutableObject, PyMutableObject, PyObject, UnaryFuncType, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, ManyArgFunc, NewFunc, UnaryFunc, VariadicFunc};
+use crate::builtins::types::bytearray::{new_bytearray, ByteArrayInstance};
+use crate::builtins::types::list::new_list;
+use crate::builtins::types::pyint::expect_int;
+use crate::builtins::types::slice::{adjust_slice_indices, expect_slice_bound, normalize_index, slice_indices};
+use crate::pyarena::PyArena;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    Utf8,
+    Latin1,
+    Ascii,
+}
+
+impl Encoding {
+    pub fn from_name(name: &str) -> Option<Encoding> {
+        match name.to_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" | "u8" => Some(Encoding::Utf8),
+            "latin-1" | "latin1" | "iso-8859-1" | "iso8859-1" | "l1" => Some(Encoding::Latin1),
+            "ascii" | "us-ascii" => Some(Encoding::Ascii),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Latin1 => "latin-1",
+            Encoding::Ascii => "ascii",
+        }
+    }
+}
+
+fn parse_codec_args(args: &[PyObject], arena: &mut PyArena) -> Result<(Encoding, String), PyException> {
+    let encoding_name = match args.first() {
+        Some(encoding) => expect_str_arg(encoding, "encoding", arena)?,
+        None => "utf-8".to_string(),
+    };
+    let errors = match args.get(1) {
+        Some(errors) => expect_str_arg(errors, "errors", arena)?,
+        None => "strict".to_string(),
+    };
+
+    let encoding = Encoding::from_name(&encoding_name).ok_or_else(|| arena.exceptions.lookup_error.instantiate(format!("unknown encoding: {encoding_name}")))?;
+
+    if !["strict", "ignore", "replace"].contains(&errors.as_str()) {
+        return Err(arena.exceptions.lookup_error.instantiate(format!("unknown error handler name '{errors}'")));
+    }
+
+    Ok((encoding, errors))
+}
+
+fn expect_str_arg(pyobj: &PyObject, arg_name: &str, arena: &mut PyArena) -> Result<String, PyException> {
+    match *pyobj.expect_immutable() {
+        PyImmutableObject::Str(ref value) => Ok(value.clone()),
+        ref value => {
+            let message = format!("argument '{arg_name}' must be str, not {}", value.get_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+pub fn encode_str(value: &str, encoding: Encoding, errors: &str, arena: &mut PyArena) -> Result<Vec<u8>, PyException> {
+    let limit = match encoding {
+        Encoding::Utf8 => return Ok(value.as_bytes().to_vec()),
+        Encoding::Latin1 => 0x100,
+        Encoding::Ascii => 0x80,
+    };
+
+    let mut encoded = Vec::with_capacity(value.len());
+
+    for (position, char) in value.chars().enumerate() {
+        if (char as u32) < limit {
+            encoded.push(char as u8);
+            continue;
+        }
+
+        match errors {
+            "ignore" => {},
+            "replace" => encoded.push(b'?'),
+            _ => {
+                let message = format!("'{}' codec can't encode character '\\u{:04x}' in position {position}: ordinal not in range({limit})", encoding.as_str(), char as u32);
+                return Err(arena.exceptions.unicode_encode_error.instantiate(message));
+            }
+        }
+    }
+
+    Ok(encoded)
+}
+
+pub fn decode_bytes(value: &[u8], encoding: Encoding, errors: &str, arena: &mut PyArena) -> Result<String, PyException> {
+    match encoding {
+        Encoding::Latin1 => Ok(value.iter().map(|byte| *byte as char).collect()),
+        Encoding::Ascii => {
+            let mut decoded = String::with_capacity(value.len());
+
+            for (position, byte) in value.iter().enumerate() {
+                if byte.is_ascii() {
+                    decoded.push(*byte as char);
+                    continue;
+                }
+
+                match errors {
+                    "ignore" => {},
+                    "replace" => decoded.push(char::REPLACEMENT_CHARACTER),
+                    _ => {
+                        let message = format!("'ascii' codec can't decode byte 0x{byte:02x} in position {position}: ordinal not in range(128)");
+                        return Err(arena.exceptions.unicode_decode_error.instantiate(message));
+                    }
+                }
+            }
+
+            Ok(decoded)
+        }
+        Encoding::Utf8 => {
+            match (std::str::from_utf8(value), errors) {
+                (Ok(decoded), _) => Ok(decoded.to_string()),
+                (Err(_), "replace") => Ok(String::from_utf8_lossy(value).into_owned()),
+                (Err(_), "ignore") => Ok(String::from_utf8_lossy(value).replace(char::REPLACEMENT_CHARACTER, "")),
+                (Err(error), _) => {
+                    let position = error.valid_up_to();
+                    let byte = value[position];
+
+                    let reason = if error.error_len().is_none() {
+                        "unexpected end of data"
+                    } else if (0x80..0xc2).contains(&byte) || byte > 0xf4 {
+                        "invalid start byte"
+                    } else {
+                        "invalid continuation byte"
+                    };
+
+                    let message = format!("'utf-8' codec can't decode byte 0x{byte:02x} in position {position}: {reason}");
+                    Err(arena.exceptions.unicode_decode_error.instantiate(message))
+                }
+            }
+        }
+    }
+}
+
+pub fn try_bytes_like(pyobj: &PyObject) -> Option<Vec<u8>> {  // copies the contents of a `bytes` or `bytearray`
+    match pyobj {
+        PyObject::Immutable(inner) => match **inner {
+            PyImmutableObject::Bytes(ref value) => Some(value.clone()),
+            _ => None,
+        },
+        PyObject::Mutable(inner) => {
+            let inner = inner.borrow();
+
+            match *inner {
+                PyMutableObject::Instance(ref instance) => instance.internal.downcast_ref::<ByteArrayInstance>().map(|bytearray| bytearray.value.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+pub fn expect_bytes_like(pyobj: &PyObject, arena: &mut PyArena) -> Result<Vec<u8>, PyException> {
+    try_bytes_like(pyobj).ok_or_else(|| {
+        let message = format!("a bytes-like object is required, not '{}'", pyobj.clone_class(arena).get_name());
+        arena.exceptions.type_error.instantiate(message)
+    })
+}
+
+pub fn expect_bytes_like_promotion(pyobj: &PyObject, arena: &mut PyArena) -> Result<Vec<u8>, PyException> {
+    try_bytes_like(pyobj).ok_or_else(|| arena.exceptions.not_implemented_error.empty())
+}
+
+fn expect_byte(pyobj: &PyObject, arena: &mut PyArena) -> Result<u8, PyException> {
+    let value = match *pyobj.expect_immutable() {
+        PyImmutableObject::Bool(value) => value as i64,
+        _ => expect_int(pyobj, arena)?,
+    };
+
+    u8::try_from(value).map_err(|_error| arena.exceptions.value_error.instantiate("byte must be in range(0, 256)".to_string()))
+}
+
+fn is_bytearray(pyobj: &PyObject) -> bool {
+    matches!(pyobj, PyObject::Mutable(_))
+}
+
+fn new_same_type(pyself: &PyObject, value: Vec<u8>, arena: &mut PyArena) -> PyObject {  // bytes methods return bytes, bytearray methods return bytearray
+    if is_bytearray(pyself) {
+        new_bytearray(value, arena)
+    } else {
+        PyObject::new_bytes(value)
+    }
+}
+
+pub fn bytes_from_args(args: &[PyObject], arena: &mut PyArena) -> Result<Vec<u8>, PyException> {
+    let Some(source) = args.first() else {
+        return Ok(vec![]);
+    };
+
+    if let Some(inner) = source.as_immutable() {
+        match *inner {
+            PyImmutableObject::Str(ref value) => {
+                if args.len() < 2 {
+                    return Err(arena.exceptions.type_error.instantiate("string argument without an encoding".to_string()));
+                }
+                let (encoding, errors) = parse_codec_args(&args[1..], arena)?;
+
+                return encode_str(value, encoding, &errors, arena);
+            }
+            PyImmutableObject::Int(count) => {
+                if count < 0 {
+                    return Err(arena.exceptions.value_error.instantiate("negative count".to_string()));
+                }
+                return Ok(vec![0; count as usize]);
+            }
+            _ => {}
+        }
+    }
+
+    if args.len() > 1 {
+        return Err(arena.exceptions.type_error.instantiate("encoding without a string argument".to_string()));
+    }
+
+    if let Some(value) = try_bytes_like(source) {
+        return Ok(value);
+    }
+
+    let items = collect_iterable(source, arena).map_err(|_error| {
+        let message = format!("cannot convert '{}' object to bytes", source.clone_class(arena).get_name());
+        arena.exceptions.type_error.instantiate(message)
+    })?;
+
+    items.iter().map(|item| expect_byte(item, arena)).collect()
+}
+
+pub fn format_bytes_literal(value: &[u8]) -> String {
+    let quote = if value.contains(&b'\'') && !value.contains(&b'"') { '"' } else { '\'' };
+
+    let mut literal = format!("b{quote}");
+
+    for byte in value {
+        match *byte {
+            b'\\' => literal.push_str("\\\\"),
+            b'\t' => literal.push_str("\\t"),
+            b'\n' => literal.push_str("\\n"),
+            b'\r' => literal.push_str("\\r"),
+            byte if byte as char == quote => {
+                literal.push('\\');
+                literal.push(quote);
+            }
+            byte if (0x20..0x7f).contains(&byte) => literal.push(byte as char),
+            byte => literal.push_str(&format!("\\x{byte:02x}")),
+        }
+    }
+
+    literal.push(quote);
+    literal
+}
+
+pub fn hash_bytes(value: &[u8]) -> i64 {  // SipHash-1-3 with a zeroed key, the same as CPython run with `PYTHONHASHSEED=0`
+    if value.is_empty() {
+        return 0;
+    }
+
+    let (mut v0, mut v1, mut v2, mut v3): (u64, u64, u64, u64) = (0x736f6d6570736575, 0x646f72616e646f6d, 0x6c7967656e657261, 0x7465646279746573);
+
+    fn sip_round(v0: &mut u64, v1: &mut u64, v2: &mut u64, v3: &mut u64) {
+        *v0 = v0.wrapping_add(*v1); *v1 = v1.rotate_left(13); *v1 ^= *v0; *v0 = v0.rotate_left(32);
+        *v2 = v2.wrapping_add(*v3); *v3 = v3.rotate_left(16); *v3 ^= *v2;
+        *v0 = v0.wrapping_add(*v3); *v3 = v3.rotate_left(21); *v3 ^= *v0;
+        *v2 = v2.wrapping_add(*v1); *v1 = v1.rotate_left(17); *v1 ^= *v2; *v2 = v2.rotate_left(32);
+    }
+
+    let mut chunks = value.chunks_exact(8);
+
+    for chunk in &mut chunks {
+        let message = u64::from_le_bytes(chunk.try_into().expect("chunks are 8 bytes long"));
+        v3 ^= message;
+        sip_round(&mut v0, &mut v1, &mut v2, &mut v3);
+        v0 ^= message;
+    }
+
+    let mut last = (value.len() as u64) << 56;
+    for (i, byte) in chunks.remainder().iter().enumerate() {
+        last |= (*byte as u64) << (8 * i);
+    }
+
+    v3 ^= last;
+    sip_round(&mut v0, &mut v1, &mut v2, &mut v3);
+    v0 ^= last;
+
+    v2 ^= 0xff;
+    for _ in 0..3 {
+        sip_round(&mut v0, &mut v1, &mut v2, &mut v3);
+    }
+
+    let hash = (v0 ^ v1 ^ v2 ^ v3) as i64;
+
+    if hash == -1 { -2 } else { hash }
+}
+
+pub fn bytes__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    Ok(PyObject::new_bytes(bytes_from_args(pyargs, arena)?))
+}
+
+pub fn bytes__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+
+    Ok(PyObject::new_string(format_bytes_literal(&value)))
+}
+
+pub fn bytes__hash__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+    Ok(PyObject::new_int(hash_bytes(&value)))
+}
+
+pub fn bytes__len__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_int(expect_bytes_like(pyself, arena)?.len() as i64))
+}
+
+pub fn bytes__getitem__(arena: &mut PyArena, pyself: &PyObject, key: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+
+    match *key.expect_immutable() {
+        PyImmutableObject::Int(index) => {
+            let index = normalize_index(index, value.len()).ok_or_else(|| arena.exceptions.index_error.instantiate("index out of range".to_string()))?;
+            Ok(PyObject::new_int(value[index] as i64))
+        }
+        PyImmutableObject::Bool(index) => Ok(PyObject::new_int(*value.get(index as usize).ok_or_else(|| arena.exceptions.index_error.instantiate("index out of range".to_string()))? as i64)),
+        ref slice @ PyImmutableObject::Slice {..} => {
+            let sliced = slice_indices(slice, value.len(), arena)?.into_iter().map(|i| value[i]).collect();
+            Ok(new_same_type(pyself, sliced, arena))
+        }
+        ref other => {
+            let message = format!("byte indices must be integers or slices, not {}", other.get_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+pub fn bytes__add__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let mut value = expect_bytes_like(pyself, arena)?;
+    let other_value = expect_bytes_like_promotion(other, arena)?;
+
+    value.extend(other_value);
+
+    Ok(new_same_type(pyself, value, arena))
+}
+
+pub fn bytes__mul__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {  // also `__rmul__`, the count is always `other`
+    let value = expect_bytes_like(pyself, arena)?;
+
+    let count = match other.as_immutable().as_deref() {
+        Some(PyImmutableObject::Int(count)) => *count,
+        Some(PyImmutableObject::Bool(value)) => *value as i64,
+        _ => return Err(arena.exceptions.not_implemented_error.empty()),
+    };
+
+    Ok(new_same_type(pyself, value.repeat(count.max(0) as usize), arena))
+}
+
+pub fn bytes__eq__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+    let other_value = expect_bytes_like_promotion(other, arena)?;
+
+    Ok(arena.statics.get_bool(value == other_value).clone())
+}
+
+pub fn bytes__ne__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+    let other_value = expect_bytes_like_promotion(other, arena)?;
+
+    Ok(arena.statics.get_bool(value != other_value).clone())
+}
+
+pub fn bytes__lt__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+    let other_value = expect_bytes_like_promotion(other, arena)?;
+
+    Ok(arena.statics.get_bool(value < other_value).clone())
+}
+
+pub fn bytes__le__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+    let other_value = expect_bytes_like_promotion(other, arena)?;
+
+    Ok(arena.statics.get_bool(value <= other_value).clone())
+}
+
+pub fn bytes__gt__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+    let other_value = expect_bytes_like_promotion(other, arena)?;
+
+    Ok(arena.statics.get_bool(value > other_value).clone())
+}
+
+pub fn bytes__ge__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+    let other_value = expect_bytes_like_promotion(other, arena)?;
+
+    Ok(arena.statics.get_bool(value >= other_value).clone())
+}
+
+pub fn bytes__contains__(arena: &mut PyArena, pyself: &PyObject, item: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+
+    let contains = match try_bytes_like(item) {
+        Some(sub) => find_sub(&value, &sub, 0, value.len()).is_some(),
+        None => value.contains(&expect_byte(item, arena)?),
+    };
+
+    Ok(arena.statics.get_bool(contains).clone())
+}
+
+pub fn bytes__iter__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+
+    Ok(PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.bytes_iterator_class.clone(),
+        Box::new(BytesIteratorInstance { value, index: 0 })
+    ))))
+}
+
+fn find_sub(value: &[u8], sub: &[u8], start: usize, end: usize) -> Option<usize> {
+    if start > end || end > value.len() || sub.len() > end - start {
+        return None;
+    }
+
+    (start..=end - sub.len()).find(|i| &value[*i..*i + sub.len()] == sub)
+}
+
+pub fn bytes_find(arena: &mut PyArena, pyself: &PyObject, args: &[PyObject]) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+
+    let sub = match args.first() {
+        Some(sub) => match try_bytes_like(sub) {
+            Some(sub) => sub,
+            None => vec![expect_byte(sub, arena)?],
+        },
+        None => return Err(arena.exceptions.type_error.instantiate("find expected at least 1 argument, got 0".to_string())),
+    };
+
+    let start = match args.get(1) { Some(start) => expect_slice_bound(start, arena)?, None => None };
+    let end = match args.get(2) { Some(end) => expect_slice_bound(end, arena)?, None => None };
+
+    let (start, end, _step, _length) = adjust_slice_indices(start, end, None, value.len(), arena)?;
+
+    let index = find_sub(&value, &sub, start as usize, end.max(start) as usize);
+
+    Ok(PyObject::new_int(index.map(|index| index as i64).unwrap_or(-1)))
+}
+
+pub fn bytes_split(arena: &mut PyArena, pyself: &PyObject, args: &[PyObject]) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+
+    let separator = match args.first() {
+        Some(separator) if matches!(*separator.expect_immutable(), PyImmutableObject::None) => None,
+        Some(separator) => Some(expect_bytes_like(separator, arena)?),
+        None => None,
+    };
+    let max_split = match args.get(1) {
+        Some(max_split) => expect_int(max_split, arena)?,
+        None => -1,
+    };
+    let max_split = if max_split < 0 { usize::MAX } else { max_split as usize };
+
+    let mut parts: Vec<Vec<u8>> = vec![];
+
+    match separator {
+        Some(separator) => {
+            if separator.is_empty() {
+                return Err(arena.exceptions.value_error.instantiate("empty separator".to_string()));
+            }
+
+            let mut start = 0;
+            while parts.len() < max_split {
+                let Some(index) = find_sub(&value, &separator, start, value.len()) else {
+                    break;
+                };
+                parts.push(value[start..index].to_vec());
+                start = index + separator.len();
+            }
+            parts.push(value[start..].to_vec());
+        }
+        None => {  // split on runs of ASCII whitespace, never producing empty parts
+            let mut index = 0;
+            loop {
+                while index < value.len() && value[index].is_ascii_whitespace() {
+                    index += 1;
+                }
+                if index == value.len() {
+                    break;
+                }
+
+                if parts.len() == max_split {
+                    let mut end = value.len();
+                    while value[end - 1].is_ascii_whitespace() {
+                        end -= 1;
+                    }
+                    parts.push(value[index..end].to_vec());
+                    break;
+                }
+
+                let start = index;
+                while index < value.len() && !value[index].is_ascii_whitespace() {
+                    index += 1;
+                }
+                parts.push(value[start..index].to_vec());
+            }
+        }
+    }
+
+    let parts = parts.into_iter().map(|part| new_same_type(pyself, part, arena)).collect();
+
+    Ok(new_list(parts, arena))
+}
+
+pub fn bytes_join(arena: &mut PyArena, pyself: &PyObject, iterable: &PyObject) -> FuncReturnType {
+    let separator = expect_bytes_like(pyself, arena)?;
+    let items = collect_iterable(iterable, arena)?;
+
+    let mut joined = vec![];
+
+    for (i, item) in items.iter().enumerate() {
+        let Some(item) = try_bytes_like(item) else {
+            let message = format!("sequence item {i}: expected a bytes-like object, {} found", item.clone_class(arena).get_name());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        };
+
+        if i > 0 {
+            joined.extend_from_slice(&separator);
+        }
+        joined.extend(item);
+    }
+
+    Ok(new_same_type(pyself, joined, arena))
+}
+
+pub fn bytes_hex(arena: &mut PyArena, pyself: &PyObject, args: &[PyObject]) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+
+    let separator = match args.first() {
+        Some(separator) => {
+            let separator = match *separator.expect_immutable() {
+                PyImmutableObject::Str(ref separator) => separator.clone(),
+                _ => String::from_utf8_lossy(&expect_bytes_like(separator, arena)?).into_owned(),
+            };
+
+            if separator.chars().count() != 1 {
+                return Err(arena.exceptions.value_error.instantiate("sep must be length 1.".to_string()));
+            }
+            Some(separator)
+        }
+        None => None,
+    };
+    let bytes_per_separator = match args.get(1) {
+        Some(bytes_per_separator) => expect_int(bytes_per_separator, arena)?,
+        None => 1,
+    };
+
+    let digits: Vec<String> = value.iter().map(|byte| format!("{byte:02x}")).collect();
+
+    let Some(separator) = separator.filter(|_separator| bytes_per_separator != 0 && !digits.is_empty()) else {
+        return Ok(PyObject::new_string(digits.concat()));
+    };
+
+    // a positive group size counts from the right, a negative one from the left
+    let group_size = bytes_per_separator.unsigned_abs() as usize;
+    let first_group = if bytes_per_separator > 0 && !digits.len().is_multiple_of(group_size) { digits.len() % group_size } else { group_size };
+
+    let mut groups = vec![digits[..first_group.min(digits.len())].concat()];
+    groups.extend(digits[first_group.min(digits.len())..].chunks(group_size).map(|chunk| chunk.concat()));
+
+    Ok(PyObject::new_string(groups.join(&separator)))
+}
+
+pub fn parse_fromhex(arena: &mut PyArena, args: &[PyObject]) -> Result<Vec<u8>, PyException> {
+    let [ref hex_string] = args[..] else {
+        let message = format!("fromhex() takes exactly one argument ({} given)", args.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let hex_string = expect_str_arg(hex_string, "string", arena)?;
+    let chars: Vec<char> = hex_string.chars().collect();
+
+    let mut value = vec![];
+    let mut position = 0;
+
+    while position < chars.len() {
+        if chars[position].is_whitespace() {
+            position += 1;
+            continue;
+        }
+
+        let high = chars[position].to_digit(16);
+        let low = chars.get(position + 1).and_then(|char| char.to_digit(16));
+
+        match (high, low) {
+            (Some(high), Some(low)) => value.push((high * 16 + low) as u8),
+            (None, _) => return Err(arena.exceptions.value_error.instantiate(format!("non-hexadecimal number found in fromhex() arg at position {position}"))),
+            (Some(_), None) => return Err(arena.exceptions.value_error.instantiate(format!("non-hexadecimal number found in fromhex() arg at position {}", position + 1))),
+        }
+
+        position += 2;
+    }
+
+    Ok(value)
+}
+
+pub fn bytes_fromhex(arena: &mut PyArena, args: &[PyObject]) -> FuncReturnType {
+    Ok(PyObject::new_bytes(parse_fromhex(arena, args)?))
+}
+
+pub fn bytes_decode(arena: &mut PyArena, pyself: &PyObject, args: &[PyObject]) -> FuncReturnType {
+    let value = expect_bytes_like(pyself, arena)?;
+    let (encoding, errors) = parse_codec_args(args, arena)?;
+
+    Ok(PyObject::new_string(decode_bytes(&value, encoding, &errors, arena)?))
+}
+
+pub fn bytes_methods() -> Vec<(String, PyObject)> {  // shared by `bytes` and `bytearray`
+    vec![
+        ("hex".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(bytes_hex as VariadicFuncType))))),
+        ("decode".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(bytes_decode as VariadicFuncType))))),
+        ("join".to_string(), PyObject::new_internal_func(Rc::new(BivariateFunc(&(bytes_join as BivariateFuncType))))),
+        ("split".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(bytes_split as VariadicFuncType))))),
+        ("find".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(bytes_find as VariadicFuncType))))),
+    ]
+}
+
+pub fn get_bytes_class(object_class: Rc<PyClass>) -> PyClass {
+    let mut attributes: AHashMap<String, PyObject> = bytes_methods().into_iter().collect();
+    attributes.insert("fromhex".to_string(), PyObject::new_internal_func(Rc::new(ManyArgFunc(&(bytes_fromhex as ManyArgFuncType)))));
+
+    PyClass::Internal {
+        name: "bytes".to_string(),
+        super_classes: vec![object_class],
+        attributes,
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(bytes__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(bytes__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(bytes__hash__ as UnaryFuncType)))),
+
+            __add__: Some(Rc::new(BivariateFunc(&(bytes__add__ as BivariateFuncType)))),
+            __mul__: Some(Rc::new(BivariateFunc(&(bytes__mul__ as BivariateFuncType)))),
+            __rmul__: Some(Rc::new(BivariateFunc(&(bytes__mul__ as BivariateFuncType)))),
+
+            __iter__: Some(Rc::new(UnaryFunc(&(bytes__iter__ as UnaryFuncType)))),
+
+            __eq__: Some(Rc::new(BivariateFunc(&(bytes__eq__ as BivariateFuncType)))),
+            __ne__: Some(Rc::new(BivariateFunc(&(bytes__ne__ as BivariateFuncType)))),
+            __lt__: Some(Rc::new(BivariateFunc(&(bytes__lt__ as BivariateFuncType)))),
+            __le__: Some(Rc::new(BivariateFunc(&(bytes__le__ as BivariateFuncType)))),
+            __gt__: Some(Rc::new(BivariateFunc(&(bytes__gt__ as BivariateFuncType)))),
+            __ge__: Some(Rc::new(BivariateFunc(&(bytes__ge__ as BivariateFuncType)))),
+            __contains__: Some(Rc::new(BivariateFunc(&(bytes__contains__ as BivariateFuncType)))),
+
+            __len__: Some(Rc::new(UnaryFunc(&(bytes__len__ as UnaryFuncType)))),
+            __getitem__: Some(Rc::new(BivariateFunc(&(bytes__getitem__ as BivariateFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
+
+#[derive(Debug)]
+struct BytesIteratorInstance {
+    value: Vec<u8>,
+    index: usize,
+}
+
+impl PyInstanceInternal for BytesIteratorInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, _key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        None
+    }
+}
+
+pub fn bytes_iterator__next__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let mut pyself = pyself.expect_mutable().borrow_mut();
+    let instance = pyself.expect_instance_mut();
+
+    if let Some(bytes_iterator_internal) = instance.internal.downcast_mut::<BytesIteratorInstance>() {
+        let Some(byte) = bytes_iterator_internal.value.get(bytes_iterator_internal.index) else {
+            return Ok(PyObject::stop_iteration());
+        };
+
+        bytes_iterator_internal.index += 1;
+
+        Ok(PyObject::new_int(*byte as i64))
+    } else {
+        panic!("instance is not BytesIteratorInstance, its {:?}", instance)
+    }
+}
+
+pub fn get_bytes_iterator_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {  // Hidden class
+        name: "bytes_iterator".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __next__: Some(Rc::new(UnaryFunc(&(bytes_iterator__next__ as UnaryFuncType)))),
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
@@ -0,0 +1,408 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::call_function_1_arg_min;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethod, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{BivariateFuncType, FuncReturnType, NewFuncType, PyImmutableObject, PyObject, UnaryFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, UnaryFunc};
+use crate::builtins::types::pyfloat::{convert_mutable_to_float, hash_float};
+use crate::pyarena::PyArena;
+
+pub type Complex = (f64, f64);
+
+pub fn expect_complex(pyobj: &PyObject, arena: &mut PyArena) -> Result<Complex, PyException> {
+    match pyobj.as_immutable().as_deref() {
+        Some(PyImmutableObject::Complex {real, imag}) => {Ok((*real, *imag))}
+        _ => {
+            let message = format!("'{}' object cannot be interpreted as a complex", pyobj.clone_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        },
+    }
+}
+
+pub fn expect_complex_promotion(pyobj: &PyObject, arena: &mut PyArena) -> Result<Complex, PyException> {
+    match pyobj.as_immutable().as_deref() {
+        Some(PyImmutableObject::Complex {real, imag}) => {Ok((*real, *imag))}
+        Some(PyImmutableObject::Float(value)) => {Ok((*value, 0.0))}
+        Some(PyImmutableObject::Int(value)) => {Ok((*value as f64, 0.0))}
+        Some(PyImmutableObject::Bool(value)) => {Ok((if *value {1.0} else {0.0}, 0.0))}
+        _ => {
+            Err(arena.exceptions.not_implemented_error.empty())
+        },
+    }
+}
+
+pub fn convert_mutable_to_complex(pyobj: &PyObject, arena: &mut PyArena) -> Result<Complex, PyException> {
+    let complex_func = pyobj.get_magic_method(&PyMagicMethod::Complex, arena);
+
+    if let Some(complex_func) = complex_func {
+        let func_result = call_function_1_arg_min(&complex_func, pyobj, &[], arena)?;
+
+        let complex_result = expect_complex(&func_result, arena);
+
+        return complex_result.map_err(|_error| {
+            let message = format!("{}.__complex__ returned non-complex (type {})", pyobj.clone_class(arena).get_name(), func_result.clone_class(arena).get_name());
+            arena.exceptions.type_error.instantiate(message)
+        });
+    }
+
+    Ok((convert_mutable_to_float(pyobj, arena)?, 0.0))
+}
+
+fn parse_complex_component(value: &str) -> Option<f64> {
+    if value.starts_with(['+', '-']) && value[1..].starts_with(['+', '-']) {
+        return None;
+    }
+    value.parse::<f64>().ok()
+}
+
+fn parse_complex_str(value: &str) -> Option<Complex> {
+    let value = value.trim();
+    let value = value.strip_prefix('(').and_then(|value| value.strip_suffix(')')).unwrap_or(value).trim();
+
+    let Some(body) = value.strip_suffix(['j', 'J']) else {
+        return Some((parse_complex_component(value)?, 0.0));
+    };
+
+    // the imaginary part starts at the last sign that isn't part of an exponent
+    let split = body.char_indices()
+        .skip(1)
+        .filter(|(i, c)| matches!(c, '+' | '-') && !matches!(body.as_bytes()[i - 1], b'e' | b'E'))
+        .map(|(i, _c)| i)
+        .last();
+
+    let (real_str, imag_str) = match split {
+        Some(i) => (&body[..i], &body[i..]),
+        None => ("", body),
+    };
+
+    let real = if real_str.is_empty() { 0.0 } else { parse_complex_component(real_str)? };
+    let imag = match imag_str {
+        "" | "+" => 1.0,
+        "-" => -1.0,
+        imag_str => parse_complex_component(imag_str)?,
+    };
+
+    Some((real, imag))
+}
+
+fn convert_pyobj_to_complex(pyobj: &PyObject, arena: &mut PyArena) -> Result<Complex, PyException> {
+    match pyobj {
+        PyObject::Mutable(_) => convert_mutable_to_complex(pyobj, arena),
+        PyObject::None | PyObject::Bool(_) | PyObject::Int(_) | PyObject::Float(_) | PyObject::Immutable(_) => {
+            if let PyImmutableObject::Str(ref value) = *pyobj.expect_immutable() {
+                return parse_complex_str(value).ok_or_else(|| arena.exceptions.value_error.instantiate("complex() arg is a malformed string".to_string()));
+            }
+
+            expect_complex_promotion(pyobj, arena).map_err(|_error| {
+                let message = format!("complex() first argument must be a string or a number, not '{}'", pyobj.clone_class(arena).get_name());
+                arena.exceptions.type_error.instantiate(message)
+            })
+        },
+        value => {
+            let message = format!("complex() first argument must be a string or a number, not '{}'", value.clone_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+pub fn parse_complex_op_func_params(pyself: &PyObject, other: &PyObject, arena: &mut PyArena) -> Result<(Complex, Complex), PyException> {
+    let self_value = expect_complex(pyself, arena)?;
+    let other_value = expect_complex_promotion(other, arena)?;
+    Ok((self_value, other_value))
+}
+
+fn complex_mul((a, b): Complex, (c, d): Complex) -> Complex {
+    (a * c - b * d, a * d + b * c)
+}
+
+fn complex_div((a, b): Complex, (c, d): Complex) -> Option<Complex> {  // Smith's algorithm, like CPython's `_Py_c_quot`
+    let abs_c = c.abs();
+    let abs_d = d.abs();
+
+    if abs_c >= abs_d {
+        if abs_c == 0.0 {
+            return None;
+        }
+        let ratio = d / c;
+        let denominator = c + d * ratio;
+        Some(((a + b * ratio) / denominator, (b - a * ratio) / denominator))
+    } else if abs_d >= abs_c {
+        let ratio = c / d;
+        let denominator = c * ratio + d;
+        Some(((a * ratio + b) / denominator, (b * ratio - a) / denominator))
+    } else {  // at least one of c and d is NaN
+        Some((f64::NAN, f64::NAN))
+    }
+}
+
+fn complex_pow_unsigned(base: Complex, mut exponent: u64) -> Complex {
+    let mut result = (1.0, 0.0);
+    let mut power = base;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = complex_mul(result, power);
+        }
+        power = complex_mul(power, power);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+pub fn complex_pow(base: Complex, exponent: Complex, arena: &mut PyArena) -> Result<Complex, PyException> {
+    let (a, b) = base;
+    let (c, d) = exponent;
+
+    if c == 0.0 && d == 0.0 {
+        return Ok((1.0, 0.0));
+    }
+
+    if a == 0.0 && b == 0.0 {
+        if d != 0.0 || c < 0.0 {
+            return Err(arena.exceptions.zero_division_error.instantiate("0.0 to a negative or complex power".to_string()));
+        }
+        return Ok((0.0, 0.0));
+    }
+
+    let result = if d == 0.0 && c == c.trunc() && c.abs() <= 100.0 {  // small integer powers are exact, like CPython's `c_powi`
+        let powered = complex_pow_unsigned(base, c.abs() as u64);
+
+        if c < 0.0 {
+            complex_div((1.0, 0.0), powered).unwrap_or((f64::INFINITY, f64::INFINITY))
+        } else {
+            powered
+        }
+    } else {
+        let magnitude = a.hypot(b);
+        let angle = b.atan2(a);
+
+        let mut length = magnitude.powf(c);
+        let mut phase = angle * c;
+
+        if d != 0.0 {
+            length /= (angle * d).exp();
+            phase += d * magnitude.ln();
+        }
+
+        (length * phase.cos(), length * phase.sin())
+    };
+
+    if (result.0.is_infinite() || result.1.is_infinite()) && [a, b, c, d].iter().all(|value| value.is_finite()) {
+        return Err(arena.exceptions.overflow_error.instantiate("complex exponentiation".to_string()));
+    }
+
+    Ok(result)
+}
+
+fn format_complex_component(value: f64) -> String {
+    if value.is_nan() {
+        return "nan".to_string();
+    } else if value.is_infinite() {
+        return if value > 0.0 { "inf".to_string() } else { "-inf".to_string() };
+    }
+
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-4..1e16).contains(&magnitude) {
+        let formatted = format!("{:e}", value);  // Rust writes `1e20`, Python writes `1e+20`
+        return match formatted.split_once('e') {
+            Some((mantissa, exponent)) if !exponent.starts_with('-') => format!("{mantissa}e+{exponent:0>2}"),
+            Some((mantissa, exponent)) => format!("{mantissa}e-{:0>2}", &exponent[1..]),
+            None => formatted,
+        };
+    }
+
+    value.to_string()
+}
+
+pub fn complex__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    if pyargs.len() > 2 {
+        let message = format!("complex() takes at most 2 arguments ({} given)", pyargs.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let (mut real, mut imag) = match pyargs.first() {
+        Some(value) => convert_pyobj_to_complex(value, arena)?,
+        None => (0.0, 0.0),
+    };
+
+    if let Some(second) = pyargs.get(1) {
+        let first_is_str = matches!(pyargs[0], PyObject::Immutable(ref inner) if matches!(**inner, PyImmutableObject::Str(_)));
+        let second_is_str = matches!(second, PyObject::Immutable(ref inner) if matches!(**inner, PyImmutableObject::Str(_)));
+
+        if first_is_str {
+            return Err(arena.exceptions.type_error.instantiate("complex() can't take second arg if first is a string".to_string()));
+        } else if second_is_str {
+            return Err(arena.exceptions.type_error.instantiate("complex() second arg can't be a string".to_string()));
+        }
+
+        let (second_real, second_imag) = convert_pyobj_to_complex(second, arena)?;
+
+        // complex(a, b) == a + b*1j, even when `b` is itself complex
+        real -= second_imag;
+        imag += second_real;
+    }
+
+    Ok(PyObject::new_complex(real, imag))
+}
+
+pub fn complex__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let (real, imag) = expect_complex(pyself, arena)?;
+
+    let imag_str = format_complex_component(imag);
+
+    if real == 0.0 && real.is_sign_positive() {
+        return Ok(PyObject::new_string(format!("{imag_str}j")));
+    }
+
+    let sign = if imag.is_sign_negative() && !imag.is_nan() { "" } else { "+" };
+
+    Ok(PyObject::new_string(format!("({}{sign}{imag_str}j)", format_complex_component(real))))
+}
+
+pub fn complex__hash__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let (real, imag) = expect_complex(pyself, arena)?;
+
+    let hash = (hash_float(real) as u64).wrapping_add(1000003u64.wrapping_mul(hash_float(imag) as u64)) as i64;
+
+    Ok(PyObject::new_int(if hash == -1 { -2 } else { hash }))
+}
+
+pub fn complex__bool__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let (real, imag) = expect_complex(pyself, arena)?;
+
+    Ok(arena.statics.get_bool(real != 0.0 || imag != 0.0).clone())
+}
+
+pub fn complex__abs__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let (real, imag) = expect_complex(pyself, arena)?;
+
+    let magnitude = real.hypot(imag);
+
+    if magnitude.is_infinite() && real.is_finite() && imag.is_finite() {
+        return Err(arena.exceptions.overflow_error.instantiate("absolute value too large".to_string()));
+    }
+
+    Ok(PyObject::new_float(magnitude))
+}
+
+pub fn complex__neg__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let (real, imag) = expect_complex(pyself, arena)?;
+    Ok(PyObject::new_complex(-real, -imag))
+}
+
+pub fn complex__pos__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let (real, imag) = expect_complex(pyself, arena)?;
+    Ok(PyObject::new_complex(real, imag))
+}
+
+pub fn complex_conjugate(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let (real, imag) = expect_complex(pyself, arena)?;
+
+    Ok(PyObject::new_complex(real, -imag))
+}
+
+pub fn complex__add__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let ((a, b), (c, d)) = parse_complex_op_func_params(pyself, other, arena)?;
+
+    Ok(PyObject::new_complex(a + c, b + d))
+}
+
+pub fn complex__sub__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let ((a, b), (c, d)) = parse_complex_op_func_params(pyself, other, arena)?;
+
+    Ok(PyObject::new_complex(a - c, b - d))
+}
+
+pub fn complex__rsub__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let ((a, b), (c, d)) = parse_complex_op_func_params(pyself, other, arena)?;
+
+    Ok(PyObject::new_complex(c - a, d - b))
+}
+
+pub fn complex__mul__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_complex_op_func_params(pyself, other, arena)?;
+
+    let (real, imag) = complex_mul(self_value, other_value);
+    Ok(PyObject::new_complex(real, imag))
+}
+
+pub fn complex__truediv__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_complex_op_func_params(pyself, other, arena)?;
+
+    let (real, imag) = complex_div(self_value, other_value).ok_or_else(|| arena.exceptions.zero_division_error.instantiate("complex division by zero".to_string()))?;
+    Ok(PyObject::new_complex(real, imag))
+}
+
+pub fn complex__rtruediv__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_complex_op_func_params(pyself, other, arena)?;
+
+    let (real, imag) = complex_div(other_value, self_value).ok_or_else(|| arena.exceptions.zero_division_error.instantiate("complex division by zero".to_string()))?;
+    Ok(PyObject::new_complex(real, imag))
+}
+
+pub fn complex__pow__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_complex_op_func_params(pyself, other, arena)?;
+
+    let (real, imag) = complex_pow(self_value, other_value, arena)?;
+    Ok(PyObject::new_complex(real, imag))
+}
+
+pub fn complex__rpow__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_complex_op_func_params(pyself, other, arena)?;
+
+    let (real, imag) = complex_pow(other_value, self_value, arena)?;
+    Ok(PyObject::new_complex(real, imag))
+}
+
+pub fn complex__eq__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_complex_op_func_params(pyself, other, arena)?;
+
+    Ok(arena.statics.get_bool(self_value == other_value).clone())
+}
+
+pub fn complex__ne__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_complex_op_func_params(pyself, other, arena)?;
+
+    Ok(arena.statics.get_bool(self_value != other_value).clone())
+}
+
+pub fn get_complex_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "complex".to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("conjugate".to_string(), PyObject::new_internal_func(Rc::new(UnaryFunc(&(complex_conjugate as UnaryFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(complex__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(complex__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(complex__hash__ as UnaryFuncType)))),
+            __bool__: Some(Rc::new(UnaryFunc(&(complex__bool__ as UnaryFuncType)))),
+
+            __add__: Some(Rc::new(BivariateFunc(&(complex__add__ as BivariateFuncType)))),
+            __radd__: Some(Rc::new(BivariateFunc(&(complex__add__ as BivariateFuncType)))),
+            __sub__: Some(Rc::new(BivariateFunc(&(complex__sub__ as BivariateFuncType)))),
+            __rsub__: Some(Rc::new(BivariateFunc(&(complex__rsub__ as BivariateFuncType)))),
+            __mul__: Some(Rc::new(BivariateFunc(&(complex__mul__ as BivariateFuncType)))),
+            __rmul__: Some(Rc::new(BivariateFunc(&(complex__mul__ as BivariateFuncType)))),
+            __truediv__: Some(Rc::new(BivariateFunc(&(complex__truediv__ as BivariateFuncType)))),
+            __rtruediv__: Some(Rc::new(BivariateFunc(&(complex__rtruediv__ as BivariateFuncType)))),
+            __pow__: Some(Rc::new(BivariateFunc(&(complex__pow__ as BivariateFuncType)))),
+            __rpow__: Some(Rc::new(BivariateFunc(&(complex__rpow__ as BivariateFuncType)))),
+
+            __abs__: Some(Rc::new(UnaryFunc(&(complex__abs__ as UnaryFuncType)))),
+            __neg__: Some(Rc::new(UnaryFunc(&(complex__neg__ as UnaryFuncType)))),
+            __pos__: Some(Rc::new(UnaryFunc(&(complex__pos__ as UnaryFuncType)))),
+
+            __eq__: Some(Rc::new(BivariateFunc(&(complex__eq__ as BivariateFuncType)))),
+            __ne__: Some(Rc::new(BivariateFunc(&(complex__ne__ as BivariateFuncType)))),
+
+            ..py_magic_methods_defaults()
+        }),
+    }.create()
+}
@@ -1,37 +1,40 @@
 #![allow(non_snake_case)]
+use std::cmp::Ordering;
 use std::rc::Rc;
 use ahash::AHashMap;
 use crate::builtins::function_utils::call_function_1_arg_min;
 use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethod, PyMagicMethods};
 use crate::builtins::structure::pyclass::PyClass;
 use crate::builtins::structure::pyexception::PyException;
-use crate::builtins::structure::pyobject::{BivariateFuncType, FuncReturnType, NewFuncType, PyImmutableObject, PyMutableObject, PyObject, UnaryFuncType};
+use crate::builtins::structure::pyobject::{BivariateFuncType, FuncReturnType, NewFuncType, PyImmutableObject, PyObject, UnaryFuncType};
 use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, UnaryFunc};
+use crate::builtins::types::pycomplex::complex_pow;
+use crate::builtins::types::str::format_str_literal;
 use crate::pyarena::PyArena;
 
 pub fn expect_float(pyobj: &PyObject, arena: &mut PyArena) -> Result<f64, PyException> {
-    match **pyobj.expect_immutable() {
-        PyImmutableObject::Float(value) => {Ok(value)}
-        ref value => {
-            let message = format!("'{}' object cannot be interpreted as an float", value.get_class(arena).get_name());
+    match pyobj.as_immutable().as_deref() {
+        Some(PyImmutableObject::Float(value)) => {Ok(*value)}
+        _ => {
+            let message = format!("'{}' object cannot be interpreted as an float", pyobj.clone_class(arena).get_name());
             Err(arena.exceptions.type_error.instantiate(message))
         },
     }
 }
 
 pub fn expect_float_promotion(pyobj: &PyObject, arena: &mut PyArena) -> Result<f64, PyException> {
-    match **pyobj.expect_immutable() {
-        PyImmutableObject::Float(ref value) => {Ok(*value)}
-        PyImmutableObject::Int(ref value) => {Ok(*value as f64)}
-        PyImmutableObject::Bool(ref value) => {Ok(if *value {1.0} else {0.0})}
-        ref _value => {
+    match pyobj.as_immutable().as_deref() {
+        Some(PyImmutableObject::Float(value)) => {Ok(*value)}
+        Some(PyImmutableObject::Int(value)) => {Ok(*value as f64)}
+        Some(PyImmutableObject::Bool(value)) => {Ok(if *value {1.0} else {0.0})}
+        _ => {
             Err(arena.exceptions.not_implemented_error.empty())
         },
     }
 }
 
-pub fn convert_mutable_to_float(pyobj: &PyObject, mutable_obj: &PyMutableObject, arena: &mut PyArena ) -> Result<f64, PyException> {
-    let float_func = mutable_obj.get_magic_method(&PyMagicMethod::Float, arena);
+pub fn convert_mutable_to_float(pyobj: &PyObject, arena: &mut PyArena) -> Result<f64, PyException> {
+    let float_func = pyobj.get_magic_method(&PyMagicMethod::Float, arena);
 
     if let Some(float_func) = float_func {
         let func_result = call_function_1_arg_min(&float_func, pyobj, &[], arena)?;
@@ -39,7 +42,7 @@ pub fn convert_mutable_to_float(pyobj: &PyObject, mutable_obj: &PyMutableObject,
         let float_result = expect_float(&func_result, arena);
 
         return float_result.map_err(|_error| {
-                let message = format!("{}.__float__ returned non-float (type {{<other type>}})", pyobj.clone_class(arena).get_name());
+                let message = format!("{}.__float__ returned non-float (type {})", pyobj.clone_class(arena).get_name(), func_result.clone_class(arena).get_name());
                 arena.exceptions.type_error.instantiate(message)
         });
     }
@@ -52,7 +55,10 @@ pub fn convert_immutable_to_float(immutable_obj: &PyImmutableObject, arena: &mut
     match *immutable_obj {
         PyImmutableObject::Int(ref value) => Ok(*value as f64),  // copy the value
         PyImmutableObject::Float(ref value) => Ok(*value),
-        PyImmutableObject::Str(ref value) => Ok(value.parse::<f64>().unwrap()), // TODO remove unwrap
+        PyImmutableObject::Str(ref value) => parse_float_str(value).ok_or_else(|| {
+            let message = format!("could not convert string to float: {}", format_str_literal(value));
+            arena.exceptions.value_error.instantiate(message)
+        }),
         PyImmutableObject::Bool(ref value) => Ok(if *value { 1.0 } else { 0.0 }),
         ref value => {
             let message = format!("float() argument must be a string or a real number, not '{}'", value.get_class(arena).get_name());
@@ -61,20 +67,154 @@ pub fn convert_immutable_to_float(immutable_obj: &PyImmutableObject, arena: &mut
     }
 }
 
+fn parse_float_str(value: &str) -> Option<f64> {  // accepts everything Rust does plus underscores between digits, e.g. "1_000.5", "-inf", " nan "
+    let value = value.trim();
+
+    let chars: Vec<char> = value.chars().collect();
+    for (i, char) in chars.iter().enumerate() {
+        if *char == '_' && !(i > 0 && chars[i - 1].is_ascii_digit() && chars.get(i + 1).is_some_and(|next| next.is_ascii_digit())) {
+            return None;
+        }
+    }
+
+    value.replace('_', "").parse::<f64>().ok()
+}
+
+pub fn compare_int_float(int_value: i64, float_value: f64) -> Option<Ordering> {  // exact, even where the int can't be represented as a float
+    if float_value.is_nan() {
+        return None;
+    } else if float_value.is_infinite() {
+        return Some(if float_value > 0.0 { Ordering::Less } else { Ordering::Greater });
+    }
+
+    let whole = float_value.trunc();
+
+    if whole >= 9223372036854775808.0 {
+        return Some(Ordering::Less);
+    } else if whole < -9223372036854775808.0 {
+        return Some(Ordering::Greater);
+    }
+
+    match int_value.cmp(&(whole as i64)) {
+        Ordering::Equal => 0.0.partial_cmp(&(float_value - whole)),
+        ordering => Some(ordering),
+    }
+}
+
+pub fn format_float_repr(value: f64) -> String {  // shortest round-tripping repr, formatted like CPython's `float_repr`
+    if value.is_nan() {
+        return "nan".to_string();
+    } else if value.is_infinite() {
+        return if value > 0.0 { "inf".to_string() } else { "-inf".to_string() };
+    } else if value == 0.0 {
+        return if value.is_sign_negative() { "-0.0".to_string() } else { "0.0".to_string() };
+    }
+
+    let formatted = format!("{:e}", value);
+    let (mantissa, exponent) = formatted.split_once('e').expect("exponent formatting always contains `e`");
+    let exponent: i32 = exponent.parse().expect("exponent is always an integer");
+
+    if !(-4..16).contains(&exponent) {
+        let exponent_sign = if exponent < 0 { "-" } else { "+" };
+        return format!("{mantissa}e{exponent_sign}{:02}", exponent.abs());
+    }
+
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits = mantissa.replace('.', "");
+
+    let positional = if exponent < 0 {
+        format!("0.{}{digits}", "0".repeat((-exponent - 1) as usize))
+    } else if digits.len() <= exponent as usize + 1 {
+        format!("{digits}{}.0", "0".repeat(exponent as usize + 1 - digits.len()))
+    } else {
+        let (whole, fraction) = digits.split_at(exponent as usize + 1);
+        format!("{whole}.{fraction}")
+    };
+
+    format!("{sign}{positional}")
+}
+
 pub fn parse_float_op_func_params(pyself: &PyObject, other: &PyObject, arena: &mut PyArena) -> Result<(f64, f64), PyException> {
     let self_value = expect_float(pyself, arena)?;
     let other_value = expect_float_promotion(other, arena)?;
     Ok((self_value, other_value))
 }
 
+const HASH_BITS: u32 = 61;
+pub const HASH_MODULUS: u64 = (1 << HASH_BITS) - 1;
+const HASH_INF: i64 = 314159;
+
+fn frexp(value: f64) -> (f64, i32) {
+    if value == 0.0 || !value.is_finite() {
+        return (value, 0);
+    }
+
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i32;
+
+    if exponent == 0 {  // subnormal, scale it into the normal range first
+        let (mantissa, exponent) = frexp(value * 2f64.powi(54));
+        return (mantissa, exponent - 54);
+    }
+
+    let mantissa = f64::from_bits((bits & !(0x7ff << 52)) | (1022 << 52));
+    (mantissa, exponent - 1022)
+}
+
+pub fn hash_float(value: f64) -> i64 {  // port of CPython's `_Py_HashDouble`, so equal ints and floats hash the same
+    if value.is_infinite() {
+        return if value > 0.0 { HASH_INF } else { -HASH_INF };
+    }
+    if value.is_nan() {
+        return 0;
+    }
+
+    let (mut mantissa, mut exponent) = frexp(value);
+
+    let mut sign = 1;
+    if mantissa < 0.0 {
+        sign = -1;
+        mantissa = -mantissa;
+    }
+
+    let mut hash: u64 = 0;
+    while mantissa != 0.0 {
+        hash = ((hash << 28) & HASH_MODULUS) | (hash >> (HASH_BITS - 28));
+        mantissa *= 268435456.0;  // 2**28
+        exponent -= 28;
+
+        let digits = mantissa as u64;
+        mantissa -= digits as f64;
+        hash += digits;
+
+        if hash >= HASH_MODULUS {
+            hash -= HASH_MODULUS;
+        }
+    }
+
+    let exponent = if exponent >= 0 {
+        exponent as u32 % HASH_BITS
+    } else {
+        HASH_BITS - 1 - ((-1 - exponent) as u32 % HASH_BITS)
+    };
+    hash = ((hash << exponent) & HASH_MODULUS) | (hash >> (HASH_BITS - exponent));
+
+    let hash = hash as i64 * sign;
+    if hash == -1 { -2 } else { hash }
+}
+
 pub fn float__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
     let value = pyargs.first();
     let new_value;
     
     if let Some(value) = value {
         new_value = match value {
-            PyObject::Immutable(immutable) => convert_immutable_to_float(immutable, arena)?,
-            PyObject::Mutable(mutable) => convert_mutable_to_float(value, &mutable.borrow(), arena)?,
+            PyObject::Float(value) => *value,
+            PyObject::None | PyObject::Bool(_) | PyObject::Int(_) | PyObject::Immutable(_) => convert_immutable_to_float(&value.expect_immutable(), arena)?,
+            PyObject::Mutable(_) => convert_mutable_to_float(value, arena)?,
             value => {
                 let message = format!("float() argument must be a string or a real number, not '{}'", value.clone_class(arena).get_name());
                 return Err(arena.exceptions.type_error.instantiate(message))
@@ -88,7 +228,11 @@ pub fn float__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObje
 }
 
 pub fn float__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
-    Ok(PyObject::new_string(expect_float(pyself, arena)?.to_string()))
+    Ok(PyObject::new_string(format_float_repr(expect_float(pyself, arena)?)))
+}
+
+pub fn float__hash__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_int(hash_float(expect_float(pyself, arena)?)))
 }
 
 pub fn float__add__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
@@ -106,7 +250,7 @@ pub fn float__sub__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) ->
 pub fn float__rsub__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_float_op_func_params(pyself, other, arena)?;
 
-    Ok(PyObject::new_float(self_value - other_value))
+    Ok(PyObject::new_float(other_value - self_value))
 }
 
 pub fn float__mul__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
@@ -115,28 +259,171 @@ pub fn float__mul__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) ->
     Ok(PyObject::new_float(self_value * other_value))
 }
 
+fn float_true_div(value: f64, divisor: f64, arena: &mut PyArena) -> FuncReturnType {
+    if divisor == 0.0 {
+        return Err(arena.exceptions.zero_division_error.instantiate("float division by zero".to_string()));
+    }
+
+    Ok(PyObject::new_float(value / divisor))
+}
+
 pub fn float__truediv__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_float_op_func_params(pyself, other, arena)?;
 
-    Ok(PyObject::new_float(self_value / other_value))
+    float_true_div(self_value, other_value, arena)
 }
 
 pub fn float__rtruediv__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_float_op_func_params(pyself, other, arena)?;
 
-    Ok(PyObject::new_float(other_value / self_value))
+    float_true_div(other_value, self_value, arena)
+}
+
+fn float_div_mod(value: f64, divisor: f64) -> (f64, f64) {  // port of CPython's `_float_div_mod`, the divisor must be non-zero
+    let mut modulo = value % divisor;
+    let mut div = (value - modulo) / divisor;
+
+    if modulo != 0.0 {
+        if (divisor < 0.0) != (modulo < 0.0) {  // the remainder takes the sign of the divisor
+            modulo += divisor;
+            div -= 1.0;
+        }
+    } else {
+        modulo = 0.0f64.copysign(divisor);
+    }
+
+    let floor_div = if div != 0.0 {
+        let floor_div = div.floor();
+        if div - floor_div > 0.5 { floor_div + 1.0 } else { floor_div }
+    } else {
+        0.0f64.copysign(value / divisor)
+    };
+
+    (floor_div, modulo)
+}
+
+fn float_floor_div(value: f64, divisor: f64, arena: &mut PyArena) -> FuncReturnType {
+    if divisor == 0.0 {
+        return Err(arena.exceptions.zero_division_error.instantiate("float floor division by zero".to_string()));
+    }
+
+    Ok(PyObject::new_float(float_div_mod(value, divisor).0))
+}
+
+pub fn float__floordiv__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_float_op_func_params(pyself, other, arena)?;
+
+    float_floor_div(self_value, other_value, arena)
+}
+
+pub fn float__rfloordiv__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_float_op_func_params(pyself, other, arena)?;
+
+    float_floor_div(other_value, self_value, arena)
+}
+
+fn float_mod(value: f64, divisor: f64, arena: &mut PyArena) -> FuncReturnType {
+    if divisor == 0.0 {
+        return Err(arena.exceptions.zero_division_error.instantiate("float modulo by zero".to_string()));
+    }
+
+    Ok(PyObject::new_float(float_div_mod(value, divisor).1))
+}
+
+pub fn float__mod__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_float_op_func_params(pyself, other, arena)?;
+
+    float_mod(self_value, other_value, arena)
+}
+
+pub fn float__rmod__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_float_op_func_params(pyself, other, arena)?;
+
+    float_mod(other_value, self_value, arena)
+}
+
+fn float_pow(base: f64, exponent: f64, arena: &mut PyArena) -> FuncReturnType {
+    if base == 0.0 && exponent < 0.0 {
+        return Err(arena.exceptions.zero_division_error.instantiate("0.0 cannot be raised to a negative power".to_string()));
+    }
+
+    if base < 0.0 && base.is_finite() && exponent.is_finite() && exponent != exponent.trunc() {  // a negative number to a fractional power is complex
+        let (real, imag) = complex_pow((base, 0.0), (exponent, 0.0), arena)?;
+        return Ok(PyObject::new_complex(real, imag));
+    }
+
+    let result = base.powf(exponent);
+
+    if result.is_infinite() && base.is_finite() && exponent.is_finite() {
+        return Err(arena.exceptions.overflow_error.instantiate("(34, 'Numerical result out of range')".to_string()));
+    }
+
+    Ok(PyObject::new_float(result))
 }
 
 pub fn float__pow__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_float_op_func_params(pyself, other, arena)?;
 
-    Ok(PyObject::new_float(self_value.powf(other_value)))
+    float_pow(self_value, other_value, arena)
 }
 
 pub fn float__rpow__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_float_op_func_params(pyself, other, arena)?;
 
-    Ok(PyObject::new_float(other_value.powf(self_value)))
+    float_pow(other_value, self_value, arena)
+}
+
+pub fn float__abs__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_float(expect_float(pyself, arena)?.abs()))
+}
+
+pub fn float__neg__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_float(-expect_float(pyself, arena)?))
+}
+
+pub fn float__pos__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_float(expect_float(pyself, arena)?))
+}
+
+fn float_compare(pyself: &PyObject, other: &PyObject, arena: &mut PyArena) -> Result<Option<Ordering>, PyException> {  // `None` when either side is nan
+    let self_value = expect_float(pyself, arena)?;
+
+    match other.as_immutable().as_deref() {
+        Some(PyImmutableObject::Float(other_value)) => Ok(self_value.partial_cmp(other_value)),
+        Some(PyImmutableObject::Int(other_value)) => Ok(compare_int_float(*other_value, self_value).map(Ordering::reverse)),
+        Some(PyImmutableObject::Bool(other_value)) => Ok(compare_int_float(*other_value as i64, self_value).map(Ordering::reverse)),
+        _ => Err(arena.exceptions.not_implemented_error.empty()),
+    }
+}
+
+pub fn float__eq__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let ordering = float_compare(pyself, other, arena)?;
+    Ok(arena.statics.get_bool(ordering == Some(Ordering::Equal)).clone())
+}
+
+pub fn float__ne__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let ordering = float_compare(pyself, other, arena)?;
+    Ok(arena.statics.get_bool(ordering != Some(Ordering::Equal)).clone())
+}
+
+pub fn float__lt__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let ordering = float_compare(pyself, other, arena)?;
+    Ok(arena.statics.get_bool(ordering == Some(Ordering::Less)).clone())
+}
+
+pub fn float__le__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let ordering = float_compare(pyself, other, arena)?;
+    Ok(arena.statics.get_bool(matches!(ordering, Some(Ordering::Less | Ordering::Equal))).clone())
+}
+
+pub fn float__gt__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let ordering = float_compare(pyself, other, arena)?;
+    Ok(arena.statics.get_bool(ordering == Some(Ordering::Greater)).clone())
+}
+
+pub fn float__ge__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let ordering = float_compare(pyself, other, arena)?;
+    Ok(arena.statics.get_bool(matches!(ordering, Some(Ordering::Greater | Ordering::Equal))).clone())
 }
 
 pub fn get_float_class(object_class: Rc<PyClass>) -> PyClass {
@@ -148,6 +435,7 @@ pub fn get_float_class(object_class: Rc<PyClass>) -> PyClass {
             __new__: Some(Rc::new(NewFunc(&(float__new__ as NewFuncType)))),
             
             __repr__: Some(Rc::new(UnaryFunc(&(float__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(float__hash__ as UnaryFuncType)))),
             
             __add__: Some(Rc::new(BivariateFunc(&(float__add__ as BivariateFuncType)))),
             __radd__: Some(Rc::new(BivariateFunc(&(float__add__ as BivariateFuncType)))),
@@ -157,9 +445,24 @@ pub fn get_float_class(object_class: Rc<PyClass>) -> PyClass {
             __rmul__: Some(Rc::new(BivariateFunc(&(float__mul__ as BivariateFuncType)))),
             __truediv__: Some(Rc::new(BivariateFunc(&(float__truediv__ as BivariateFuncType)))),
             __rtruediv__: Some(Rc::new(BivariateFunc(&(float__rtruediv__ as BivariateFuncType)))),
+            __floordiv__: Some(Rc::new(BivariateFunc(&(float__floordiv__ as BivariateFuncType)))),
+            __rfloordiv__: Some(Rc::new(BivariateFunc(&(float__rfloordiv__ as BivariateFuncType)))),
+            __mod__: Some(Rc::new(BivariateFunc(&(float__mod__ as BivariateFuncType)))),
+            __rmod__: Some(Rc::new(BivariateFunc(&(float__rmod__ as BivariateFuncType)))),
             __pow__: Some(Rc::new(BivariateFunc(&(float__pow__ as BivariateFuncType)))),
             __rpow__: Some(Rc::new(BivariateFunc(&(float__rpow__ as BivariateFuncType)))),
             
+            __abs__: Some(Rc::new(UnaryFunc(&(float__abs__ as UnaryFuncType)))),
+            __neg__: Some(Rc::new(UnaryFunc(&(float__neg__ as UnaryFuncType)))),
+            __pos__: Some(Rc::new(UnaryFunc(&(float__pos__ as UnaryFuncType)))),
+
+            __eq__: Some(Rc::new(BivariateFunc(&(float__eq__ as BivariateFuncType)))),
+            __ge__: Some(Rc::new(BivariateFunc(&(float__ge__ as BivariateFuncType)))),
+            __le__: Some(Rc::new(BivariateFunc(&(float__le__ as BivariateFuncType)))),
+            __gt__: Some(Rc::new(BivariateFunc(&(float__gt__ as BivariateFuncType)))),
+            __lt__: Some(Rc::new(BivariateFunc(&(float__lt__ as BivariateFuncType)))),
+            __ne__: Some(Rc::new(BivariateFunc(&(float__ne__ as BivariateFuncType)))),
+            
             ..py_magic_methods_defaults()
         }),
     }.create()
@@ -5,32 +5,35 @@ use crate::builtins::function_utils::call_function_1_arg_min;
 use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethod, PyMagicMethods};
 use crate::builtins::structure::pyclass::PyClass;
 use crate::builtins::structure::pyexception::PyException;
-use crate::builtins::structure::pyobject::{BivariateFuncType, FuncReturnType, NewFuncType, PyImmutableObject, PyMutableObject, PyObject, UnaryFuncType};
-use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, UnaryFunc};
+use crate::builtins::structure::pyobject::{BivariateFuncType, FuncReturnType, NewFuncType, PyImmutableObject, PyObject, UnaryFuncType, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, UnaryFunc, VariadicFunc};
+use crate::builtins::types::pyfloat::HASH_MODULUS;
+use crate::builtins::types::str::format_str_literal;
 use crate::pyarena::PyArena;
 
 pub fn expect_int(pyobj: &PyObject, arena: &mut PyArena) -> Result<i64, PyException> {
-    match **pyobj.expect_immutable() {
-        PyImmutableObject::Int(ref value) => {Ok(*value)}
-        ref value => {
-            let message = format!("'{}' object cannot be interpreted as an integer", value.get_class(arena).get_name());
+    match pyobj.as_immutable().as_deref() {
+        Some(PyImmutableObject::Int(value)) => {Ok(*value)}
+        Some(PyImmutableObject::Bool(value)) => {Ok(*value as i64)}
+        _ => {
+            let message = format!("'{}' object cannot be interpreted as an integer", pyobj.clone_class(arena).get_name());
             Err(arena.exceptions.type_error.instantiate(message))
         },
     }
 }
 
 pub fn expect_int_promotion(pyobj: &PyObject, arena: &mut PyArena) -> Result<i64, PyException> {
-    match **pyobj.expect_immutable() {
-        PyImmutableObject::Int(ref value) => {Ok(*value)}
-        PyImmutableObject::Bool(ref value) => {Ok(*value as i64)}
-        ref _value => {
+    match pyobj.as_immutable().as_deref() {
+        Some(PyImmutableObject::Int(value)) => {Ok(*value)}
+        Some(PyImmutableObject::Bool(value)) => {Ok(*value as i64)}
+        _ => {
             Err(arena.exceptions.not_implemented_error.empty())
         },
     }
 }
 
-pub fn convert_mutable_to_int(pyobj: &PyObject, mutable_obj: &PyMutableObject, arena: &mut PyArena ) -> Result<i64, PyException> {
-    let int_func = mutable_obj.get_magic_method(&PyMagicMethod::Int, arena);
+pub fn convert_mutable_to_int(pyobj: &PyObject, arena: &mut PyArena) -> Result<i64, PyException> {
+    let int_func = pyobj.get_magic_method(&PyMagicMethod::Int, arena);
 
     if let Some(int_func) = int_func {
         let func_result = call_function_1_arg_min(&int_func, pyobj, &[], arena)?;
@@ -38,7 +41,7 @@ pub fn convert_mutable_to_int(pyobj: &PyObject, mutable_obj: &PyMutableObject, a
         let int_result = expect_int(&func_result, arena);
 
         return int_result.map_err(|_error| {
-            let message = format!("{}.__int__ returned non-int (type {{<other type>}})", pyobj.clone_class(arena).get_name());
+            let message = format!("{}.__int__ returned non-int (type {})", pyobj.clone_class(arena).get_name(), func_result.clone_class(arena).get_name());
             arena.exceptions.type_error.instantiate(message)
         });
     }
@@ -51,13 +54,26 @@ pub fn convert_immutable_to_int(immutable_obj: &PyImmutableObject, arena: &mut P
     match *immutable_obj {
         PyImmutableObject::Int(ref value) => Ok(*value),  // copy the value
         PyImmutableObject::Bool(ref value) => Ok(if *value { 1 } else { 0 }),
-        PyImmutableObject::Float(ref value) => Ok(*value as i64),
-        PyImmutableObject::Str(ref value) => value.parse::<i64>().map_err(|_error| {
-            let message = format!("invalid literal for int() with base 10: '{}'", value);  // TODO add support for different bases
-            arena.exceptions.type_error.instantiate(message)
+        PyImmutableObject::Float(value) => {
+            if value.is_nan() {
+                return Err(arena.exceptions.value_error.instantiate("cannot convert float NaN to integer".to_string()));
+            } else if value.is_infinite() {
+                return Err(arena.exceptions.overflow_error.instantiate("cannot convert float infinity to integer".to_string()));
+            }
+
+            let value = value.trunc();
+            if !(-9223372036854775808.0..9223372036854775808.0).contains(&value) {
+                return Err(overflow_error(arena));
+            }
+
+            Ok(value as i64)
+        }
+        PyImmutableObject::Str(ref value) => value.trim().replace('_', "").parse::<i64>().map_err(|_error| {
+            let message = format!("invalid literal for int() with base 10: {}", format_str_literal(value));  // TODO add support for different bases
+            arena.exceptions.value_error.instantiate(message)
         }),
         ref value => {
-            let message = format!("float() argument must be a string, a bytes-like object or a real number, not '{}'", value.get_class(arena).get_name());
+            let message = format!("int() argument must be a string, a bytes-like object or a real number, not '{}'", value.get_class(arena).get_name());
             Err(arena.exceptions.type_error.instantiate(message))
         },
     }
@@ -75,8 +91,9 @@ pub fn int__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject
 
     if let Some(value) = value {
         new_value = match value {
-            PyObject::Immutable(immutable) => convert_immutable_to_int(immutable, arena)?,
-            PyObject::Mutable(mutable) => convert_mutable_to_int(value, &mutable.borrow(), arena)?,
+            PyObject::Int(value) => *value,
+            PyObject::None | PyObject::Bool(_) | PyObject::Float(_) | PyObject::Immutable(_) => convert_immutable_to_int(&value.expect_immutable(), arena)?,
+            PyObject::Mutable(_) => convert_mutable_to_int(value, arena)?,
             value => {
                 let message = format!("int() argument must be a string, a bytes-like object or a real number, not '{}'", value.clone_class(arena).get_name());
                 return Err(arena.exceptions.type_error.instantiate(message))
@@ -89,64 +106,221 @@ pub fn int__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject
     Ok(PyObject::new_int(new_value))  // I don't know how to do inheritance with this
 }
 
+fn overflow_error(arena: &mut PyArena) -> PyException {  // ints are fixed width, so results past i64 can't be represented
+    arena.exceptions.overflow_error.instantiate("integer overflow".to_string())
+}
+
+pub fn int_floor_div(value: i64, divisor: i64) -> Option<i64> {  // rounds towards negative infinity, `None` on overflow
+    let quotient = value.checked_div(divisor)?;
+
+    if value % divisor != 0 && (value < 0) != (divisor < 0) {
+        return Some(quotient - 1);
+    }
+
+    Some(quotient)
+}
+
+pub fn int_mod(value: i64, divisor: i64) -> i64 {  // result takes the sign of the divisor
+    let remainder = value.wrapping_rem(divisor);
+
+    if remainder != 0 && (remainder < 0) != (divisor < 0) {
+        return remainder + divisor;
+    }
+
+    remainder
+}
+
+fn int_mod_inverse(value: i64, modulus: i64) -> Option<i64> {  // extended euclidean algorithm
+    let (mut old_r, mut r) = (int_mod(value, modulus.abs()) as i128, modulus.abs() as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+
+    if old_r != 1 {
+        return None;
+    }
+
+    Some(old_s.rem_euclid(modulus.abs() as i128) as i64)
+}
+
+fn int_pow_mod(base: i64, exponent: i64, modulus: i64, arena: &mut PyArena) -> FuncReturnType {
+    if modulus == 0 {
+        return Err(arena.exceptions.value_error.instantiate("pow() 3rd argument cannot be 0".to_string()));
+    }
+
+    let mut base = base;
+    if exponent < 0 {
+        base = int_mod_inverse(base, modulus).ok_or_else(|| arena.exceptions.value_error.instantiate("base is not invertible for the given modulus".to_string()))?;
+    }
+
+    let modulus_abs = modulus.unsigned_abs() as u128;
+    let mut result: u128 = 1 % modulus_abs;
+    let mut base = int_mod(base, modulus.abs()) as u128;
+    let mut exponent = exponent.unsigned_abs();
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus_abs;
+        }
+        base = base * base % modulus_abs;
+        exponent >>= 1;
+    }
+
+    Ok(PyObject::new_int(int_mod(result as i64, modulus)))
+}
+
+fn int_pow(base: i64, exponent: i64, arena: &mut PyArena) -> FuncReturnType {
+    if exponent < 0 {
+        if base == 0 {
+            return Err(arena.exceptions.zero_division_error.instantiate("0.0 cannot be raised to a negative power".to_string()));
+        }
+        return Ok(PyObject::new_float((base as f64).powf(exponent as f64)));
+    }
+
+    let result = match base {
+        0 | 1 => Some(if exponent == 0 { 1 } else { base }),
+        -1 => Some(if exponent % 2 == 0 { 1 } else { -1 }),
+        _ => u32::try_from(exponent).ok().and_then(|exponent| base.checked_pow(exponent)),
+    };
+
+    result.map(PyObject::new_int).ok_or_else(|| overflow_error(arena))
+}
+
 pub fn int__add__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
     
-    Ok(PyObject::new_int(self_value + other_value))
+    self_value.checked_add(other_value).map(PyObject::new_int).ok_or_else(|| overflow_error(arena))
 }
 
 pub fn int__sub__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
     
-    Ok(PyObject::new_int(self_value - other_value))
+    self_value.checked_sub(other_value).map(PyObject::new_int).ok_or_else(|| overflow_error(arena))
 }
 
 pub fn int__rsub__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
     
-    Ok(PyObject::new_int(other_value - self_value))
+    other_value.checked_sub(self_value).map(PyObject::new_int).ok_or_else(|| overflow_error(arena))
 }
 
 pub fn int__mul__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
 
-    Ok(PyObject::new_int(self_value * other_value))
+    self_value.checked_mul(other_value).map(PyObject::new_int).ok_or_else(|| overflow_error(arena))
+}
+
+fn int_true_div(value: i64, divisor: i64, arena: &mut PyArena) -> FuncReturnType {
+    if divisor == 0 {
+        return Err(arena.exceptions.zero_division_error.instantiate("division by zero".to_string()));
+    }
+
+    Ok(PyObject::new_float(value as f64 / divisor as f64))
 }
 
 pub fn int__truediv__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
 
-    Ok(PyObject::new_float(self_value as f64 / other_value as f64))
+    int_true_div(self_value, other_value, arena)
 }
 
 pub fn int__rtruediv__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
 
-    Ok(PyObject::new_float(other_value as f64 / self_value as f64))
+    int_true_div(other_value, self_value, arena)
+}
+
+fn int_checked_floor_div(value: i64, divisor: i64, arena: &mut PyArena) -> FuncReturnType {
+    if divisor == 0 {
+        return Err(arena.exceptions.zero_division_error.instantiate("integer division or modulo by zero".to_string()));
+    }
+
+    int_floor_div(value, divisor).map(PyObject::new_int).ok_or_else(|| overflow_error(arena))
 }
 
-pub fn int__pow__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+pub fn int__floordiv__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
-    
-    if other_value < 0 {
-        return Ok(PyObject::new_float((self_value as f64).powf(other_value as f64)));
+
+    int_checked_floor_div(self_value, other_value, arena)
+}
+
+pub fn int__rfloordiv__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
+
+    int_checked_floor_div(other_value, self_value, arena)
+}
+
+fn int_checked_mod(value: i64, divisor: i64, arena: &mut PyArena) -> FuncReturnType {
+    if divisor == 0 {
+        return Err(arena.exceptions.zero_division_error.instantiate("integer modulo by zero".to_string()));
     }
 
-    Ok(PyObject::new_int(self_value.pow(other_value as u32)))
+    Ok(PyObject::new_int(int_mod(value, divisor)))
 }
 
-pub fn int__rpow__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+pub fn int__mod__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
     let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
 
-    if other_value < 0 {
-        return Ok(PyObject::new_float((other_value as f64).powf(self_value as f64)));
+    int_checked_mod(self_value, other_value, arena)
+}
+
+pub fn int__rmod__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
+
+    int_checked_mod(other_value, self_value, arena)
+}
+
+pub fn int__pow__(arena: &mut PyArena, pyself: &PyObject, args: &[PyObject]) -> FuncReturnType {  // takes an optional modulus for 3 argument `pow()`
+    let (self_value, other_value) = parse_int_op_func_params(pyself, &args[0], arena)?;
+
+    match args.get(1) {
+        Some(modulus) if !matches!(*modulus.expect_immutable(), PyImmutableObject::None) => {
+            let modulus = expect_int_promotion(modulus, arena)?;
+            int_pow_mod(self_value, other_value, modulus, arena)
+        }
+        _ => int_pow(self_value, other_value, arena),
     }
+}
+
+pub fn int__rpow__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let (self_value, other_value) = parse_int_op_func_params(pyself, other, arena)?;
 
-    Ok(PyObject::new_int(other_value.pow(self_value as u32)))
+    int_pow(other_value, self_value, arena)
+}
+
+pub fn int__neg__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let value = expect_int_promotion(pyself, arena)?;
+    value.checked_neg().map(PyObject::new_int).ok_or_else(|| overflow_error(arena))
+}
+
+pub fn int__pos__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let value = expect_int_promotion(pyself, arena)?;
+    Ok(PyObject::new_int(value))
+}
+
+pub fn hash_int(value: i64) -> i64 {  // reduces modulo 2**61 - 1 like CPython, so `hash(n) == hash(float(n))`
+    let hash = (value.unsigned_abs() % HASH_MODULUS) as i64;
+    let hash = if value < 0 { -hash } else { hash };
+
+    if hash == -1 { -2 } else { hash }
+}
+
+pub fn int__hash__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let value = expect_int_promotion(pyself, arena)?;
+    Ok(PyObject::new_int(hash_int(value)))
+}
+
+pub fn int__abs__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let value = expect_int_promotion(pyself, arena)?;
+    value.checked_abs().map(PyObject::new_int).ok_or_else(|| overflow_error(arena))
 }
 
 pub fn int__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
-    let value = expect_int(&pyself, arena)?;
+    let value = expect_int(pyself, arena)?;
     Ok(PyObject::new_string(value.to_string()))
 }
 
@@ -201,6 +375,7 @@ pub fn get_int_class(object_class: Rc<PyClass>) -> PyClass {
             __new__: Some(Rc::new(NewFunc(&(int__new__ as NewFuncType)))),
 
             __repr__: Some(Rc::new(UnaryFunc(&(int__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(int__hash__ as UnaryFuncType)))),
             
             __add__: Some(Rc::new(BivariateFunc(&(int__add__ as BivariateFuncType)))),
             __radd__: Some(Rc::new(BivariateFunc(&(int__add__ as BivariateFuncType)))),
@@ -210,9 +385,17 @@ pub fn get_int_class(object_class: Rc<PyClass>) -> PyClass {
             __rmul__: Some(Rc::new(BivariateFunc(&(int__mul__ as BivariateFuncType)))),
             __truediv__: Some(Rc::new(BivariateFunc(&(int__truediv__ as BivariateFuncType)))),
             __rtruediv__: Some(Rc::new(BivariateFunc(&(int__rtruediv__ as BivariateFuncType)))),
-            __pow__: Some(Rc::new(BivariateFunc(&(int__pow__ as BivariateFuncType)))),
+            __floordiv__: Some(Rc::new(BivariateFunc(&(int__floordiv__ as BivariateFuncType)))),
+            __rfloordiv__: Some(Rc::new(BivariateFunc(&(int__rfloordiv__ as BivariateFuncType)))),
+            __mod__: Some(Rc::new(BivariateFunc(&(int__mod__ as BivariateFuncType)))),
+            __rmod__: Some(Rc::new(BivariateFunc(&(int__rmod__ as BivariateFuncType)))),
+            __pow__: Some(Rc::new(VariadicFunc(&(int__pow__ as VariadicFuncType)))),
             __rpow__: Some(Rc::new(BivariateFunc(&(int__rpow__ as BivariateFuncType)))),
             
+            __abs__: Some(Rc::new(UnaryFunc(&(int__abs__ as UnaryFuncType)))),
+            __neg__: Some(Rc::new(UnaryFunc(&(int__neg__ as UnaryFuncType)))),
+            __pos__: Some(Rc::new(UnaryFunc(&(int__pos__ as UnaryFuncType)))),
+            
             __eq__: Some(Rc::new(BivariateFunc(&(int__eq__ as BivariateFuncType)))),
             __ge__: Some(Rc::new(BivariateFunc(&(int__ge__ as BivariateFuncType)))),
             __le__: Some(Rc::new(BivariateFunc(&(int__le__ as BivariateFuncType)))),
@@ -9,10 +9,10 @@ use crate::builtins::structure::pyobject::PyInternalFunction::{NewFunc, UnaryFun
 use crate::pyarena::PyArena;
 
 pub fn expect_none(pyobj: &PyObject, arena: &mut PyArena) -> Result<(), PyException> {
-    match **pyobj.expect_immutable() {
-        PyImmutableObject::None => { Ok(()) }
-        ref value => {
-            let message = format!("'{}' object cannot be interpreted as a NoneType", value.get_class(arena).get_name());
+    match pyobj.as_immutable().as_deref() {
+        Some(PyImmutableObject::None) => { Ok(()) }
+        _ => {
+            let message = format!("'{}' object cannot be interpreted as a NoneType", pyobj.clone_class(arena).get_name());
             Err(arena.exceptions.type_error.instantiate(message))
         },
     }
@@ -29,6 +29,11 @@ pub fn none__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
     Ok(PyObject::new_string("None".to_string()))
 }
 
+pub fn none__hash__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {  // constant since CPython 3.12, rather than the address of the singleton
+    expect_none(pyself, arena)?;
+    Ok(PyObject::new_int(0xFCA86420))
+}
+
 pub fn get_none_class(object_class: Rc<PyClass>) -> PyClass {
     PyClass::Internal {
         name: "NoneType".to_string(),
@@ -38,9 +43,37 @@ pub fn get_none_class(object_class: Rc<PyClass>) -> PyClass {
             __new__: Some(Rc::new(NewFunc(&(none__new__ as NewFuncType)))),
 
             __repr__: Some(Rc::new(UnaryFunc(&(none__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(none__hash__ as UnaryFuncType)))),
 
             ..py_magic_methods_defaults()
         })
     }.create()
 }
 
+
+pub fn not_implemented__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    if !pyargs.is_empty() {
+        return Err(arena.exceptions.type_error.instantiate("NotImplementedType takes no arguments".to_string()));
+    }
+
+    Ok(arena.statics.not_implemented().clone())
+}
+
+pub fn not_implemented__repr__(_arena: &mut PyArena, _pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_string("NotImplemented".to_string()))
+}
+
+pub fn get_not_implemented_class(object_class: Rc<PyClass>) -> PyClass {  // the class of the `NotImplemented` singleton binary methods return
+    PyClass::Internal {
+        name: "NotImplementedType".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(not_implemented__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(not_implemented__repr__ as UnaryFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
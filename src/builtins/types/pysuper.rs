@@ -0,0 +1,139 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::functions::attributes::descriptor_get;
+use crate::builtins::functions::isinstance::is_instance;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethod, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{EmptyFuncReturnType, FuncReturnType, NewFuncType, PyInternalFunction, PyInternalObject, PyMutableObject, PyObject, UnaryFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{NewFunc, UnaryFunc};
+use crate::builtins::types::method::new_method;
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+pub struct SuperInstance {
+    thisclass: Rc<PyClass>,
+    pyself: PyObject,  // an instance, or a class when `super()` is used in a classmethod
+    self_class: Rc<PyClass>,
+    bind_instance: bool,  // false when `pyself` is a subclass of `thisclass` rather than an instance, classes can be both through a metaclass
+    remaining_mro: Vec<Rc<PyClass>>,  // the part of `self_class`'s MRO after `thisclass`, where lookups start
+}
+
+impl PyInstanceInternal for SuperInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        match key {
+            "__thisclass__" => Some(PyObject::new_internal_class(self.thisclass.clone())),
+            "__self__" => Some(self.pyself.clone()),
+            "__self_class__" => Some(PyObject::new_internal_class(self.self_class.clone())),
+            _ => None,
+        }
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        visit(&self.pyself);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        vec![std::mem::replace(&mut self.pyself, PyObject::None)]
+    }
+}
+
+pub fn super__new__(arena: &mut PyArena, pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    let [thisclass, pyself] = pyargs else {
+        if pyargs.is_empty() {  // zero-argument `super()` is rewritten by the compiler wherever it can work
+            return Err(arena.exceptions.runtime_error.instantiate("super(): no arguments".to_string()));
+        }
+
+        let message = format!("super() expected 2 arguments, got {}", pyargs.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let PyObject::Internal(PyInternalObject::InternalClass(thisclass)) = thisclass else {
+        let message = format!("super() argument 1 must be a type, not {}", thisclass.clone_class(arena).get_name());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let (self_class, bind_instance) = match pyself {
+        PyObject::Internal(PyInternalObject::InternalClass(self_class)) if self_class.is_subclass_of(thisclass) => (self_class.clone(), false),
+        instance if is_instance(instance, thisclass, arena) => (instance.clone_class(arena), true),
+        _ => return Err(arena.exceptions.type_error.instantiate("super(type, obj): obj must be an instance or subtype of type".to_string())),
+    };
+
+    let remaining_mro = std::iter::once(self_class.clone()).chain(self_class.get_mro())  // `get_mro` leaves out the class itself
+        .skip_while(|base_class| !Rc::ptr_eq(base_class, thisclass))
+        .skip(1)
+        .collect();
+
+    Ok(PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        pyclass,
+        Box::new(SuperInstance { thisclass: thisclass.clone(), pyself: pyself.clone(), self_class, bind_instance, remaining_mro })
+    ))))
+}
+
+pub fn is_super(pyobj: &PyObject, arena: &PyArena) -> bool {
+    match pyobj {
+        PyObject::Mutable(inner) => Rc::ptr_eq(inner.borrow().get_class(), &arena.globals.super_class),
+        _ => false,
+    }
+}
+
+pub fn super_get_attribute(pyobj: &PyObject, name: &str, arena: &mut PyArena) -> Result<Option<PyObject>, PyException> {  // `None` for anything that isn't a `super` object or isn't found past `thisclass`
+    if !is_super(pyobj, arena) {
+        return Ok(None);
+    }
+
+    let (pyself, self_class, bind_instance, remaining_mro) = {
+        let pyobj = pyobj.expect_mutable().borrow();
+        let pysuper = pyobj.expect_instance().internal.downcast_ref::<SuperInstance>().expect("instance is a super object");
+        (pysuper.pyself.clone(), pysuper.self_class.clone(), pysuper.bind_instance, pysuper.remaining_mro.clone())
+    };
+
+    let instance = bind_instance.then_some(&pyself);
+
+    for base_class in &remaining_mro {
+        if let Some(attribute) = base_class.get_own_attribute(name) {
+            return descriptor_get(attribute, instance, &self_class, arena).map(Some);
+        }
+
+        if base_class.is_user_defined() {
+            continue;
+        }
+
+        let Some(magic_method) = PyMagicMethod::from_string(name).and_then(|magic_method| base_class.get_own_magic_method(&magic_method)) else {
+            continue;
+        };
+
+        let is_static = matches!(magic_method, PyObject::Internal(PyInternalObject::InternalFunction(ref func)) if matches!(**func, PyInternalFunction::NewFunc(_)));
+
+        return Ok(Some(if is_static { magic_method } else { new_method(magic_method, pyself.clone(), arena) }));  // builtin slots like `__init__` take `self` but aren't plain functions
+    }
+
+    Ok(None)
+}
+
+pub fn super__repr__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let pyself = pyself.expect_mutable().borrow();
+    let pysuper = pyself.expect_instance().internal.downcast_ref::<SuperInstance>().expect("instance is a super object");
+
+    Ok(PyObject::new_string(format!("<super: <class '{}'>, <{} object>>", pysuper.thisclass.get_name(), pysuper.self_class.get_name())))
+}
+
+pub fn get_super_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "super".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(super__new__ as NewFuncType)))),
+            __repr__: Some(Rc::new(UnaryFunc(&(super__repr__ as UnaryFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
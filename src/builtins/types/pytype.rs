@@ -0,0 +1,309 @@
+#![allow(non_snake_case)]
+use std::cell::RefCell;
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::{call_function, call_function_1_arg_min, collect_iterable, eval_obj_init};
+use crate::builtins::functions::attributes::{ATTRIBUTE_HOOKS, descriptor_get, is_data_descriptor, is_plain_function};
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::{InstanceLayout, PyClass};
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{ClassCell, EmptyFuncReturnType, FuncReturnType, InitFuncType, NewFuncType, PyImmutableObject, PyInternalObject, PyObject, UnaryFuncType, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{InitFunc, NewFunc, UnaryFunc, VariadicFunc};
+use crate::builtins::types::classmethod::function_wrapper__new__;
+use crate::builtins::types::dict::try_dict_items;
+use crate::builtins::types::member::new_member;
+use crate::builtins::types::object::{expect_class, unhashable__hash__};
+use crate::builtins::types::tuple::{new_tuple, try_tuple_items};
+use crate::gc::note_finalizer_defined;
+use crate::pyarena::PyArena;
+
+const IMPLICIT_CLASSMETHODS: [&str; 2] = ["__init_subclass__", "__class_getitem__"];  // plain functions under these names are wrapped in `classmethod`, like CPython
+
+pub fn type__new__(arena: &mut PyArena, metaclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    match pyargs {
+        [pyobj] if Rc::ptr_eq(&metaclass, &arena.globals.type_class) => Ok(PyObject::new_internal_class(pyobj.clone_class(arena))),
+        [name, bases, namespace] => {
+            let Some(name) = try_string(name) else {
+                let message = format!("type.__new__() argument 1 must be str, not {}", name.clone_class(arena).get_name());
+                return Err(arena.exceptions.type_error.instantiate(message));
+            };
+
+            let Some(bases) = try_tuple_items(bases) else {
+                let message = format!("type.__new__() argument 2 must be tuple, not {}", bases.clone_class(arena).get_name());
+                return Err(arena.exceptions.type_error.instantiate(message));
+            };
+
+            let super_classes = expect_bases(bases, arena)?;
+            let attributes = expect_namespace(namespace, arena)?;
+            let metaclass = calculate_metaclass(metaclass, &super_classes, arena)?;
+
+            new_class(name, super_classes, attributes, metaclass, None, arena)
+        }
+        _ => Err(arena.exceptions.type_error.instantiate("type() takes 1 or 3 arguments".to_string())),
+    }
+}
+
+pub fn type__init__(arena: &mut PyArena, _pyself: &PyObject, pyargs: &[PyObject]) -> EmptyFuncReturnType {  // everything already happened in `__new__`
+    match pyargs.len() {
+        1 | 3 => Ok(()),
+        _ => Err(arena.exceptions.type_error.instantiate("type.__init__() takes 1 or 3 arguments".to_string())),
+    }
+}
+
+pub fn type__call__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {  // lets a metaclass's `__call__` fall back to the normal construction
+    eval_obj_init(expect_class(pyself), pyargs, arena)
+}
+
+pub fn type__repr__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let pyclass = expect_class(pyself);
+
+    Ok(PyObject::new_string(match pyclass.is_user_defined() {
+        true => format!("<class '__main__.{}'>", pyclass.get_name()),
+        false => format!("<class '{}'>", pyclass.get_name()),
+    }))
+}
+
+pub fn expect_bases(bases: Vec<PyObject>, arena: &mut PyArena) -> Result<Vec<Rc<PyClass>>, PyException> {
+    let mut super_classes = Vec::with_capacity(bases.len().max(1));
+
+    for base in bases {
+        match base {
+            PyObject::Internal(PyInternalObject::InternalClass(base_class)) => super_classes.push(base_class),
+            _ => return Err(arena.exceptions.type_error.instantiate("bases must be types".to_string())),
+        }
+    }
+
+    Ok(super_classes)
+}
+
+fn expect_namespace(namespace: &PyObject, arena: &mut PyArena) -> Result<Vec<(String, PyObject)>, PyException> {
+    let Some(items) = try_dict_items(namespace) else {
+        let message = format!("type.__new__() argument 3 must be dict, not {}", namespace.clone_class(arena).get_name());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let mut attributes = vec![];
+
+    for (name, value) in items {
+        let Some(name) = try_string(&name) else {
+            let message = format!("attribute name must be string, not '{}'", name.clone_class(arena).get_name());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        };
+
+        attributes.push((name, value));
+    }
+
+    Ok(attributes)
+}
+
+pub fn calculate_metaclass(metaclass: Rc<PyClass>, bases: &[Rc<PyClass>], arena: &mut PyArena) -> Result<Rc<PyClass>, PyException> {  // the most derived of the given metaclass and those of the bases
+    let mut winner = metaclass;
+
+    for base_class in bases {
+        let base_metaclass = base_class.get_metaclass(arena);
+
+        if winner.is_subclass_of(&base_metaclass) {
+            continue;
+        }
+
+        if !base_metaclass.is_subclass_of(&winner) {
+            let message = "metaclass conflict: the metaclass of a derived class must be a (non-strict) subclass of the metaclasses of all its bases";
+            return Err(arena.exceptions.type_error.instantiate(message.to_string()));
+        }
+
+        winner = base_metaclass;
+    }
+
+    Ok(winner)
+}
+
+pub fn new_class(name: String, mut super_classes: Vec<Rc<PyClass>>, namespace: Vec<(String, PyObject)>, metaclass: Rc<PyClass>, class_cell: Option<ClassCell>, arena: &mut PyArena) -> FuncReturnType {  // what `type.__new__` does once its arguments are checked
+    if super_classes.is_empty() {
+        super_classes.push(arena.globals.object_class.clone());
+    }
+
+    if let Some(duplicate) = super_classes.iter().enumerate().find(|(index, base_class)| super_classes[..*index].iter().any(|other| Rc::ptr_eq(other, base_class))) {
+        let message = format!("duplicate base class {}", duplicate.1.get_name());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let Some(mro) = PyClass::linearize(&super_classes) else {
+        let names = super_classes.iter().map(|base_class| base_class.get_name().as_str()).collect::<Vec<_>>().join(", ");
+        let message = format!("Cannot create a consistent method resolution\norder (MRO) for bases {names}");  // CPython breaks the line here too
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let set_name_targets: Vec<(String, PyObject)> = namespace.iter()  // in definition order
+        .filter(|(_name, value)| matches!(value, PyObject::Mutable(_)))
+        .cloned()
+        .collect();
+    let mut attributes: AHashMap<String, PyObject> = namespace.into_iter().collect();
+
+    if attributes.contains_key("__eq__") && !attributes.contains_key("__hash__") {  // same rule `PyClass::create` applies to builtin classes
+        attributes.insert("__hash__".to_string(), PyObject::new_internal_func(Rc::new(UnaryFunc(&(unhashable__hash__ as UnaryFuncType)))));
+    }
+
+    for hook in IMPLICIT_CLASSMETHODS {
+        if let Some(function) = attributes.get(hook).filter(|function| is_plain_function(function)).cloned() {
+            let classmethod_class = arena.globals.classmethod_class.clone();
+            attributes.insert(hook.to_string(), function_wrapper__new__(arena, classmethod_class, &[function])?);
+        }
+    }
+
+    let layout = instance_layout(&name, &super_classes, &mut attributes, arena)?;
+    arena.attribute_hooks_defined |= ATTRIBUTE_HOOKS.iter().any(|hook| attributes.contains_key(*hook));
+    if attributes.contains_key("__del__") {
+        note_finalizer_defined();
+    }
+
+    let pyclass = Rc::new(PyClass::UserDefined {
+        name,
+        super_classes,
+        attributes: RefCell::new(attributes),
+        layout,
+        mro,
+        metaclass,
+    });
+
+    if let Some(class_cell) = class_cell {
+        *class_cell.borrow_mut() = Rc::downgrade(&pyclass);
+    }
+
+    let class_object = PyObject::new_internal_class(pyclass.clone());
+
+    for (name, value) in set_name_targets {  // lets descriptors learn the name they were assigned to
+        if let Some(set_name_func) = value.get_method("__set_name__", arena) {
+            call_function_1_arg_min(&set_name_func, &value, &[class_object.clone(), PyObject::new_string(name)], arena)?;
+        }
+    }
+
+    let parent_hook = pyclass.get_mro().iter().find_map(|base_class| base_class.get_own_attribute("__init_subclass__"));
+
+    if let Some(init_subclass) = parent_hook {  // `super().__init_subclass__()` from the new class's point of view
+        let init_subclass = descriptor_get(init_subclass, None, &pyclass, arena)?;
+        call_function(init_subclass, &[], arena)?;
+    }
+
+    Ok(class_object)
+}
+
+fn instance_layout(class_name: &str, super_classes: &[Rc<PyClass>], attributes: &mut AHashMap<String, PyObject>, arena: &mut PyArena) -> Result<InstanceLayout, PyException> {  // adds a member descriptor for every name in `__slots__`
+    let mut slot_owner: Option<Rc<PyClass>> = None;  // the class that added the last slots the new class inherits
+
+    for base_class in super_classes.iter().filter(|base_class| base_class.get_layout().slot_count > 0) {
+        let owner = slots_owner(base_class);
+
+        slot_owner = match slot_owner {
+            Some(current) if owner.is_subclass_of(&current) => Some(owner),
+            Some(current) if current.is_subclass_of(&owner) => Some(current),
+            Some(_) => return Err(arena.exceptions.type_error.instantiate("multiple bases have instance lay-out conflict".to_string())),  // the slots of both would need the same indices
+            None => Some(owner),
+        };
+    }
+
+    let mut slot_count = slot_owner.map_or(0, |owner| owner.get_layout().slot_count);
+
+    let Some(slots) = attributes.get("__slots__").cloned() else {
+        return Ok(InstanceLayout { slot_count, has_dict: true });
+    };
+
+    let slot_names = match try_string(&slots) {
+        Some(name) => vec![PyObject::new_string(name)],
+        None => collect_iterable(&slots, arena)?,
+    };
+
+    let mut has_dict = super_classes.iter().any(|base_class| base_class.get_layout().has_dict);
+
+    for slot_name in slot_names {
+        let Some(name) = try_string(&slot_name) else {
+            let message = format!("__slots__ items must be strings, not '{}'", slot_name.clone_class(arena).get_name());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        };
+
+        match name.as_str() {
+            "__dict__" if has_dict => return Err(arena.exceptions.type_error.instantiate("__dict__ slot disallowed: we already got one".to_string())),
+            "__dict__" => has_dict = true,
+            "__weakref__" => {}  // instances of user classes can always be weakly referenced
+            _ if attributes.contains_key(&name) => {
+                let message = format!("'{name}' in __slots__ conflicts with class variable");
+                return Err(arena.exceptions.value_error.instantiate(message));
+            }
+            _ => {
+                let member = new_member(name.clone(), class_name.to_string(), slot_count, arena);
+                attributes.insert(name, member);
+                slot_count += 1;
+            }
+        }
+    }
+
+    Ok(InstanceLayout { slot_count, has_dict })
+}
+
+fn slots_owner(pyclass: &Rc<PyClass>) -> Rc<PyClass> {  // the furthest ancestor that still has all of the class's slots
+    let slot_count = pyclass.get_layout().slot_count;
+    pyclass.get_mro().into_iter().rfind(|base_class| base_class.get_layout().slot_count == slot_count).unwrap_or_else(|| pyclass.clone())
+}
+
+fn try_string(pyobj: &PyObject) -> Option<String> {
+    match pyobj {
+        PyObject::Immutable(immutable) => match **immutable {
+            PyImmutableObject::Str(ref string) => Some(string.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn type_get_attribute(pyclass: &Rc<PyClass>, name: &str, arena: &mut PyArena) -> FuncReturnType {  // `type.__getattribute__`, the class's own MRO comes between the metaclass's data and non-data descriptors
+    let metaclass = pyclass.get_metaclass(arena);
+    let class_object = PyObject::new_internal_class(pyclass.clone());
+    let meta_attribute = metaclass.search_for_attribute(name);
+
+    if let Some(ref descriptor) = meta_attribute {
+        if is_data_descriptor(descriptor, arena) {
+            return descriptor_get(descriptor.clone(), Some(&class_object), &metaclass, arena);
+        }
+    }
+
+    if let Some(field) = get_class_field(pyclass, &metaclass, name, arena) {
+        return Ok(field);
+    }
+
+    if let Some(attribute) = pyclass.search_for_attribute(name) {
+        return descriptor_get(attribute, None, pyclass, arena);
+    }
+
+    if let Some(meta_attribute) = meta_attribute {  // methods defined on the metaclass bind the class
+        return descriptor_get(meta_attribute, Some(&class_object), &metaclass, arena);
+    }
+
+    let message = format!("type object '{}' has no attribute '{name}'", pyclass.get_name());
+    Err(arena.exceptions.attribute_error.instantiate(message))
+}
+
+fn get_class_field(pyclass: &Rc<PyClass>, metaclass: &Rc<PyClass>, name: &str, arena: &mut PyArena) -> Option<PyObject> {  // the read-only data every class has
+    match name {
+        "__name__" => Some(PyObject::new_string(pyclass.get_name().clone())),
+        "__bases__" => Some(new_tuple(pyclass.get_super_classes().into_iter().map(PyObject::new_internal_class).collect(), arena)),
+        "__mro__" => Some(new_tuple(std::iter::once(pyclass.clone()).chain(pyclass.get_mro()).map(PyObject::new_internal_class).collect(), arena)),
+        "__class__" => Some(PyObject::new_internal_class(metaclass.clone())),
+        _ => None,
+    }
+}
+
+pub fn get_type_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "type".to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("__call__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(type__call__ as VariadicFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(type__new__ as NewFuncType)))),
+            __init__: Some(Rc::new(InitFunc(&(type__init__ as InitFuncType)))),
+            __repr__: Some(Rc::new(UnaryFunc(&(type__repr__ as UnaryFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
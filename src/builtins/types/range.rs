@@ -3,12 +3,13 @@ use std::fmt::{Debug};
 use std::rc::Rc;
 use ahash::AHashMap;
 use crate::builtins::function_utils::init_internal_class;
+use crate::builtins::functions::compare::eq_with_identity;
 use crate::builtins::types::pyint::expect_int;
 use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
 use crate::builtins::structure::pyclass::PyClass;
 use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
-use crate::builtins::structure::pyobject::{EmptyFuncReturnType, FuncReturnType, NewFuncType, PyImmutableObject, PyMutableObject, PyObject, UnaryFuncType};
-use crate::builtins::structure::pyobject::PyInternalFunction::{NewFunc, UnaryFunc};
+use crate::builtins::structure::pyobject::{BivariateFuncType, EmptyFuncReturnType, FuncReturnType, NewFuncType, PyImmutableObject, PyMutableObject, PyObject, UnaryFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, UnaryFunc};
 use crate::pyarena::PyArena;
 
 #[derive(Debug)]
@@ -40,7 +41,7 @@ impl PyInstanceInternal for RangeInstance {
 }
 
 pub fn range__new__(arena: &mut PyArena, pyclass: Rc<PyClass>, args: &[PyObject]) -> FuncReturnType {
-    let arg1 = args.get(0).ok_or_else(|| arena.exceptions.type_error.instantiate("range expected at least 1 argument, got 0".to_string()))?;
+    let arg1 = args.first().ok_or_else(|| arena.exceptions.type_error.instantiate("range expected at least 1 argument, got 0".to_string()))?;
     
     let first = expect_int(arg1, arena)?;
     let second = args.get(1);
@@ -86,8 +87,52 @@ pub fn range__repr__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType
     }
 }
 
+fn expect_range_bounds(pyobj: &PyObject) -> (i64, i64, i64) {
+    let pyobj = pyobj.expect_mutable().borrow();
+    let instance = pyobj.expect_instance();
+
+    let range_internal = instance.internal.downcast_ref::<RangeInstance>().unwrap_or_else(|| panic!("Instance received is not of RangeInstance type, instead {:?}", instance));
+    (range_internal.start, range_internal.stop, range_internal.step)
+}
+
+fn range_contains_int(start: i64, stop: i64, step: i64, value: i64) -> bool {  // O(1), no iteration needed for ints
+    let in_bounds = if step > 0 {
+        start <= value && value < stop
+    } else {
+        stop < value && value <= start
+    };
+
+    in_bounds && (value as i128 - start as i128) % step as i128 == 0
+}
+
+pub fn range__contains__(arena: &mut PyArena, pyself: &PyObject, item: &PyObject) -> FuncReturnType {
+    let (start, stop, step) = expect_range_bounds(pyself);
+
+    let int_value = match *item {
+        PyObject::Int(value) => Some(value),
+        PyObject::Bool(value) => Some(value as i64),
+        _ => None,
+    };
+
+    if let Some(value) = int_value {
+        return Ok(arena.statics.get_bool(range_contains_int(start, stop, step, value)).clone());
+    }
+
+    let mut current = start;  // other types can still compare equal to an int (e.g. `1.0 in range(3)`), so fall back to a linear scan
+
+    while (step > 0 && current < stop) || (step < 0 && current > stop) {
+        if eq_with_identity(&PyObject::new_int(current), item, arena)? {
+            return Ok(arena.statics.get_bool(true).clone());
+        }
+
+        current += step;
+    }
+
+    Ok(arena.statics.get_bool(false).clone())
+}
+
 pub fn range__iter__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
-    init_internal_class(arena.globals.range_iterator_class.clone(), &[pyself.clone()], arena)
+    init_internal_class(arena.globals.range_iterator_class.clone(), std::slice::from_ref(pyself), arena)
 }
 
 pub fn get_range_class(object_class: Rc<PyClass>) -> PyClass {
@@ -101,6 +146,7 @@ pub fn get_range_class(object_class: Rc<PyClass>) -> PyClass {
             __repr__: Some(Rc::new(UnaryFunc(&(range__repr__ as UnaryFuncType)))),
 
             __iter__: Some(Rc::new(UnaryFunc(&(range__iter__ as UnaryFuncType)))),
+            __contains__: Some(Rc::new(BivariateFunc(&(range__contains__ as BivariateFuncType)))),
 
             ..py_magic_methods_defaults()
         })
@@ -142,26 +188,34 @@ pub fn range_iterator__new__(arena: &mut PyArena, pyclass: Rc<PyClass>, args: &[
     ))))
 }
 
+pub fn range_iterator_next(iterator: &PyObject) -> Option<Option<i64>> {  // `None` when `iterator` isn't a range iterator, then `None` once it is exhausted
+    let PyObject::Mutable(inner) = iterator else {
+        return None;
+    };
+
+    let mut inner = inner.borrow_mut();
+    let PyMutableObject::Instance(instance) = &mut *inner else {
+        return None;
+    };
+
+    let range_iterator_internal = instance.internal.downcast_mut::<RangeIteratorInstance>()?;
+    let current = range_iterator_internal.current;
+    let stop = range_iterator_internal.stop;
+    let step = range_iterator_internal.step;
+
+    if (step > 0 && current >= stop) || (step < 0 && current <= stop) {
+        return Some(None);
+    }
+
+    range_iterator_internal.current += step;
+    Some(Some(current))
+}
+
 pub fn range_iterator__next__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
-    let mut pyself = pyself.expect_mutable().borrow_mut();
-    let instance = pyself.expect_instance_mut();
-    
-    if let Some(range_iterator_internal) = instance.internal.downcast_mut::<RangeIteratorInstance>() {
-        let current = &mut range_iterator_internal.current;
-        let stop = range_iterator_internal.stop;
-        let step = range_iterator_internal.step;
-        
-        if (step > 0 && *current >= stop) || (step < 0 && *current <= stop) {
-            return Ok(PyObject::stop_iteration())  // TODO change this, in CPython it just returns null
-        }
-        
-        let rtn_val = PyObject::new_immutable(PyImmutableObject::Int(*current));
-        
-        *current += step;
-        
-        Ok(rtn_val)
-    } else {
-        panic!("instance is not RangeIteratorType, its {:?}", instance)
+    match range_iterator_next(pyself) {
+        Some(Some(current)) => Ok(PyObject::new_int(current)),
+        Some(None) => Ok(PyObject::stop_iteration()),  // TODO change this, in CPython it just returns null
+        None => panic!("instance is not RangeIteratorType, its {:?}", pyself),
     }
 }
 
@@ -0,0 +1,114 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{FuncReturnType, NewFuncType, PyImmutableObject, PyObject, UnaryFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{NewFunc, UnaryFunc};
+use crate::pyarena::PyArena;
+
+pub fn expect_slice_bound(pyobj: &PyObject, arena: &mut PyArena) -> Result<Option<i64>, PyException> {
+    match *pyobj.expect_immutable() {
+        PyImmutableObject::None => Ok(None),
+        PyImmutableObject::Int(value) => Ok(Some(value)),
+        PyImmutableObject::Bool(value) => Ok(Some(value as i64)),
+        _ => Err(arena.exceptions.type_error.instantiate("slice indices must be integers or None or have an __index__ method".to_string())),
+    }
+}
+
+pub fn normalize_index(index: i64, length: usize) -> Option<usize> {  // resolves negative indexes, `None` if out of range
+    let index = if index < 0 { index + length as i64 } else { index };
+
+    if index < 0 || index >= length as i64 {
+        return None;
+    }
+
+    Some(index as usize)
+}
+
+pub fn adjust_slice_indices(start: Option<i64>, stop: Option<i64>, step: Option<i64>, length: usize, arena: &mut PyArena) -> Result<(i64, i64, i64, usize), PyException> {  // port of CPython's `PySlice_AdjustIndices`
+    let step = step.unwrap_or(1);
+    let length = length as i64;
+
+    if step == 0 {
+        return Err(arena.exceptions.value_error.instantiate("slice step cannot be zero".to_string()));
+    }
+
+    let clamp = |bound: Option<i64>, default: i64| {
+        match bound {
+            None => default,
+            Some(bound) if bound < 0 => {
+                let bound = bound + length;
+                if bound < 0 { if step < 0 { -1 } else { 0 } } else { bound }
+            }
+            Some(bound) if bound >= length => if step < 0 { length - 1 } else { length },
+            Some(bound) => bound,
+        }
+    };
+
+    let (start, stop) = if step > 0 {
+        (clamp(start, 0), clamp(stop, length))
+    } else {
+        (clamp(start, length - 1), clamp(stop, -1))
+    };
+
+    let slice_length = if step < 0 {
+        if stop < start { (start - stop - 1) / -step + 1 } else { 0 }
+    } else if start < stop {
+        (stop - start - 1) / step + 1
+    } else {
+        0
+    };
+
+    Ok((start, stop, step, slice_length as usize))
+}
+
+pub fn slice_indices(slice: &PyImmutableObject, length: usize, arena: &mut PyArena) -> Result<Vec<usize>, PyException> {
+    let PyImmutableObject::Slice {start, stop, step} = slice else {
+        panic!("Expected slice, got {:?}", slice)
+    };
+
+    let (start, _stop, step, slice_length) = adjust_slice_indices(*start, *stop, *step, length, arena)?;
+
+    Ok((0..slice_length as i64).map(|i| (start + i * step) as usize).collect())
+}
+
+pub fn slice__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    let bounds = pyargs.iter().map(|arg| expect_slice_bound(arg, arena)).collect::<Result<Vec<_>, _>>()?;
+
+    match bounds[..] {
+        [stop] => Ok(PyObject::new_slice(None, stop, None)),
+        [start, stop] => Ok(PyObject::new_slice(start, stop, None)),
+        [start, stop, step] => Ok(PyObject::new_slice(start, stop, step)),
+        _ => {
+            let message = format!("slice expected at least 1 argument, got {}", pyargs.len());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+pub fn slice__repr__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let PyImmutableObject::Slice {start, stop, step} = *pyself.expect_immutable() else {
+        panic!("Expected slice, got {:?}", pyself)
+    };
+
+    let format_bound = |bound: Option<i64>| bound.map(|bound| bound.to_string()).unwrap_or_else(|| "None".to_string());
+
+    Ok(PyObject::new_string(format!("slice({}, {}, {})", format_bound(start), format_bound(stop), format_bound(step))))
+}
+
+pub fn get_slice_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "slice".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(slice__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(slice__repr__ as UnaryFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
@@ -1,32 +1,239 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
 use crate::builtins::function_utils::call_function_1_arg_min;
-use crate::builtins::structure::magic_methods::PyMagicMethod;
-use crate::builtins::structure::pyobject::{FuncReturnType, PyObject};
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethod, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{BivariateFuncType, FuncReturnType, NewFuncType, PyImmutableObject, PyObject, UnaryFuncType, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, UnaryFunc, VariadicFunc};
+use crate::builtins::types::slice::{normalize_index, slice_indices};
+use crate::builtins::types::pybytes::{decode_bytes, encode_str, expect_bytes_like, hash_bytes, Encoding};
 use crate::pyarena::PyArena;
 
-pub fn py_str_tmp(obj: &PyObject, arena: &mut PyArena) -> FuncReturnType {
-    let str_fn = obj.expect_immutable().get_magic_method(&PyMagicMethod::Str, arena);
-    
+pub fn expect_str(pyobj: &PyObject, arena: &mut PyArena) -> Result<String, PyException> {
+    match pyobj {
+        PyObject::Immutable(inner) if matches!(**inner, PyImmutableObject::Str(_)) => Ok(inner.expect_string()),
+        other => {
+            let message = format!("expected str, got {}", other.clone_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+fn expect_str_promotion(pyobj: &PyObject, arena: &mut PyArena) -> Result<String, PyException> {
+    match pyobj {
+        PyObject::Immutable(inner) if matches!(**inner, PyImmutableObject::Str(_)) => Ok(inner.expect_string()),
+        _ => Err(arena.exceptions.not_implemented_error.empty()),
+    }
+}
+
+pub fn py_str(obj: &PyObject, arena: &mut PyArena) -> FuncReturnType {
+    let str_fn = obj.get_magic_method(&PyMagicMethod::Str, arena);
+
     if str_fn.is_none() {
         panic!("Object has no __str__ method");
     }
-    
+
     let str_fn = str_fn.unwrap();
-    
-    let str_rtn = call_function_1_arg_min(&str_fn, obj, &[], arena);
-    
-    str_rtn  // TODO assert str_rtn is a string
+
+    let str_rtn = call_function_1_arg_min(&str_fn, obj, &[], arena)?;
+
+    expect_str_result(str_rtn, "__str__", arena)
 }
 
 pub fn py_repr(obj: &PyObject, arena: &mut PyArena) -> FuncReturnType {
-    let repr_fn = obj.expect_immutable().get_magic_method(&PyMagicMethod::Repr, arena);
-    
+    let repr_fn = obj.get_magic_method(&PyMagicMethod::Repr, arena);
+
     if repr_fn.is_none() {
         panic!("Object has no __repr__ method");
     }
-    
+
     let repr_fn = repr_fn.unwrap();
-    
-    let repr_rtn = call_function_1_arg_min(&repr_fn, obj, &[], arena);
-    
-    repr_rtn  // TODO assert repr_rtn is a string
-}
\ No newline at end of file
+
+    let repr_rtn = call_function_1_arg_min(&repr_fn, obj, &[], arena)?;
+
+    expect_str_result(repr_rtn, "__repr__", arena)
+}
+
+fn expect_str_result(result: PyObject, method_name: &str, arena: &mut PyArena) -> FuncReturnType {  // callers rely on `__str__`/`__repr__` handing back a str
+    match result {
+        PyObject::Immutable(ref inner) if matches!(**inner, PyImmutableObject::Str(_)) => Ok(result),
+        other => {
+            let message = format!("{method_name} returned non-string (type {})", other.clone_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+pub fn format_str_literal(value: &str) -> String {
+    let quote = if value.contains('\'') && !value.contains('"') { '"' } else { '\'' };
+
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push(quote);
+
+    for char in value.chars() {
+        match char {
+            '\\' => literal.push_str("\\\\"),
+            '\t' => literal.push_str("\\t"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            char if char == quote => {
+                literal.push('\\');
+                literal.push(quote);
+            }
+            char if (char as u32) < 0x20 || char as u32 == 0x7f => literal.push_str(&format!("\\x{:02x}", char as u32)),
+            char => literal.push(char),
+        }
+    }
+
+    literal.push(quote);
+    literal
+}
+
+pub fn str__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    match pyargs {
+        [] => Ok(PyObject::new_string(String::new())),
+        [value] => py_str(value, arena),
+        [value, codec_args @ ..] => {  // str(b"...", encoding, errors) decodes
+            let value = expect_bytes_like(value, arena)?;
+            let encoding_name = expect_str(&codec_args[0], arena)?;
+            let errors = match codec_args.get(1) {
+                Some(errors) => expect_str(errors, arena)?,
+                None => "strict".to_string(),
+            };
+
+            let encoding = Encoding::from_name(&encoding_name).ok_or_else(|| arena.exceptions.lookup_error.instantiate(format!("unknown encoding: {encoding_name}")))?;
+
+            Ok(PyObject::new_string(decode_bytes(&value, encoding, &errors, arena)?))
+        }
+    }
+}
+
+pub fn str__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let value = expect_str(pyself, arena)?;
+    Ok(PyObject::new_string(format_str_literal(&value)))
+}
+
+pub fn str__str__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(pyself.clone())
+}
+
+pub fn str__hash__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {  // CPython hashes the PEP 393 representation, so the code units are widened to match it
+    let value = expect_str(pyself, arena)?;
+    let max_char = value.chars().map(|char| char as u32).max().unwrap_or(0);
+
+    let code_units: Vec<u8> = if max_char < 0x100 {
+        value.chars().map(|char| char as u8).collect()
+    } else if max_char < 0x10000 {
+        value.chars().flat_map(|char| (char as u16).to_le_bytes()).collect()
+    } else {
+        value.chars().flat_map(|char| (char as u32).to_le_bytes()).collect()
+    };
+
+    Ok(PyObject::new_int(hash_bytes(&code_units)))
+}
+
+pub fn str__len__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let value = expect_str(pyself, arena)?;
+    Ok(PyObject::new_int(value.chars().count() as i64))
+}
+
+pub fn str__getitem__(arena: &mut PyArena, pyself: &PyObject, key: &PyObject) -> FuncReturnType {
+    let chars: Vec<char> = expect_str(pyself, arena)?.chars().collect();
+
+    match *key.expect_immutable() {
+        PyImmutableObject::Int(index) => {
+            let index = normalize_index(index, chars.len()).ok_or_else(|| arena.exceptions.index_error.instantiate("string index out of range".to_string()))?;
+            Ok(PyObject::new_string(chars[index].to_string()))
+        }
+        ref slice @ PyImmutableObject::Slice {..} => {
+            let sliced = slice_indices(slice, chars.len(), arena)?.into_iter().map(|i| chars[i]).collect();
+            Ok(PyObject::new_string(sliced))
+        }
+        ref other => {
+            let message = format!("string indices must be integers, not '{}'", other.get_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+pub fn str__add__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let value = expect_str(pyself, arena)?;
+    let other_value = expect_str_promotion(other, arena)?;
+
+    Ok(PyObject::new_string(value + &other_value))
+}
+
+pub fn str__eq__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let value = expect_str(pyself, arena)?;
+    let other_value = expect_str_promotion(other, arena)?;
+
+    Ok(arena.statics.get_bool(value == other_value).clone())
+}
+
+pub fn str__ne__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let value = expect_str(pyself, arena)?;
+    let other_value = expect_str_promotion(other, arena)?;
+
+    Ok(arena.statics.get_bool(value != other_value).clone())
+}
+
+pub fn str__contains__(arena: &mut PyArena, pyself: &PyObject, item: &PyObject) -> FuncReturnType {
+    let value = expect_str(pyself, arena)?;
+
+    let substring = match item {
+        PyObject::Immutable(inner) if matches!(**inner, PyImmutableObject::Str(_)) => inner.expect_string(),
+        other => {
+            let message = format!("'in <string>' requires string as left operand, not {}", other.clone_class(arena).get_name());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    };
+
+    Ok(arena.statics.get_bool(value.contains(&substring)).clone())
+}
+
+pub fn str_encode(arena: &mut PyArena, pyself: &PyObject, args: &[PyObject]) -> FuncReturnType {
+    let value = expect_str(pyself, arena)?;
+
+    let encoding_name = match args.first() {
+        Some(encoding) => expect_str(encoding, arena)?,
+        None => "utf-8".to_string(),
+    };
+    let errors = match args.get(1) {
+        Some(errors) => expect_str(errors, arena)?,
+        None => "strict".to_string(),
+    };
+
+    let encoding = Encoding::from_name(&encoding_name).ok_or_else(|| arena.exceptions.lookup_error.instantiate(format!("unknown encoding: {encoding_name}")))?;
+
+    Ok(PyObject::new_bytes(encode_str(&value, encoding, &errors, arena)?))
+}
+
+pub fn get_str_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "str".to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("encode".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(str_encode as VariadicFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(str__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(str__repr__ as UnaryFuncType)))),
+            __str__: Some(Rc::new(UnaryFunc(&(str__str__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(str__hash__ as UnaryFuncType)))),
+
+            __add__: Some(Rc::new(BivariateFunc(&(str__add__ as BivariateFuncType)))),
+
+            __eq__: Some(Rc::new(BivariateFunc(&(str__eq__ as BivariateFuncType)))),
+            __ne__: Some(Rc::new(BivariateFunc(&(str__ne__ as BivariateFuncType)))),
+            __contains__: Some(Rc::new(BivariateFunc(&(str__contains__ as BivariateFuncType)))),
+
+            __len__: Some(Rc::new(UnaryFunc(&(str__len__ as UnaryFuncType)))),
+            __getitem__: Some(Rc::new(BivariateFunc(&(str__getitem__ as BivariateFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
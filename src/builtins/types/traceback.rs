@@ -0,0 +1,77 @@
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::structure::magic_methods::py_magic_methods_defaults;
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{EmptyFuncReturnType, PyMutableObject, PyObject};
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+pub struct TracebackInstance {  // one frame an exception unwound through, `next` leads towards where it was raised
+    name: String,
+    line: usize,
+    next: Option<PyObject>,
+}
+
+impl PyInstanceInternal for TracebackInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, key: &str, arena: &mut PyArena) -> Option<PyObject> {
+        match key {
+            "tb_lineno" => Some(PyObject::new_int(self.line as i64)),
+            "tb_next" => Some(self.next.clone().unwrap_or_else(|| arena.statics.none().clone())),
+            _ => None,
+        }
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        self.next.iter().for_each(visit);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        self.next.take().into_iter().collect()
+    }
+}
+
+pub fn new_traceback(name: String, line: usize, next: Option<PyObject>, arena: &mut PyArena) -> PyObject {
+    PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.traceback_class.clone(),
+        Box::new(TracebackInstance { name, line, next })
+    )))
+}
+
+pub fn is_traceback(pyobj: &PyObject) -> bool {
+    match pyobj {
+        PyObject::Mutable(inner) => match inner.try_borrow() {
+            Ok(inner) => matches!(&*inner, PyMutableObject::Instance(instance) if instance.internal.downcast_ref::<TracebackInstance>().is_some()),
+            Err(_) => false,  // being mutated, so it can't be inspected
+        },
+        _ => false,
+    }
+}
+
+pub fn traceback_entries(traceback: &PyObject) -> Vec<(String, usize)> {  // the function name and line of every frame, outermost first
+    let mut entries = vec![];
+    let mut link = Some(traceback.clone());
+
+    while let Some(traceback) = link {
+        let traceback = traceback.expect_mutable().borrow();
+        let traceback = traceback.expect_instance().internal.downcast_ref::<TracebackInstance>().expect("instance is a traceback");
+
+        entries.push((traceback.name.clone(), traceback.line));
+        link = traceback.next.clone();
+    }
+
+    entries
+}
+
+pub fn get_traceback_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "traceback".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(py_magic_methods_defaults()),
+    }.create()
+}
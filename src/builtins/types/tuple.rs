@@ -0,0 +1,274 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::collect_iterable;
+use crate::builtins::functions::compare::eq_with_identity;
+use crate::builtins::functions::hash::hash_value;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{BivariateFuncType, EmptyFuncReturnType, FuncReturnType, NewFuncType, PyImmutableObject, PyMutableObject, PyObject, UnaryFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, UnaryFunc};
+use crate::builtins::types::slice::{normalize_index, slice_indices};
+use crate::builtins::types::str::py_repr;
+use crate::pyarena::PyArena;
+
+#[derive(Debug)]
+pub struct TupleInstance {
+    items: Vec<PyObject>,
+}
+
+impl PyInstanceInternal for TupleInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, _key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        None
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        self.items.iter().for_each(visit);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        std::mem::take(&mut self.items)
+    }
+}
+
+pub fn new_tuple(items: Vec<PyObject>, arena: &mut PyArena) -> PyObject {
+    PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.tuple_class.clone(),
+        Box::new(TupleInstance { items })
+    )))
+}
+
+pub fn try_tuple_items(pyobj: &PyObject) -> Option<Vec<PyObject>> {  // clones the items so they can be used while calling back into python
+    let PyObject::Mutable(inner) = pyobj else {
+        return None;
+    };
+
+    let inner = inner.borrow();
+
+    match *inner {
+        PyMutableObject::Instance(ref instance) => instance.internal.downcast_ref::<TupleInstance>().map(|tuple| tuple.items.clone()),
+        _ => None,
+    }
+}
+
+fn expect_tuple_items(pyobj: &PyObject) -> Vec<PyObject> {
+    try_tuple_items(pyobj).unwrap_or_else(|| panic!("Expected tuple, got {:?}", pyobj))
+}
+
+fn expect_tuple_items_promotion(pyobj: &PyObject, arena: &mut PyArena) -> Result<Vec<PyObject>, PyException> {
+    try_tuple_items(pyobj).ok_or_else(|| arena.exceptions.not_implemented_error.empty())
+}
+
+fn items_equal(left: &[PyObject], right: &[PyObject], arena: &mut PyArena) -> Result<bool, PyException> {
+    if left.len() != right.len() {
+        return Ok(false);
+    }
+
+    for (left, right) in left.iter().zip(right) {
+        if !eq_with_identity(left, right, arena)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+pub fn tuple__new__(arena: &mut PyArena, _pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    let items = match pyargs {
+        [] => vec![],
+        [iterable] => collect_iterable(iterable, arena)?,
+        _ => {
+            let message = format!("tuple expected at most 1 argument, got {}", pyargs.len());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    };
+
+    Ok(new_tuple(items, arena))
+}
+
+pub fn tuple__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let items = expect_tuple_items(pyself);
+
+    let reprs = items.iter().map(|item| Ok(py_repr(item, arena)?.expect_immutable().expect_string())).collect::<Result<Vec<_>, PyException>>()?;
+
+    if reprs.len() == 1 {
+        return Ok(PyObject::new_string(format!("({},)", reprs[0])));
+    }
+
+    Ok(PyObject::new_string(format!("({})", reprs.join(", "))))
+}
+
+pub fn tuple__hash__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {  // port of CPython's xxHash based `tuplehash`
+    const XXPRIME_1: u64 = 11400714785074694791;
+    const XXPRIME_2: u64 = 14029467366897019727;
+    const XXPRIME_5: u64 = 2870177450012600261;
+
+    let items = expect_tuple_items(pyself);
+    let mut acc = XXPRIME_5;
+
+    for item in items.iter() {
+        let lane = hash_value(item, arena)? as u64;
+        acc = acc.wrapping_add(lane.wrapping_mul(XXPRIME_2));
+        acc = acc.rotate_left(31);
+        acc = acc.wrapping_mul(XXPRIME_1);
+    }
+
+    acc = acc.wrapping_add(items.len() as u64 ^ (XXPRIME_5 ^ 3527539));
+
+    if acc == u64::MAX {
+        return Ok(PyObject::new_int(1546275796));
+    }
+
+    Ok(PyObject::new_int(acc as i64))
+}
+
+pub fn tuple__len__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_int(expect_tuple_items(pyself).len() as i64))
+}
+
+pub fn tuple__getitem__(arena: &mut PyArena, pyself: &PyObject, key: &PyObject) -> FuncReturnType {
+    let items = expect_tuple_items(pyself);
+
+    match *key.expect_immutable() {
+        PyImmutableObject::Int(index) => {
+            let index = normalize_index(index, items.len()).ok_or_else(|| arena.exceptions.index_error.instantiate("tuple index out of range".to_string()))?;
+            Ok(items[index].clone())
+        }
+        ref slice @ PyImmutableObject::Slice {..} => {
+            let sliced = slice_indices(slice, items.len(), arena)?.into_iter().map(|i| items[i].clone()).collect();
+            Ok(new_tuple(sliced, arena))
+        }
+        ref other => {
+            let message = format!("tuple indices must be integers or slices, not {}", other.get_class(arena).get_name());
+            Err(arena.exceptions.type_error.instantiate(message))
+        }
+    }
+}
+
+pub fn tuple__add__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let mut items = expect_tuple_items(pyself);
+    items.extend(expect_tuple_items_promotion(other, arena)?);
+
+    Ok(new_tuple(items, arena))
+}
+
+pub fn tuple__eq__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let items = expect_tuple_items(pyself);
+    let other_items = expect_tuple_items_promotion(other, arena)?;
+
+    let equal = items_equal(&items, &other_items, arena)?;
+    Ok(arena.statics.get_bool(equal).clone())
+}
+
+pub fn tuple__ne__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    let items = expect_tuple_items(pyself);
+    let other_items = expect_tuple_items_promotion(other, arena)?;
+
+    let equal = items_equal(&items, &other_items, arena)?;
+    Ok(arena.statics.get_bool(!equal).clone())
+}
+
+pub fn tuple__contains__(arena: &mut PyArena, pyself: &PyObject, item: &PyObject) -> FuncReturnType {
+    let items = expect_tuple_items(pyself);
+
+    for candidate in items.iter() {
+        if eq_with_identity(candidate, item, arena)? {
+            return Ok(arena.statics.get_bool(true).clone());
+        }
+    }
+
+    Ok(arena.statics.get_bool(false).clone())
+}
+
+pub fn tuple__iter__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        arena.globals.tuple_iterator_class.clone(),
+        Box::new(TupleIteratorInstance { items: expect_tuple_items(pyself), index: 0 })
+    ))))
+}
+
+pub fn get_tuple_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "tuple".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(tuple__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(tuple__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(tuple__hash__ as UnaryFuncType)))),
+
+            __add__: Some(Rc::new(BivariateFunc(&(tuple__add__ as BivariateFuncType)))),
+
+            __iter__: Some(Rc::new(UnaryFunc(&(tuple__iter__ as UnaryFuncType)))),
+
+            __eq__: Some(Rc::new(BivariateFunc(&(tuple__eq__ as BivariateFuncType)))),
+            __ne__: Some(Rc::new(BivariateFunc(&(tuple__ne__ as BivariateFuncType)))),
+            __contains__: Some(Rc::new(BivariateFunc(&(tuple__contains__ as BivariateFuncType)))),
+
+            __len__: Some(Rc::new(UnaryFunc(&(tuple__len__ as UnaryFuncType)))),
+            __getitem__: Some(Rc::new(BivariateFunc(&(tuple__getitem__ as BivariateFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
+
+#[derive(Debug)]
+struct TupleIteratorInstance {
+    items: Vec<PyObject>,
+    index: usize,
+}
+
+impl PyInstanceInternal for TupleIteratorInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, _key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        None
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        self.items.iter().for_each(visit);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        std::mem::take(&mut self.items)
+    }
+}
+
+pub fn tuple_iterator__next__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let mut pyself = pyself.expect_mutable().borrow_mut();
+    let instance = pyself.expect_instance_mut();
+
+    if let Some(tuple_iterator_internal) = instance.internal.downcast_mut::<TupleIteratorInstance>() {
+        let Some(item) = tuple_iterator_internal.items.get(tuple_iterator_internal.index).cloned() else {
+            return Ok(PyObject::stop_iteration());
+        };
+
+        tuple_iterator_internal.index += 1;
+
+        Ok(item)
+    } else {
+        panic!("instance is not TupleIteratorInstance, its {:?}", instance)
+    }
+}
+
+pub fn get_tuple_iterator_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {  // Hidden class
+        name: "tuple_iterator".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __next__: Some(Rc::new(UnaryFunc(&(tuple_iterator__next__ as UnaryFuncType)))),
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
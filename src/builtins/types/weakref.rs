@@ -0,0 +1,518 @@
+#![allow(non_snake_case)]
+use std::rc::Rc;
+use ahash::AHashMap;
+use crate::builtins::function_utils::collect_iterable;
+use crate::builtins::functions::compare::{compare_op, eq_with_identity};
+use crate::builtins::functions::hash::hash_value;
+use crate::builtins::structure::magic_methods::{py_magic_methods_defaults, PyMagicMethods};
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyinstance::{PyInstance, PyInstanceInternal};
+use crate::builtins::structure::pyobject::{BivariateFuncType, EmptyFuncReturnType, FuncReturnType, NewFuncType, PyMutableObject, PyObject, PyWeakPointer, UnaryFuncType, VariadicFuncType};
+use crate::builtins::structure::pyobject::PyInternalFunction::{BivariateFunc, NewFunc, UnaryFunc, VariadicFunc};
+use crate::builtins::types::dict::key_error;
+use crate::builtins::types::list::{list__iter__, new_list};
+use crate::builtins::types::object::unhashable__hash__;
+use crate::builtins::types::str::py_str;
+use crate::builtins::types::tuple::{new_tuple, try_tuple_items};
+use crate::parser::Comparator;
+use crate::pyarena::PyArena;
+
+pub fn new_weak_pointer(pyobj: &PyObject, arena: &mut PyArena) -> Result<PyWeakPointer<PyMutableObject>, PyException> {  // only instances of user-defined classes can be weakly referenced
+    if let PyObject::Mutable(inner) = pyobj {
+        if matches!(*inner.borrow(), PyMutableObject::Instance(ref instance) if instance.class.is_user_defined()) {
+            return Ok(inner.downgrade());
+        }
+    }
+
+    let message = format!("cannot create weak reference to '{}' object", pyobj.clone_class(arena).get_name());
+    Err(arena.exceptions.type_error.instantiate(message))
+}
+
+#[derive(Debug)]
+pub struct WeakRefInstance {  // backs both `weakref.ref` and `weakref.proxy`
+    referent: PyWeakPointer<PyMutableObject>,
+    callback: Option<PyObject>,  // taken once the referent is freed
+    hash: Option<i64>,  // kept, so the hash stays usable after the referent is gone
+}
+
+impl PyInstanceInternal for WeakRefInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, key: &str, arena: &mut PyArena) -> Option<PyObject> {
+        match key {
+            "__callback__" => Some(self.callback.clone().unwrap_or_else(|| arena.statics.none().clone())),
+            _ => None,
+        }
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        self.callback.iter().for_each(visit);
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        self.callback.take().into_iter().collect()
+    }
+}
+
+fn new_weakref(pyclass: Rc<PyClass>, pyargs: &[PyObject], arena: &mut PyArena) -> FuncReturnType {
+    let (referent, callback) = match pyargs {
+        [referent] => (referent, None),
+        [referent, callback] => (referent, Some(callback.clone()).filter(|callback| !callback.is_none())),
+        _ => {
+            let message = format!("{}() takes 1 or 2 arguments ({} given)", pyclass.get_name(), pyargs.len());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    };
+
+    let has_callback = callback.is_some();
+    let weakref = PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        pyclass,
+        Box::new(WeakRefInstance { referent: new_weak_pointer(referent, arena)?, callback, hash: None })
+    )));
+
+    if has_callback {  // the referent notifies these when it's freed
+        referent.expect_mutable().borrow_mut().expect_instance_mut().weakrefs.push(weakref.expect_mutable().downgrade());
+    }
+
+    Ok(weakref)
+}
+
+fn with_weakref<T>(pyself: &PyObject, func: impl FnOnce(&mut WeakRefInstance) -> T) -> T {
+    let mut pyself = pyself.expect_mutable().borrow_mut();
+    let weakref = pyself.expect_instance_mut().internal.downcast_mut::<WeakRefInstance>().expect("instance is a weak reference");
+
+    func(weakref)
+}
+
+fn get_referent(pyself: &PyObject) -> Option<PyObject> {
+    with_weakref(pyself, |weakref| weakref.referent.upgrade().map(PyObject::Mutable))
+}
+
+fn is_weakref(pyobj: &PyObject, arena: &PyArena) -> bool {
+    matches!(pyobj, PyObject::Mutable(inner) if inner.borrow().get_class().is_subclass_of(&arena.globals.weakref_class))
+}
+
+pub fn take_weakref_callback(weakref: &PyObject) -> Option<PyObject> {
+    with_weakref(weakref, |weakref| weakref.callback.take())
+}
+
+pub fn weakref__new__(arena: &mut PyArena, pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    new_weakref(pyclass, pyargs, arena)
+}
+
+pub fn weakref__call__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    if !pyargs.is_empty() {
+        let message = format!("weakref() takes no arguments ({} given)", pyargs.len());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    Ok(get_referent(pyself).unwrap_or_else(|| arena.statics.none().clone()))
+}
+
+pub fn weakref__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let repr = match get_referent(pyself) {
+        Some(referent) => format!("<weakref at {:#x}; to '{}' at {:#x}>", pyself.get_memory_location(), referent.clone_class(arena).get_name(), referent.get_memory_location()),
+        None => format!("<weakref at {:#x}; dead>", pyself.get_memory_location()),
+    };
+
+    Ok(PyObject::new_string(repr))
+}
+
+pub fn weakref__hash__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    if let Some(hash) = with_weakref(pyself, |weakref| weakref.hash) {
+        return Ok(PyObject::new_int(hash));
+    }
+
+    let Some(referent) = get_referent(pyself) else {
+        return Err(arena.exceptions.type_error.instantiate("weak object has gone away".to_string()));
+    };
+
+    let hash = hash_value(&referent, arena)?;
+    with_weakref(pyself, |weakref| weakref.hash = Some(hash));
+
+    Ok(PyObject::new_int(hash))
+}
+
+fn weakref_compare(pyself: &PyObject, other: &PyObject, comp: Comparator, arena: &mut PyArena) -> FuncReturnType {  // live references compare their referents, dead ones compare by identity
+    if !is_weakref(other, arena) {
+        return Err(arena.exceptions.not_implemented_error.empty());
+    }
+
+    match (get_referent(pyself), get_referent(other)) {
+        (Some(left), Some(right)) => compare_op(&left, &right, &comp, arena),
+        _ => Ok(arena.statics.get_bool(pyself.is_same_object(other) == matches!(comp, Comparator::Equal)).clone()),
+    }
+}
+
+pub fn weakref__eq__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    weakref_compare(pyself, other, Comparator::Equal, arena)
+}
+
+pub fn weakref__ne__(arena: &mut PyArena, pyself: &PyObject, other: &PyObject) -> FuncReturnType {
+    weakref_compare(pyself, other, Comparator::NotEqual, arena)
+}
+
+pub fn get_weakref_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "weakref".to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("__call__".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(weakref__call__ as VariadicFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(weakref__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(weakref__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(weakref__hash__ as UnaryFuncType)))),
+
+            __eq__: Some(Rc::new(BivariateFunc(&(weakref__eq__ as BivariateFuncType)))),
+            __ne__: Some(Rc::new(BivariateFunc(&(weakref__ne__ as BivariateFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
+
+pub fn new_weakproxy(pyargs: &[PyObject], arena: &mut PyArena) -> FuncReturnType {
+    new_weakref(arena.globals.weakproxy_class.clone(), pyargs, arena)
+}
+
+pub fn resolve_proxy(pyobj: &PyObject, arena: &mut PyArena) -> Result<Option<PyObject>, PyException> {  // attribute access and method calls on a proxy go to its referent
+    let PyObject::Mutable(inner) = pyobj else {
+        return Ok(None);
+    };
+
+    if !Rc::ptr_eq(inner.borrow().get_class(), &arena.globals.weakproxy_class) {
+        return Ok(None);
+    }
+
+    expect_proxy_referent(pyobj, arena).map(Some)
+}
+
+fn expect_proxy_referent(pyself: &PyObject, arena: &mut PyArena) -> FuncReturnType {
+    get_referent(pyself).ok_or_else(|| arena.exceptions.reference_error.instantiate("weakly-referenced object no longer exists".to_string()))
+}
+
+pub fn weakproxy__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let repr = match get_referent(pyself) {
+        Some(referent) => format!("<weakproxy at {:#x}; to '{}' at {:#x}>", pyself.get_memory_location(), referent.clone_class(arena).get_name(), referent.get_memory_location()),
+        None => format!("<weakproxy at {:#x}; dead>", pyself.get_memory_location()),
+    };
+
+    Ok(PyObject::new_string(repr))
+}
+
+pub fn weakproxy__str__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    let referent = expect_proxy_referent(pyself, arena)?;
+    py_str(&referent, arena)
+}
+
+pub fn get_weakproxy_class(object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: "weakproxy".to_string(),
+        super_classes: vec![object_class],
+        attributes: AHashMap::new(),
+        magic_methods: Box::new(PyMagicMethods {
+            __str__: Some(Rc::new(UnaryFunc(&(weakproxy__str__ as UnaryFuncType)))),
+            __repr__: Some(Rc::new(UnaryFunc(&(weakproxy__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(unhashable__hash__ as UnaryFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
+
+#[derive(Debug)]
+enum WeakSlot {
+    Strong(PyObject),
+    Weak(PyWeakPointer<PyMutableObject>),
+}
+
+impl WeakSlot {
+    fn get(&self) -> Option<PyObject> {
+        match self {
+            WeakSlot::Strong(pyobj) => Some(pyobj.clone()),
+            WeakSlot::Weak(pointer) => pointer.upgrade().map(PyObject::Mutable),
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        match self {
+            WeakSlot::Strong(_) => true,
+            WeakSlot::Weak(pointer) => pointer.is_alive(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct WeakMapEntry {
+    key: WeakSlot,
+    value: WeakSlot,
+}
+
+#[derive(Debug)]
+pub struct WeakMapInstance {  // backs `WeakValueDictionary` and `WeakKeyDictionary`, entries whose key or value died are skipped and purged lazily
+    buckets: AHashMap<i64, Vec<WeakMapEntry>>,  // by the hash of the key, taken while the key was alive
+    weak_keys: bool,
+    entries: usize,
+    entries_after_purge: usize,
+}
+
+impl WeakMapInstance {
+    fn live_entries(&self) -> Vec<(PyObject, PyObject)> {
+        self.buckets.values().flatten().filter_map(|entry| Some((entry.key.get()?, entry.value.get()?))).collect()
+    }
+
+    fn bucket_keys(&self, hash: i64) -> Vec<PyObject> {
+        self.buckets.get(&hash).into_iter().flatten().filter(|entry| entry.value.is_alive()).filter_map(|entry| entry.key.get()).collect()
+    }
+
+    fn entry_mut(&mut self, hash: i64, key: &PyObject) -> Option<&mut WeakMapEntry> {  // `key` must be the stored key object, found with `find_key`
+        self.buckets.get_mut(&hash)?.iter_mut().find(|entry| entry.key.get().is_some_and(|stored| stored.is_same_object(key)))
+    }
+
+    fn remove(&mut self, hash: i64, key: &PyObject) -> Option<WeakMapEntry> {
+        let bucket = self.buckets.get_mut(&hash)?;
+        let index = bucket.iter().position(|entry| entry.key.get().is_some_and(|stored| stored.is_same_object(key)))?;
+        self.entries -= 1;
+
+        Some(bucket.swap_remove(index))
+    }
+
+    fn insert(&mut self, hash: i64, entry: WeakMapEntry) {
+        self.buckets.entry(hash).or_default().push(entry);
+        self.entries += 1;
+
+        if self.entries > 2 * self.entries_after_purge + 8 {  // amortized, so dead entries can't pile up
+            self.buckets.retain(|_hash, bucket| {
+                bucket.retain(|entry| entry.key.is_alive() && entry.value.is_alive());
+                !bucket.is_empty()
+            });
+            self.entries = self.buckets.values().map(Vec::len).sum();
+            self.entries_after_purge = self.entries;
+        }
+    }
+}
+
+impl PyInstanceInternal for WeakMapInstance {
+    fn set_field(&mut self, _key: String, _value: PyObject, _arena: &mut PyArena) -> Option<EmptyFuncReturnType> {
+        None
+    }
+
+    fn get_field(&self, _key: &str, _arena: &mut PyArena) -> Option<PyObject> {
+        None
+    }
+
+    fn traverse(&self, visit: &mut dyn FnMut(&PyObject)) {
+        for entry in self.buckets.values().flatten() {
+            for slot in [&entry.key, &entry.value] {
+                if let WeakSlot::Strong(pyobj) = slot {
+                    visit(pyobj);
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self) -> Vec<PyObject> {
+        let released = self.buckets.drain().flat_map(|(_hash, bucket)| bucket).flat_map(|entry| [entry.key, entry.value]);
+        released.filter_map(|slot| match slot {
+            WeakSlot::Strong(pyobj) => Some(pyobj),
+            WeakSlot::Weak(_) => None,
+        }).collect()
+    }
+}
+
+fn with_weak_map<T>(pyself: &PyObject, func: impl FnOnce(&mut WeakMapInstance) -> T) -> T {
+    let mut pyself = pyself.expect_mutable().borrow_mut();
+    let weak_map = pyself.expect_instance_mut().internal.downcast_mut::<WeakMapInstance>().expect("instance is a weak dictionary");
+
+    func(weak_map)
+}
+
+fn find_key(pyself: &PyObject, key: &PyObject, arena: &mut PyArena) -> Result<(i64, Option<PyObject>), PyException> {  // the hash of `key` and the equal key that is stored
+    let hash = hash_value(key, arena)?;
+
+    for candidate in with_weak_map(pyself, |weak_map| weak_map.bucket_keys(hash)) {  // the borrow is released, `__eq__` may run python code
+        if eq_with_identity(&candidate, key, arena)? {
+            return Ok((hash, Some(candidate)));
+        }
+    }
+
+    Ok((hash, None))
+}
+
+fn lookup(pyself: &PyObject, key: &PyObject, arena: &mut PyArena) -> Result<Option<PyObject>, PyException> {
+    let (hash, Some(stored)) = find_key(pyself, key, arena)? else {
+        return Ok(None);
+    };
+
+    Ok(with_weak_map(pyself, |weak_map| weak_map.entry_mut(hash, &stored).and_then(|entry| entry.value.get())))
+}
+
+fn set_entry(pyself: &PyObject, key: PyObject, value: PyObject, arena: &mut PyArena) -> EmptyFuncReturnType {
+    let weak_keys = with_weak_map(pyself, |weak_map| weak_map.weak_keys);
+    let (key_slot, value_slot) = if weak_keys {
+        (WeakSlot::Weak(new_weak_pointer(&key, arena)?), WeakSlot::Strong(value))
+    } else {
+        (WeakSlot::Strong(key.clone()), WeakSlot::Weak(new_weak_pointer(&value, arena)?))
+    };
+
+    let (hash, stored) = find_key(pyself, &key, arena)?;
+
+    with_weak_map(pyself, |weak_map| {
+        match stored.and_then(|stored| weak_map.entry_mut(hash, &stored)) {
+            Some(entry) => entry.value = value_slot,  // like a dict, the first key is kept
+            None => weak_map.insert(hash, WeakMapEntry { key: key_slot, value: value_slot }),
+        }
+    });
+
+    Ok(())
+}
+
+fn new_weak_map(pyclass: Rc<PyClass>, pyargs: &[PyObject], arena: &mut PyArena) -> FuncReturnType {
+    let weak_keys = Rc::ptr_eq(&pyclass, &arena.globals.weak_key_dictionary_class);
+    let name = pyclass.get_name().clone();
+
+    let weak_map = PyObject::new_mutable(PyMutableObject::Instance(PyInstance::new_empty_attrs(
+        pyclass,
+        Box::new(WeakMapInstance { buckets: AHashMap::new(), weak_keys, entries: 0, entries_after_purge: 0 })
+    )));
+
+    match pyargs {
+        [] => {}
+        [pairs] => {
+            for pair in collect_iterable(pairs, arena)? {
+                let Some([key, value]) = try_tuple_items(&pair).and_then(|items| <[PyObject; 2]>::try_from(items).ok()) else {
+                    return Err(arena.exceptions.type_error.instantiate(format!("{name}() expects an iterable of (key, value) tuples")));
+                };
+
+                set_entry(&weak_map, key, value, arena)?;
+            }
+        }
+        _ => {
+            let message = format!("{name} expected at most 1 argument, got {}", pyargs.len());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    }
+
+    Ok(weak_map)
+}
+
+pub fn weak_map__new__(arena: &mut PyArena, pyclass: Rc<PyClass>, pyargs: &[PyObject]) -> FuncReturnType {
+    new_weak_map(pyclass, pyargs, arena)
+}
+
+pub fn weak_map__repr__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_string(format!("<{} at {:#x}>", pyself.clone_class(arena).get_name(), pyself.get_memory_location())))
+}
+
+pub fn weak_map__len__(_arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {
+    Ok(PyObject::new_int(with_weak_map(pyself, |weak_map| weak_map.live_entries().len()) as i64))
+}
+
+pub fn weak_map__getitem__(arena: &mut PyArena, pyself: &PyObject, key: &PyObject) -> FuncReturnType {
+    match lookup(pyself, key, arena)? {
+        Some(value) => Ok(value),
+        None => Err(key_error(key, arena)),
+    }
+}
+
+pub fn weak_map__setitem__(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let [key, value] = pyargs else {
+        panic!("__setitem__ takes a key and a value")
+    };
+
+    set_entry(pyself, key.clone(), value.clone(), arena)?;
+    Ok(arena.statics.none().clone())
+}
+
+pub fn weak_map__contains__(arena: &mut PyArena, pyself: &PyObject, key: &PyObject) -> FuncReturnType {
+    let found = lookup(pyself, key, arena)?.is_some();
+    Ok(arena.statics.get_bool(found).clone())
+}
+
+pub fn weak_map__iter__(arena: &mut PyArena, pyself: &PyObject) -> FuncReturnType {  // iterates over a snapshot of the keys
+    let keys = weak_map_keys(arena, pyself, &[])?;
+    list__iter__(arena, &keys)
+}
+
+pub fn weak_map_get(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let (key, default) = match pyargs {
+        [key] => (key, arena.statics.none().clone()),
+        [key, default] => (key, default.clone()),
+        _ => {
+            let message = format!("get expected 1 or 2 arguments, got {}", pyargs.len());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    };
+
+    Ok(lookup(pyself, key, arena)?.unwrap_or(default))
+}
+
+pub fn weak_map_pop(arena: &mut PyArena, pyself: &PyObject, pyargs: &[PyObject]) -> FuncReturnType {
+    let (key, default) = match pyargs {
+        [key] => (key, None),
+        [key, default] => (key, Some(default.clone())),
+        _ => {
+            let message = format!("pop expected 1 or 2 arguments, got {}", pyargs.len());
+            return Err(arena.exceptions.type_error.instantiate(message));
+        }
+    };
+
+    let (hash, stored) = find_key(pyself, key, arena)?;
+    let value = stored.and_then(|stored| with_weak_map(pyself, |weak_map| weak_map.remove(hash, &stored))).and_then(|entry| entry.value.get());
+
+    match (value, default) {
+        (Some(value), _) => Ok(value),
+        (None, Some(default)) => Ok(default),
+        (None, None) => Err(key_error(key, arena)),
+    }
+}
+
+pub fn weak_map_keys(arena: &mut PyArena, pyself: &PyObject, _pyargs: &[PyObject]) -> FuncReturnType {
+    let keys = with_weak_map(pyself, |weak_map| weak_map.live_entries()).into_iter().map(|(key, _value)| key).collect();
+    Ok(new_list(keys, arena))
+}
+
+pub fn weak_map_values(arena: &mut PyArena, pyself: &PyObject, _pyargs: &[PyObject]) -> FuncReturnType {
+    let values = with_weak_map(pyself, |weak_map| weak_map.live_entries()).into_iter().map(|(_key, value)| value).collect();
+    Ok(new_list(values, arena))
+}
+
+pub fn weak_map_items(arena: &mut PyArena, pyself: &PyObject, _pyargs: &[PyObject]) -> FuncReturnType {
+    let entries = with_weak_map(pyself, |weak_map| weak_map.live_entries());
+    let items = entries.into_iter().map(|(key, value)| new_tuple(vec![key, value], arena)).collect();
+    Ok(new_list(items, arena))
+}
+
+pub fn get_weak_map_class(name: &str, object_class: Rc<PyClass>) -> PyClass {
+    PyClass::Internal {
+        name: name.to_string(),
+        super_classes: vec![object_class],
+        attributes: vec![
+            ("get".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(weak_map_get as VariadicFuncType))))),
+            ("pop".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(weak_map_pop as VariadicFuncType))))),
+            ("keys".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(weak_map_keys as VariadicFuncType))))),
+            ("values".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(weak_map_values as VariadicFuncType))))),
+            ("items".to_string(), PyObject::new_internal_func(Rc::new(VariadicFunc(&(weak_map_items as VariadicFuncType))))),
+        ].into_iter().collect::<AHashMap<_, _>>(),
+        magic_methods: Box::new(PyMagicMethods {
+            __new__: Some(Rc::new(NewFunc(&(weak_map__new__ as NewFuncType)))),
+
+            __repr__: Some(Rc::new(UnaryFunc(&(weak_map__repr__ as UnaryFuncType)))),
+            __hash__: Some(Rc::new(UnaryFunc(&(unhashable__hash__ as UnaryFuncType)))),
+
+            __iter__: Some(Rc::new(UnaryFunc(&(weak_map__iter__ as UnaryFuncType)))),
+            __contains__: Some(Rc::new(BivariateFunc(&(weak_map__contains__ as BivariateFuncType)))),
+
+            __len__: Some(Rc::new(UnaryFunc(&(weak_map__len__ as UnaryFuncType)))),
+            __getitem__: Some(Rc::new(BivariateFunc(&(weak_map__getitem__ as BivariateFuncType)))),
+            __setitem__: Some(Rc::new(VariadicFunc(&(weak_map__setitem__ as VariadicFuncType)))),
+
+            ..py_magic_methods_defaults()
+        })
+    }.create()
+}
@@ -0,0 +1,152 @@
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: RustyPython [option] ... [-c cmd | -m mod | file | -] [arg] ...";
+
+const HELP: &str = "\
+Options:
+-c cmd : program passed in as string (terminates option list)
+-d     : print the parse tree before running the program
+-h     : print this help message and exit (also -? or --help)
+-m mod : run library module as a script (terminates option list)
+-V     : print the version number and exit (also --version)
+file   : program read from script file
+-      : program read from stdin (default; interactive mode if a tty)
+arg ...: arguments passed to program in sys.argv[1:]";
+
+#[derive(Debug)]
+pub enum Source {
+    Script(PathBuf),
+    Command(String),
+    Module(String),
+    Stdin,
+    Interactive,
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub source: Source,
+    pub argv: Vec<String>,  // becomes `sys.argv`, the first item names the source like CPython
+    pub dump_parse_tree: bool,
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("{message}\n{USAGE}\nTry `RustyPython -h' for more information.");
+    2
+}
+
+pub fn parse_args(args: &[String]) -> Result<Options, i32> {  // `Err` holds the exit status when there is nothing to run
+    let mut dump_parse_tree = false;
+    let mut index = 0;
+
+    while index < args.len() {
+        let arg = &args[index];
+        index += 1;
+
+        match arg.as_str() {
+            "--help" => {
+                println!("{USAGE}\n{HELP}");
+                return Err(0);
+            }
+            "--version" => {
+                println!("RustyPython {}", env!("CARGO_PKG_VERSION"));
+                return Err(0);
+            }
+            "-" => {
+                return Ok(Options { source: Source::Stdin, argv: args[index - 1..].to_vec(), dump_parse_tree });
+            }
+            flags if flags.starts_with('-') && !flags.starts_with("--") => {
+                for (position, flag) in flags.char_indices().skip(1) {
+                    match flag {
+                        'd' => dump_parse_tree = true,
+                        'h' | '?' => {
+                            println!("{USAGE}\n{HELP}");
+                            return Err(0);
+                        }
+                        'V' => {
+                            println!("RustyPython {}", env!("CARGO_PKG_VERSION"));
+                            return Err(0);
+                        }
+                        'c' | 'm' => {  // the value is the rest of this argument or the next one
+                            let attached = &flags[position + 1..];
+
+                            let value = if !attached.is_empty() {
+                                attached.to_string()
+                            } else if index < args.len() {
+                                index += 1;
+                                args[index - 1].clone()
+                            } else {
+                                return Err(usage_error(&format!("Argument expected for the -{flag} option")));
+                            };
+
+                            let rest = args[index..].iter().cloned();
+
+                            return Ok(if flag == 'c' {
+                                Options { source: Source::Command(value), argv: std::iter::once("-c".to_string()).chain(rest).collect(), dump_parse_tree }
+                            } else {
+                                Options { source: Source::Module(value.clone()), argv: std::iter::once(value).chain(rest).collect(), dump_parse_tree }  // `argv[0]` becomes the module path once it's found
+                            });
+                        }
+                        unknown => return Err(usage_error(&format!("Unknown option: -{unknown}"))),
+                    }
+                }
+            }
+            unknown if unknown.starts_with("--") => return Err(usage_error(&format!("unknown option {unknown}"))),
+            script => {
+                return Ok(Options { source: Source::Script(PathBuf::from(script)), argv: args[index - 1..].to_vec(), dump_parse_tree });
+            }
+        }
+    }
+
+    let source = if io::stdin().is_terminal() { Source::Interactive } else { Source::Stdin };
+
+    Ok(Options { source, argv: vec![String::new()], dump_parse_tree })
+}
+
+fn find_module(name: &str) -> Option<PathBuf> {  // only the working directory is searched until there is a real `sys.path`
+    let module_path: PathBuf = name.split('.').collect();
+
+    let script = module_path.with_extension("py");
+    if script.is_file() {
+        return Some(script);
+    }
+
+    let package_main = module_path.join("__main__.py");
+    package_main.is_file().then_some(package_main)
+}
+
+pub fn load_source(options: &mut Options) -> Result<String, i32> {
+    match &options.source {
+        Source::Script(path) => fs::read_to_string(path).map_err(|err| {
+            eprintln!("RustyPython: can't open file '{}': {err}", path.display());
+            2
+        }),
+        Source::Command(code) => Ok(code.clone()),
+        Source::Module(name) => {
+            let Some(path) = find_module(name) else {
+                eprintln!("RustyPython: No module named {name}");
+                return Err(1);
+            };
+
+            options.argv[0] = fs::canonicalize(&path).unwrap_or_else(|_| path.clone()).display().to_string();
+
+            fs::read_to_string(&path).map_err(|err| {
+                eprintln!("RustyPython: can't open file '{}': {err}", path.display());
+                2
+            })
+        }
+        Source::Stdin => {
+            let mut contents = String::new();
+
+            io::stdin().read_to_string(&mut contents).map_err(|err| {
+                eprintln!("RustyPython: can't read from stdin: {err}");
+                1
+            })?;
+
+            Ok(contents)
+        }
+        Source::Interactive => unreachable!("the REPL reads its own input"),
+    }
+}
@@ -0,0 +1,812 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use crate::builtins::structure::magic_methods::PyMagicMethod;
+use crate::builtins::structure::magic_methods::PyMagicMethod::{Add, FloorDiv, Mod, Mul, Pow, Sub, TrueDiv};
+use crate::builtins::structure::pyobject::PyObject;
+use crate::inline_cache::OpCache;
+use crate::namespace::GlobalCache;
+use crate::parser::*;
+use crate::preprocessor::fast_local_names;
+use crate::pyarena::PyArena;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Instruction {  // operands index into the tables of the owning `CodeObject`, jump targets are instruction indexes
+    LoadConst(usize),
+    LoadName(usize),
+    StoreName(usize),
+    DeleteName(usize),  // silently ignores names that aren't bound
+    LoadFast(usize),  // slot in the frame's locals
+    StoreFast(usize),
+    DeleteFast(usize),
+    LoadGlobal(usize),  // global cache, module code and functions skip the class bodies being executed
+    StoreGlobal(usize),
+    LoadAttr(usize),
+    StoreAttr(usize),
+    DeleteAttr(usize),
+    LoadSubscript,
+    StoreSubscript,
+    BuildSlice,  // pops start, stop and step, `None` for the missing ones
+    IntegerOverflow(usize),  // the digits of a literal too large for an int, raises `OverflowError`
+    BuildList(usize),
+    BuildTuple(usize),
+    BuildMap(usize),  // pops that many key, value pairs
+
+    BinaryOp(PyMagicMethod, usize),  // operator, inline cache
+    UnaryOp(PyMagicMethod),
+    Not,
+    CompareOp(Comparator, usize),
+
+    Call(usize),  // argument count, the callable is below the arguments
+    CallMethod(usize, usize),  // name, argument count, the object is below the arguments
+    MakeFunction(usize),  // nested code object
+    BuildClass(usize, usize, bool),  // nested code object of the body, base count, whether a metaclass is above the bases
+    Import(usize),
+
+    PopTop,
+    DupTop,
+    Jump(usize),  // backwards jumps close a loop, so they also poll for Ctrl-C
+    PopJumpIfFalse(usize),
+    JumpIfFalseOrPop(usize),
+    JumpIfTrueOrPop(usize),
+    GetIter,  // replaces the iterable with the iterator and its `__next__`
+    ForIter(usize),  // pushes the next item, or pops the iterator and jumps once it is exhausted
+
+    SetupExcept(usize),  // exceptions raised until the matching `PopBlock` jump to the target with the exception pushed
+    PopBlock,
+    MatchException,  // pops the class info, pushes whether the exception below is an instance of it
+    BeginHandler,  // marks the exception on top of the stack as being handled
+    EndHandler,  // pops the handled exception
+    PopExcept,  // forgets the handled exception, but leaves it on the stack
+    RaiseWithContext,  // pops the new and the pending exception, chains them and raises the new one
+    Reraise,  // raises the exception on top of the stack
+    Raise(bool),  // has a cause
+    ReraiseHandled,  // a bare `raise`
+
+    Assert,
+    AssertEqual,
+    PrintExpr,  // the REPL's echo of expression statements
+
+    BinaryOpInt(PyMagicMethod, usize),  // quickened forms written over the generic instruction at run time, they put it back when their guard fails
+    BinaryOpFloat(PyMagicMethod, usize),
+    CompareOpInt(Comparator, usize),
+    CompareOpFloat(Comparator, usize),
+    ForIterRange(usize),
+    PopJumpIfFalseBool(usize),
+
+    SetReturnValue,
+    LoadReturnValue,
+    Return,
+}
+
+#[derive(Debug)]
+pub struct CodeObject {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub local_names: Vec<String>,  // indexed by slot, the parameters come first
+    pub instructions: Vec<Cell<Instruction>>,  // cells, so the VM can quicken them in place
+    pub lines: Vec<usize>,  // source line of each instruction, for tracebacks
+    pub constants: Vec<PyObject>,
+    pub names: Vec<String>,
+    pub global_caches: Vec<GlobalCache>,  // one per global load or store site
+    pub op_caches: Vec<OpCache>,
+    pub code_objects: Vec<Rc<CodeObject>>,  // bodies of the functions and classes defined in this one
+    pub class_slot: Option<usize>,  // hidden `__class__` local for zero-argument `super()`, filled from the function's class cell
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CodeKind {
+    Module,
+    Interactive,
+    Class,
+    Function,
+}
+
+enum FrameBlock<'a> {  // constructs `return`, `break` and `continue` have to clean up when jumping out of them
+    Loop { is_for: bool, continue_target: usize, break_sites: Vec<usize> },
+    TryExcept,
+    Finally(&'a CodeBlock),
+    Handler(Option<&'a Rc<Variable>>),
+    FinallyPending,  // the `finally` body run while an exception is pending
+}
+
+struct Compiler<'a> {
+    code: CodeObject,
+    kind: CodeKind,
+    in_class: bool,  // a function defined directly in a class body, only those can use zero-argument `super()`
+    frame_blocks: Vec<FrameBlock<'a>>,
+    line: usize,  // line of the statement being compiled
+    arena: &'a PyArena,
+}
+
+pub type CompileResult<T> = Result<T, String>;  // the message of a `SyntaxError`
+
+pub fn compile_module(code: &CodeBlock, arena: &PyArena) -> CompileResult<CodeObject> {
+    compile_unit("<module>", vec![], vec![], code, CodeKind::Module, false, arena)
+}
+
+pub fn compile_interactive(code: &CodeBlock, arena: &PyArena) -> CompileResult<CodeObject> {
+    compile_unit("<module>", vec![], vec![], code, CodeKind::Interactive, false, arena)
+}
+
+fn compile_unit(name: &str, parameters: Vec<String>, local_names: Vec<String>, code: &CodeBlock, kind: CodeKind, in_class: bool, arena: &PyArena) -> CompileResult<CodeObject> {
+    let mut compiler = Compiler {
+        code: CodeObject {
+            name: name.to_string(),
+            parameters,
+            local_names,
+            instructions: vec![],
+            lines: vec![],
+            constants: vec![],
+            names: vec![],
+            global_caches: vec![],
+            op_caches: vec![],
+            code_objects: vec![],
+            class_slot: None,
+        },
+        kind,
+        in_class,
+        frame_blocks: vec![],
+        line: 0,
+        arena,
+    };
+
+    compiler.compile_block(code)?;
+
+    let none = compiler.add_constant(arena.statics.none().clone());
+    compiler.emit(Instruction::LoadConst(none));
+    compiler.emit(Instruction::Return);
+
+    Ok(compiler.code)
+}
+
+impl<'a> Compiler<'a> {
+    fn emit(&mut self, instruction: Instruction) -> usize {
+        self.code.instructions.push(Cell::new(instruction));
+        self.code.lines.push(self.line);
+        self.code.instructions.len() - 1
+    }
+
+    fn next_index(&self) -> usize {
+        self.code.instructions.len()
+    }
+
+    fn patch_jump(&mut self, at: usize) {  // points the jump at `at` to the next instruction
+        let target = self.next_index();
+
+        match self.code.instructions[at].get_mut() {
+            Instruction::Jump(jump_target)
+            | Instruction::PopJumpIfFalse(jump_target)
+            | Instruction::JumpIfFalseOrPop(jump_target)
+            | Instruction::JumpIfTrueOrPop(jump_target)
+            | Instruction::ForIter(jump_target)
+            | Instruction::SetupExcept(jump_target) => *jump_target = target,
+            other => panic!("{other:?} is not a jump"),
+        }
+    }
+
+    fn add_constant(&mut self, value: PyObject) -> usize {
+        self.code.constants.push(value);
+        self.code.constants.len() - 1
+    }
+
+    fn add_name(&mut self, name: &str) -> usize {
+        if let Some(index) = self.code.names.iter().position(|existing| existing == name) {
+            return index;
+        }
+
+        self.code.names.push(name.to_string());
+        self.code.names.len() - 1
+    }
+
+    fn add_global_cache(&mut self, variable: &Rc<Variable>) -> usize {
+        let name = self.add_name(&variable.name);
+        self.code.global_caches.push(GlobalCache::new(name, variable.hash));
+        self.code.global_caches.len() - 1
+    }
+
+    fn is_zero_argument_super(&self, func: &Expr) -> bool {
+        matches!(func, Expr::Var(variable) if variable.name == "super" && variable.fast_locals_location.is_none())
+            && self.in_class && !self.code.parameters.is_empty()
+    }
+
+    fn class_slot(&mut self) -> usize {  // allocated after the preprocessor's slots, the first time it's needed
+        if let Some(slot) = self.code.class_slot {
+            return slot;
+        }
+
+        self.code.local_names.push("__class__".to_string());
+        let slot = self.code.local_names.len() - 1;
+        self.code.class_slot = Some(slot);
+        slot
+    }
+
+    fn add_op_cache(&mut self) -> usize {
+        self.code.op_caches.push(OpCache::new());
+        self.code.op_caches.len() - 1
+    }
+
+    fn add_code_object(&mut self, code: CodeObject) -> usize {
+        self.code.code_objects.push(Rc::new(code));
+        self.code.code_objects.len() - 1
+    }
+
+    fn load_none(&mut self) {
+        let none = self.add_constant(self.arena.statics.none().clone());
+        self.emit(Instruction::LoadConst(none));
+    }
+
+    fn compile_value(&mut self, value: &Value) {
+        let value = match value {
+            Value::Integer(value) => PyObject::new_int(*value),
+            Value::IntegerOverflow(digits) => {
+                let digits = self.add_constant(PyObject::new_string(digits.clone()));
+                self.emit(Instruction::IntegerOverflow(digits));
+                return;
+            }
+            Value::Float(value) => PyObject::new_float(*value),
+            Value::Imaginary(value) => PyObject::new_complex(0.0, *value),
+            Value::String(value) => PyObject::new_string(value.clone()),
+            Value::Bytes(value) => PyObject::new_bytes(value.clone()),
+            Value::Boolean(value) => self.arena.statics.get_bool(*value).clone(),
+            Value::None => self.arena.statics.none().clone(),
+        };
+
+        let index = self.add_constant(value);
+        self.emit(Instruction::LoadConst(index));
+    }
+
+    fn compile_exprs(&mut self, exprs: &[Expr]) {
+        for expr in exprs {
+            self.compile_expr(expr);
+        }
+    }
+
+    fn compile_binary_op(&mut self, first: &Expr, second: &Expr, op: PyMagicMethod) {
+        self.compile_expr(first);
+        self.compile_expr(second);
+        let cache = self.add_op_cache();
+        self.emit(Instruction::BinaryOp(op, cache));
+    }
+
+    fn compile_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Var(variable) => self.load_variable(variable),
+            Expr::Val(value) => self.compile_value(value),
+            Expr::Times(first, second) => self.compile_binary_op(first, second, Mul {right: false}),
+            Expr::Divide(first, second) => self.compile_binary_op(first, second, TrueDiv {right: false}),
+            Expr::FloorDivide(first, second) => self.compile_binary_op(first, second, FloorDiv {right: false}),
+            Expr::Modulo(first, second) => self.compile_binary_op(first, second, Mod {right: false}),
+            Expr::Plus(first, second) => self.compile_binary_op(first, second, Add {right: false}),
+            Expr::Minus(first, second) => self.compile_binary_op(first, second, Sub {right: false}),
+            Expr::Pow(first, second) => self.compile_binary_op(first, second, Pow {right: false}),
+            Expr::Neg(expr) => {
+                self.compile_expr(expr);
+                self.emit(Instruction::UnaryOp(PyMagicMethod::Neg));
+            }
+            Expr::Pos(expr) => {
+                self.compile_expr(expr);
+                self.emit(Instruction::UnaryOp(PyMagicMethod::Pos));
+            }
+            Expr::Comparison(first, comp, second) => {
+                self.compile_expr(first);
+                self.compile_expr(second);
+                let cache = self.add_op_cache();
+                self.emit(Instruction::CompareOp(*comp, cache));
+            }
+            Expr::FunCall(func, args) => {
+                if let Expr::GetAttr(obj, name) = &**func {  // skips creating a bound method
+                    self.compile_expr(obj);
+                    self.compile_exprs(args);
+                    let name = self.add_name(name);
+                    self.emit(Instruction::CallMethod(name, args.len()));
+                } else if args.is_empty() && self.is_zero_argument_super(func) {  // becomes `super(__class__, <first argument>)`
+                    self.compile_expr(func);
+                    let class_slot = self.class_slot();
+                    self.emit(Instruction::LoadFast(class_slot));
+                    self.emit(Instruction::LoadFast(0));
+                    self.emit(Instruction::Call(2));
+                } else {
+                    self.compile_expr(func);
+                    self.compile_exprs(args);
+                    self.emit(Instruction::Call(args.len()));
+                }
+            }
+            Expr::GetAttr(obj, name) => {
+                self.compile_expr(obj);
+                let name = self.add_name(name);
+                self.emit(Instruction::LoadAttr(name));
+            }
+            Expr::Subscript(obj, key) => {
+                self.compile_expr(obj);
+                self.compile_expr(key);
+                self.emit(Instruction::LoadSubscript);
+            }
+            Expr::Slice(start, stop, step) => {
+                for bound in [start, stop, step] {
+                    match bound {
+                        Some(bound) => self.compile_expr(bound),
+                        None => self.load_none(),
+                    }
+                }
+                self.emit(Instruction::BuildSlice);
+            }
+            Expr::List(items) => {
+                self.compile_exprs(items);
+                self.emit(Instruction::BuildList(items.len()));
+            }
+            Expr::Tuple(items) => {
+                self.compile_exprs(items);
+                self.emit(Instruction::BuildTuple(items.len()));
+            }
+            Expr::Dict(entries) => {
+                for (key, value) in entries {
+                    self.compile_expr(key);
+                    self.compile_expr(value);
+                }
+                self.emit(Instruction::BuildMap(entries.len()));
+            }
+            Expr::Not(expr) => {
+                self.compile_expr(expr);
+                self.emit(Instruction::Not);
+            }
+            Expr::And(first, second) => {
+                self.compile_expr(first);
+                let short_circuit = self.emit(Instruction::JumpIfFalseOrPop(0));
+                self.compile_expr(second);
+                self.patch_jump(short_circuit);
+            }
+            Expr::Or(first, second) => {
+                self.compile_expr(first);
+                let short_circuit = self.emit(Instruction::JumpIfTrueOrPop(0));
+                self.compile_expr(second);
+                self.patch_jump(short_circuit);
+            }
+        }
+    }
+
+    fn load_variable(&mut self, variable: &Rc<Variable>) {
+        if let Some(slot) = variable.fast_locals_location {
+            self.emit(Instruction::LoadFast(slot));
+            return;
+        }
+
+        if self.kind == CodeKind::Class {
+            let name = self.add_name(&variable.name);
+            self.emit(Instruction::LoadName(name));
+            return;
+        }
+
+        let cache = self.add_global_cache(variable);
+        self.emit(Instruction::LoadGlobal(cache));
+    }
+
+    fn store_variable(&mut self, variable: &Rc<Variable>) {
+        if let Some(slot) = variable.fast_locals_location {
+            self.emit(Instruction::StoreFast(slot));
+            return;
+        }
+
+        if self.kind == CodeKind::Class {
+            let name = self.add_name(&variable.name);
+            self.emit(Instruction::StoreName(name));
+            return;
+        }
+
+        let cache = self.add_global_cache(variable);
+        self.emit(Instruction::StoreGlobal(cache));
+    }
+
+    fn delete_variable(&mut self, variable: &Rc<Variable>) {
+        if let Some(slot) = variable.fast_locals_location {
+            self.emit(Instruction::DeleteFast(slot));
+            return;
+        }
+
+        let name = self.add_name(&variable.name);
+        self.emit(Instruction::DeleteName(name));
+    }
+
+    fn compile_op_equals(&mut self, variable: &Rc<Variable>, expr: &Expr, op: PyMagicMethod) {
+        // TODO check if in-place funcs are defined and use them if so
+        self.load_variable(variable);
+        self.compile_expr(expr);
+        let cache = self.add_op_cache();
+        self.emit(Instruction::BinaryOp(op, cache));
+        self.store_variable(variable);
+    }
+
+    fn compile_defn(&mut self, define: &'a Define) -> CompileResult<()> {
+        match define {
+            Define::PlusEq(variable, expr) => self.compile_op_equals(variable, expr, Add {right: false}),
+            Define::MinusEq(variable, expr) => self.compile_op_equals(variable, expr, Sub {right: false}),
+            Define::DivEq(variable, expr) => self.compile_op_equals(variable, expr, TrueDiv {right: false}),
+            Define::MultEq(variable, expr) => self.compile_op_equals(variable, expr, Mul {right: false}),
+            Define::VarDefn(variable, expr) => {
+                self.compile_expr(expr);
+                self.store_variable(variable);
+            }
+            Define::SetItem(obj, key, expr) => {  // the value is evaluated before the target, like CPython
+                self.compile_expr(expr);
+                self.compile_expr(obj);
+                self.compile_expr(key);
+                self.emit(Instruction::StoreSubscript);
+            }
+            Define::SetAttr(obj, name, expr) => {
+                self.compile_expr(expr);
+                self.compile_expr(obj);
+                let name = self.add_name(name);
+                self.emit(Instruction::StoreAttr(name));
+            }
+            Define::ClassDefn(..) | Define::FunDefn(..) => {
+                let variable = self.compile_definition(define)?;
+                self.store_variable(variable);
+            }
+            Define::Decorated(decorators, definition) => {  // the decorators are evaluated before the definition, then applied innermost first
+                self.compile_exprs(decorators);
+                let variable = self.compile_definition(definition)?;
+
+                for _decorator in decorators {
+                    self.emit(Instruction::Call(1));
+                }
+
+                self.store_variable(variable);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn compile_definition(&mut self, define: &'a Define) -> CompileResult<&'a Rc<Variable>> {  // pushes the new function or class, returns the name it gets bound to
+        match define {
+            Define::ClassDefn(variable, bases, metaclass, code, _scope) => {
+                self.compile_exprs(bases);
+
+                if let Some(metaclass) = metaclass {
+                    self.compile_expr(metaclass);
+                }
+
+                let body = compile_unit(&variable.name, vec![], vec![], code, CodeKind::Class, false, self.arena)?;
+                let body = self.add_code_object(body);
+                self.emit(Instruction::BuildClass(body, bases.len(), metaclass.is_some()));
+                Ok(variable)
+            }
+            Define::FunDefn(variable, parameters, code, scope) => {
+                let parameters: Vec<String> = parameters.iter().map(|parameter| parameter.name.clone()).collect();
+
+                if let Some(duplicate) = parameters.iter().enumerate().find(|(index, name)| parameters[..*index].contains(name)) {
+                    return Err(format!("duplicate argument '{}' in function definition", duplicate.1));
+                }
+
+                let body = compile_unit(&variable.name, parameters, fast_local_names(scope), code, CodeKind::Function, self.kind == CodeKind::Class, self.arena)?;
+                let body = self.add_code_object(body);
+                self.emit(Instruction::MakeFunction(body));
+                Ok(variable)
+            }
+            _ => panic!("only functions and classes can be decorated"),  // the parser only builds `Decorated` around these
+        }
+    }
+
+    fn compile_loop_body(&mut self, code: &'a CodeBlock, is_for: bool, continue_target: usize) -> CompileResult<Vec<usize>> {  // returns the `break` jumps to patch
+        self.frame_blocks.push(FrameBlock::Loop { is_for, continue_target, break_sites: vec![] });
+        self.compile_block(code)?;
+
+        match self.frame_blocks.pop() {
+            Some(FrameBlock::Loop { break_sites, .. }) => Ok(break_sites),
+            _ => panic!("loop block was not on top of the frame blocks"),
+        }
+    }
+
+    fn compile_for(&mut self, variable: &Rc<Variable>, iter: &Expr, code: &'a CodeBlock) -> CompileResult<()> {
+        self.compile_expr(iter);
+        self.emit(Instruction::GetIter);
+
+        let top = self.next_index();
+        let exhausted = self.emit(Instruction::ForIter(0));
+        self.store_variable(variable);
+
+        let break_sites = self.compile_loop_body(code, true, top)?;
+        self.emit(Instruction::Jump(top));
+
+        self.patch_jump(exhausted);
+        for site in break_sites {
+            self.patch_jump(site);
+        }
+
+        Ok(())
+    }
+
+    fn compile_while(&mut self, condition: &Expr, code: &'a CodeBlock) -> CompileResult<()> {
+        let top = self.next_index();
+        self.compile_expr(condition);
+        let exit = self.emit(Instruction::PopJumpIfFalse(0));
+
+        let break_sites = self.compile_loop_body(code, false, top)?;
+        self.emit(Instruction::Jump(top));
+
+        self.patch_jump(exit);
+        for site in break_sites {
+            self.patch_jump(site);
+        }
+
+        Ok(())
+    }
+
+    fn compile_if(&mut self, cond: &Expr, if_code: &'a CodeBlock, elif_cond_code: &'a [(Expr, CodeBlock)], else_code: &'a Option<CodeBlock>) -> CompileResult<()> {
+        let mut end_sites = vec![];
+
+        for (cond, code) in std::iter::once((cond, if_code)).chain(elif_cond_code.iter().map(|(cond, code)| (cond, code))) {
+            self.compile_expr(cond);
+            let next = self.emit(Instruction::PopJumpIfFalse(0));
+            self.compile_block(code)?;
+            end_sites.push(self.emit(Instruction::Jump(0)));
+            self.patch_jump(next);
+        }
+
+        if let Some(else_code) = else_code {
+            self.compile_block(else_code)?;
+        }
+
+        for site in end_sites {
+            self.patch_jump(site);
+        }
+
+        Ok(())
+    }
+
+    fn compile_handlers(&mut self, handlers: &'a [ExceptHandler], end_sites: &mut Vec<usize>) -> CompileResult<()> {  // the exception is on top of the stack
+        for handler in handlers {
+            let mut next_handler = None;
+
+            if let Some(ref class_info) = handler.exception {
+                self.compile_expr(class_info);
+                self.emit(Instruction::MatchException);
+                next_handler = Some(self.emit(Instruction::PopJumpIfFalse(0)));
+            }
+
+            self.emit(Instruction::BeginHandler);
+
+            if let Some(variable) = handler.name.as_ref() {
+                self.emit(Instruction::DupTop);
+                self.store_variable(variable);
+            }
+
+            let cleanup = self.emit(Instruction::SetupExcept(0));
+            self.frame_blocks.push(FrameBlock::Handler(handler.name.as_ref()));
+            self.compile_block(&handler.code)?;
+            self.frame_blocks.pop();
+            self.emit(Instruction::PopBlock);
+            self.emit(Instruction::EndHandler);
+            self.delete_handler_name(handler.name.as_ref());
+            end_sites.push(self.emit(Instruction::Jump(0)));
+
+            self.patch_jump(cleanup);  // the handler raised, the stack is the handled exception and then the new one
+            self.emit(Instruction::PopExcept);
+            self.delete_handler_name(handler.name.as_ref());
+            self.emit(Instruction::RaiseWithContext);
+
+            if let Some(next_handler) = next_handler {
+                self.patch_jump(next_handler);
+            }
+        }
+
+        self.emit(Instruction::Reraise);  // no handler matched
+        Ok(())
+    }
+
+    fn delete_handler_name(&mut self, variable: Option<&Rc<Variable>>) {  // the name is unbound after the handler, like CPython
+        if let Some(variable) = variable {
+            self.delete_variable(variable);
+        }
+    }
+
+    fn compile_try(&mut self, code: &'a CodeBlock, handlers: &'a [ExceptHandler], else_code: &'a Option<CodeBlock>, finally_code: &'a Option<CodeBlock>) -> CompileResult<()> {
+        let finally_setup = finally_code.as_ref().map(|finally_code| {
+            self.frame_blocks.push(FrameBlock::Finally(finally_code));
+            self.emit(Instruction::SetupExcept(0))
+        });
+
+        if handlers.is_empty() {
+            self.compile_block(code)?;
+        } else {
+            let handlers_setup = self.emit(Instruction::SetupExcept(0));
+            self.frame_blocks.push(FrameBlock::TryExcept);
+            self.compile_block(code)?;
+            self.frame_blocks.pop();
+            self.emit(Instruction::PopBlock);
+
+            if let Some(else_code) = else_code {  // an exception in `else` isn't handled by this statement's handlers
+                self.compile_block(else_code)?;
+            }
+
+            let mut end_sites = vec![self.emit(Instruction::Jump(0))];
+            self.patch_jump(handlers_setup);
+            self.compile_handlers(handlers, &mut end_sites)?;
+
+            for site in end_sites {
+                self.patch_jump(site);
+            }
+        }
+
+        let (Some(finally_code), Some(finally_setup)) = (finally_code, finally_setup) else {
+            return Ok(());
+        };
+
+        self.frame_blocks.pop();
+        self.emit(Instruction::PopBlock);
+        self.compile_block(finally_code)?;
+        let end = self.emit(Instruction::Jump(0));
+
+        self.patch_jump(finally_setup);  // an exception is pending, it is re-raised after the `finally` body
+        let cleanup = self.emit(Instruction::SetupExcept(0));
+        self.frame_blocks.push(FrameBlock::FinallyPending);
+        self.compile_block(finally_code)?;
+        self.frame_blocks.pop();
+        self.emit(Instruction::PopBlock);
+        self.emit(Instruction::Reraise);
+
+        self.patch_jump(cleanup);  // the `finally` body raised over the pending exception
+        self.emit(Instruction::RaiseWithContext);
+
+        self.patch_jump(end);
+        Ok(())
+    }
+
+    fn unwind_frame_block(&mut self, index: usize) -> CompileResult<()> {  // emits the cleanup for leaving `frame_blocks[index]` early
+        match self.frame_blocks[index] {
+            FrameBlock::Loop { is_for, .. } => {
+                if is_for {
+                    self.emit(Instruction::PopTop);  // the iterator and its `__next__`
+                    self.emit(Instruction::PopTop);
+                }
+            }
+            FrameBlock::TryExcept => {
+                self.emit(Instruction::PopBlock);
+            }
+            FrameBlock::Finally(finally_code) => {
+                self.emit(Instruction::PopBlock);
+
+                let outer_blocks = self.frame_blocks.split_off(index);  // the `finally` body only sees the blocks around the `try`
+                let result = self.compile_block(finally_code);
+                self.frame_blocks.extend(outer_blocks);
+                result?;
+            }
+            FrameBlock::Handler(variable) => {
+                self.emit(Instruction::PopBlock);
+                self.emit(Instruction::EndHandler);
+                self.delete_handler_name(variable);
+            }
+            FrameBlock::FinallyPending => {
+                self.emit(Instruction::PopBlock);
+                self.emit(Instruction::PopTop);  // jumping out of `finally` discards the pending exception
+            }
+        }
+
+        Ok(())
+    }
+
+    fn compile_return(&mut self, expr: &Expr) -> CompileResult<()> {
+        if self.kind != CodeKind::Function {
+            return Err("'return' outside function".to_string());
+        }
+
+        self.compile_expr(expr);
+
+        if self.frame_blocks.is_empty() {
+            self.emit(Instruction::Return);
+            return Ok(());
+        }
+
+        self.emit(Instruction::SetReturnValue);  // the unwinding below pops what is under the value
+
+        for index in (0..self.frame_blocks.len()).rev() {
+            self.unwind_frame_block(index)?;
+        }
+
+        self.emit(Instruction::LoadReturnValue);
+        self.emit(Instruction::Return);
+        Ok(())
+    }
+
+    fn compile_loop_jump(&mut self, is_break: bool) -> CompileResult<()> {
+        let Some(loop_index) = self.frame_blocks.iter().rposition(|block| matches!(block, FrameBlock::Loop { .. })) else {
+            return Err(if is_break { "'break' outside loop" } else { "'continue' not properly in loop" }.to_string());
+        };
+
+        for index in (loop_index + 1..self.frame_blocks.len()).rev() {
+            self.unwind_frame_block(index)?;
+        }
+
+        if !is_break {
+            let FrameBlock::Loop { continue_target, .. } = self.frame_blocks[loop_index] else { unreachable!() };
+            self.emit(Instruction::Jump(continue_target));
+            return Ok(());
+        }
+
+        self.unwind_frame_block(loop_index)?;
+        let site = self.emit(Instruction::Jump(0));
+
+        if let FrameBlock::Loop { break_sites, .. } = &mut self.frame_blocks[loop_index] {
+            break_sites.push(site);
+        }
+
+        Ok(())
+    }
+
+    fn compile_statement(&mut self, statement: &'a Statement) -> CompileResult<()> {
+        match statement {
+            Statement::Expr(expr) => {
+                self.compile_expr(expr);
+                self.emit(if self.kind == CodeKind::Interactive { Instruction::PrintExpr } else { Instruction::PopTop });
+            }
+            Statement::Defn(define) => self.compile_defn(define)?,
+            Statement::If(cond, if_code, elif, else_code) => self.compile_if(cond, if_code, elif, else_code)?,
+            Statement::For(iter_var, iter_exp, code) => self.compile_for(iter_var, iter_exp, code)?,
+            Statement::While(condition, code) => self.compile_while(condition, code)?,
+            Statement::Return(rtn_expr) => self.compile_return(rtn_expr)?,
+            Statement::Assert(expr1, expr2) => {
+                self.compile_expr(expr1);
+
+                if let Some(expr2) = expr2 {
+                    self.compile_expr(expr2);
+                    self.emit(Instruction::AssertEqual);
+                } else {
+                    self.emit(Instruction::Assert);
+                }
+            }
+            Statement::Try(code, handlers, else_code, finally_code) => self.compile_try(code, handlers, else_code, finally_code)?,
+            Statement::Raise(None, _) => {
+                self.emit(Instruction::ReraiseHandled);
+            }
+            Statement::Raise(Some(expr), cause) => {
+                self.compile_expr(expr);
+
+                if let Some(cause) = cause {
+                    self.compile_expr(cause);
+                }
+
+                self.emit(Instruction::Raise(cause.is_some()));
+            }
+            Statement::Pass => {},
+            Statement::Import(modules) => {
+                for variable in modules {
+                    let name = self.add_name(&variable.name);
+                    self.emit(Instruction::Import(name));
+                    self.store_variable(variable);
+                }
+            }
+            Statement::Del(targets) => {
+                for target in targets {
+                    match target {
+                        Expr::Var(variable) => {  // loading first raises the `NameError` that deleting an unbound name should
+                            self.load_variable(variable);
+                            self.emit(Instruction::PopTop);
+                            self.delete_variable(variable);
+                        }
+                        Expr::GetAttr(obj, name) => {
+                            self.compile_expr(obj);
+                            let name = self.add_name(name);
+                            self.emit(Instruction::DeleteAttr(name));
+                        }
+                        _ => panic!("the parser only allows names and attributes in `del`"),
+                    }
+                }
+            }
+            Statement::Continue => self.compile_loop_jump(false)?,
+            Statement::Break => self.compile_loop_jump(true)?,
+        }
+
+        Ok(())
+    }
+
+    fn compile_block(&mut self, code: &'a CodeBlock) -> CompileResult<()> {
+        let outer_line = self.line;  // a loop's jump back and its next `__next__` call belong to its own line, not the body's last
+
+        for (statement, line) in code.statements.iter().zip(&code.lines) {
+            self.line = *line;
+            self.compile_statement(statement)?;
+        }
+
+        self.line = outer_line;
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+use std::cell::{Cell, RefCell};
+use ahash::AHashMap;
+use crate::builtins::function_utils::{call_function, call_function_1_arg_min};
+use crate::builtins::types::weakref::take_weakref_callback;
+use crate::builtins::structure::pyobject::{PyMutableObject, PyObject, PyPointer, PyWeakPointer};
+use crate::pyarena::PyArena;
+
+pub const GENERATIONS: usize = 3;
+const DEFAULT_THRESHOLDS: [usize; GENERATIONS] = [700, 10, 10];  // same defaults as CPython
+
+type Tracked = PyWeakPointer<PyMutableObject>;
+
+struct GcState {
+    generations: [Vec<Tracked>; GENERATIONS],  // young objects first, survivors of a collection move one generation up
+    counts: [usize; GENERATIONS],  // allocations since the last young collection, then collections of the generation below
+    thresholds: [usize; GENERATIONS],
+    enabled: bool,
+    collecting: bool,  // finalizers run python code, which must not start a nested collection
+}
+
+thread_local! {
+    static GC_STATE: RefCell<GcState> = RefCell::new(GcState {  // objects are created without the arena at hand, so the collector keeps its own state
+        generations: Default::default(),
+        counts: [0; GENERATIONS],
+        thresholds: DEFAULT_THRESHOLDS,
+        enabled: true,
+        collecting: false,
+    });
+
+    static COLLECTION_DUE: Cell<bool> = const { Cell::new(false) };  // polled at every safe point, so it has to be cheaper than looking at `GC_STATE`
+    static PENDING_CALLBACKS: RefCell<Vec<PyWeakPointer<PyMutableObject>>> = const { RefCell::new(vec![]) };  // weak references whose referent was freed
+    static CALLBACKS_DUE: Cell<bool> = const { Cell::new(false) };  // same trick as `COLLECTION_DUE`
+    static PENDING_FINALIZERS: RefCell<Vec<PyPointer<PyMutableObject>>> = const { RefCell::new(vec![]) };  // instances freed by their last reference, kept alive until `__del__` has run
+    static FINALIZERS_DUE: Cell<bool> = const { Cell::new(false) };
+    static FINALIZERS_DEFINED: Cell<bool> = const { Cell::new(false) };  // set once any class defines `__del__`, until then freeing an instance skips looking for it
+}
+
+pub fn track(pointer: &PyPointer<PyMutableObject>) {
+    GC_STATE.with_borrow_mut(|state| {
+        state.generations[0].push(pointer.downgrade());
+        state.counts[0] += 1;
+
+        if state.counts[0] <= state.thresholds[0] {
+            return;
+        }
+
+        if state.enabled {
+            COLLECTION_DUE.set(state.thresholds[0] > 0);
+        } else {  // nothing collects, but the entries of freed objects still pin their allocation
+            state.generations[0].retain(Tracked::is_alive);
+            state.counts[0] = 0;
+        }
+    });
+}
+
+pub fn is_enabled() -> bool {
+    GC_STATE.with_borrow(|state| state.enabled)
+}
+
+pub fn set_enabled(enabled: bool) {
+    GC_STATE.with_borrow_mut(|state| state.enabled = enabled);
+}
+
+pub fn get_counts() -> [usize; GENERATIONS] {
+    GC_STATE.with_borrow(|state| state.counts)
+}
+
+pub fn get_thresholds() -> [usize; GENERATIONS] {
+    GC_STATE.with_borrow(|state| state.thresholds)
+}
+
+pub fn set_thresholds(thresholds: &[usize]) {  // generations without a new threshold keep theirs
+    GC_STATE.with_borrow_mut(|state| state.thresholds[..thresholds.len()].copy_from_slice(thresholds));
+}
+
+pub fn collect_if_due(arena: &mut PyArena) {  // called at safe points, like CPython the oldest generation over its threshold is collected
+    if !COLLECTION_DUE.get() {
+        return;
+    }
+
+    COLLECTION_DUE.set(false);
+
+    let generation = GC_STATE.with_borrow(|state| {
+        if !state.enabled || state.collecting || state.thresholds[0] == 0 || state.counts[0] <= state.thresholds[0] {
+            return None;
+        }
+
+        (0..GENERATIONS).rev().find(|&generation| state.counts[generation] > state.thresholds[generation])
+    });
+
+    if let Some(generation) = generation {
+        collect(generation, arena);
+    }
+}
+
+pub fn collect(generation: usize, arena: &mut PyArena) -> usize {  // collects `generation` and every younger one, returns the number of objects freed
+    let Some(candidates) = GC_STATE.with_borrow_mut(|state| {
+        if state.collecting {
+            return None;
+        }
+
+        state.collecting = true;
+        state.counts[..=generation].fill(0);
+        if generation + 1 < GENERATIONS {
+            state.counts[generation + 1] += 1;
+        }
+
+        Some(state.generations[..=generation].iter_mut().flat_map(std::mem::take).filter_map(|tracked| tracked.upgrade()).collect::<Vec<_>>())
+    }) else {
+        return 0;
+    };
+
+    let (mut survivors, unreachable) = split_unreachable(candidates);
+
+    let finalized_any = run_finalizers(&unreachable, arena);
+    let garbage = if finalized_any {  // a finalizer may have stored a reference somewhere, those objects survive
+        let (resurrected, garbage) = split_unreachable(unreachable);
+        survivors.extend(resurrected);
+        garbage
+    } else {
+        unreachable
+    };
+
+    let collected = garbage.len();
+    let released = garbage.iter().flat_map(|object| object.borrow_mut().clear()).collect::<Vec<_>>();  // the collector still owns every object, so nothing is freed while clearing
+    drop(released);
+    drop(garbage);
+
+    GC_STATE.with_borrow_mut(|state| {
+        let older = (generation + 1).min(GENERATIONS - 1);
+        state.generations[older].extend(survivors.iter().map(PyPointer::downgrade));
+        state.collecting = false;
+    });
+
+    run_weakref_callbacks(arena);  // so the callbacks of collected objects have run by the time `gc.collect()` returns
+    collected
+}
+
+#[cold]
+pub fn schedule_weakref_callbacks(weakrefs: Vec<PyWeakPointer<PyMutableObject>>) {  // called while the referent is being dropped, so no python code can run yet
+    let _ = PENDING_CALLBACKS.try_with(|pending| pending.borrow_mut().extend(weakrefs));  // fails only while the thread shuts down
+    let _ = CALLBACKS_DUE.try_with(|due| due.set(true));
+}
+
+pub fn run_weakref_callbacks(arena: &mut PyArena) {  // called at safe points, like the collector
+    if !CALLBACKS_DUE.replace(false) {
+        return;
+    }
+
+    while let Some(weakref) = PENDING_CALLBACKS.with_borrow_mut(Vec::pop) {
+        let Some(weakref) = weakref.upgrade().map(PyObject::Mutable) else {
+            continue;  // a weak reference that is itself gone doesn't call back
+        };
+
+        let Some(callback) = take_weakref_callback(&weakref) else {
+            continue;
+        };
+
+        if let Err(err) = call_function(callback, &[weakref], arena) {
+            eprintln!("Exception ignored in weakref callback\n{}", err.format(arena));
+        }
+    }
+}
+
+pub fn note_finalizer_defined() {
+    FINALIZERS_DEFINED.set(true);
+}
+
+pub fn finalizers_defined() -> bool {
+    FINALIZERS_DEFINED.try_with(Cell::get).unwrap_or(false)  // nothing runs python while the thread shuts down
+}
+
+#[cold]
+pub fn schedule_finalizer(instance: PyPointer<PyMutableObject>) {  // called while the last reference is dropped, `__del__` runs at the next release point
+    let _ = PENDING_FINALIZERS.try_with(|pending| pending.borrow_mut().push(instance));
+    let _ = FINALIZERS_DUE.try_with(|due| due.set(true));
+}
+
+pub fn run_pending_finalizers(arena: &mut PyArena) {
+    if !FINALIZERS_DUE.replace(false) {
+        return;
+    }
+
+    while let Some(instance) = PENDING_FINALIZERS.with_borrow_mut(Vec::pop) {
+        track(&instance);  // `__del__` may store `self` somewhere, then it is an ordinary object again
+
+        let pyobj = PyObject::Mutable(instance);
+        let Some(finalizer) = pyobj.get_method("__del__", arena) else {
+            continue;
+        };
+
+        if let Err(err) = call_function_1_arg_min(&finalizer, &pyobj, &[], arena) {
+            eprintln!("Exception ignored in: {}.__del__\n{}", pyobj.clone_class(arena).get_name(), err.format(arena));
+        }
+    }
+}
+
+fn split_unreachable(candidates: Vec<PyPointer<PyMutableObject>>) -> (Vec<PyPointer<PyMutableObject>>, Vec<PyPointer<PyMutableObject>>) {  // (reachable, unreachable), the collector must hold exactly one reference to each candidate
+    let index_of = candidates.iter().enumerate().map(|(index, object)| (object.address(), index)).collect::<AHashMap<_, _>>();
+    let candidate_index = |object: &PyObject| match object {
+        PyObject::Mutable(inner) => index_of.get(&inner.address()).copied(),
+        _ => None,
+    };
+
+    let mut external_refs = candidates.iter().map(|object| object.strong_count() - 1).collect::<Vec<_>>();
+
+    for object in &candidates {
+        match object.try_borrow() {
+            Ok(object) => object.traverse(&mut |child| {  // references from inside the candidates don't keep anything alive
+                if let Some(index) = candidate_index(child) {
+                    external_refs[index] = external_refs[index].saturating_sub(1);
+                }
+            }),
+            Err(_) => external_refs[index_of[&object.address()]] = usize::MAX,  // someone is using it right now
+        }
+    }
+
+    let mut reachable = external_refs.iter().map(|&refs| refs > 0).collect::<Vec<_>>();
+    let mut pending = (0..candidates.len()).filter(|&index| reachable[index]).collect::<Vec<_>>();
+
+    while let Some(index) = pending.pop() {
+        let Ok(object) = candidates[index].try_borrow() else {
+            continue;  // a borrowed object is kept along with everything it holds
+        };
+
+        object.traverse(&mut |child| {
+            if let Some(child) = candidate_index(child) {
+                if !reachable[child] {
+                    reachable[child] = true;
+                    pending.push(child);
+                }
+            }
+        });
+    }
+
+    let mut kept = vec![];
+    let mut unreachable = vec![];
+
+    for (object, reachable) in candidates.into_iter().zip(reachable) {
+        if reachable {
+            kept.push(object);
+        } else {
+            unreachable.push(object);
+        }
+    }
+
+    (kept, unreachable)
+}
+
+fn run_finalizers(unreachable: &[PyPointer<PyMutableObject>], arena: &mut PyArena) -> bool {
+    let mut finalized_any = false;
+
+    for object in unreachable {
+        let should_finalize = match *object.borrow_mut() {
+            PyMutableObject::Instance(ref mut instance) => !std::mem::replace(&mut instance.finalized, true),
+            PyMutableObject::Function(_) => false,
+        };
+
+        let pyobj = PyObject::Mutable(object.clone());
+        let Some(finalizer) = should_finalize.then(|| pyobj.get_method("__del__", arena)).flatten() else {
+            continue;
+        };
+
+        finalized_any = true;
+
+        if let Err(err) = call_function_1_arg_min(&finalizer, &pyobj, &[], arena) {  // like CPython, errors in `__del__` are reported and otherwise ignored
+            eprintln!("Exception ignored in: {}.__del__\n{}", pyobj.clone_class(arena).get_name(), err.format(arena));
+        }
+    }
+
+    finalized_any
+}
@@ -0,0 +1,101 @@
+use std::cell::{Cell, RefCell};
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+use crate::builtins::structure::magic_methods::PyMagicMethod;
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyobject::{BivariateFuncType, PyInternalFunction, PyInternalObject, PyObject};
+use crate::pyarena::PyArena;
+
+const QUICKEN_AFTER: u8 = 8;  // executions in a row with the same operand kinds before a site is quickened
+
+pub struct OpCache {  // one per binary operator or comparison site, lives in the `CodeObject`
+    entry: RefCell<Option<CachedOp>>,
+    warmup: Cell<(OperandKind, u8)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum OperandKind {  // the operand pairs that have quickened instructions
+    Int,
+    Float,
+    Other,
+}
+
+pub fn operand_kind(left: &PyObject, right: &PyObject) -> OperandKind {
+    match (left, right) {
+        (PyObject::Int(_), PyObject::Int(_)) => OperandKind::Int,
+        (PyObject::Float(_), PyObject::Float(_)) => OperandKind::Float,
+        _ => OperandKind::Other,
+    }
+}
+
+struct CachedOp {
+    left_class: Rc<PyClass>,  // kept alive, so the addresses can't be reused by another class
+    right_class: Rc<PyClass>,
+    type_version: u64,
+    func: &'static BivariateFuncType,
+}
+
+fn class_address(obj: &PyObject, arena: &mut PyArena) -> Option<*const PyClass> {
+    match obj {
+        PyObject::None => Some(Rc::as_ptr(&arena.globals.none_class)),
+        PyObject::Bool(_) => Some(Rc::as_ptr(&arena.globals.bool_class)),
+        PyObject::Int(_) => Some(Rc::as_ptr(&arena.globals.int_class)),
+        PyObject::Float(_) => Some(Rc::as_ptr(&arena.globals.float_class)),
+        PyObject::Immutable(inner) => Some(Rc::as_ptr(inner.get_class(arena))),
+        PyObject::Mutable(inner) => Some(Rc::as_ptr(inner.borrow().get_class())),
+        PyObject::Internal(_) | PyObject::IteratorFlag(_) => None,
+    }
+}
+
+impl OpCache {
+    pub fn new() -> Self {
+        OpCache { entry: RefCell::new(None), warmup: Cell::new((OperandKind::Other, 0)) }
+    }
+
+    pub fn warm_up(&self, kind: OperandKind) -> bool {  // true once the site saw `kind` often enough in a row to be quickened
+        let (last_kind, count) = self.warmup.get();
+        let count = if last_kind == kind { count.saturating_add(1) } else { 1 };
+        self.warmup.set((kind, count));
+
+        kind != OperandKind::Other && count >= QUICKEN_AFTER
+    }
+
+    pub fn cool_down(&self) {  // the quickened instruction hit an operand it can't handle
+        self.warmup.set((OperandKind::Other, 0));
+    }
+
+    pub fn resolve(&self, py_magic_method: &PyMagicMethod, left: &PyObject, right: &PyObject, arena: &mut PyArena) -> Option<&'static BivariateFuncType> {  // the left operand's builtin method, `None` sends the caller down the generic path
+        let left_address = class_address(left, arena)?;
+        let right_address = class_address(right, arena)?;
+
+        if let Some(cached) = &*self.entry.borrow() {
+            if Rc::as_ptr(&cached.left_class) == left_address && Rc::as_ptr(&cached.right_class) == right_address && cached.type_version == arena.type_version {
+                return Some(cached.func);
+            }
+        }
+
+        let left_class = left.clone_class(arena);
+        let right_class = right.clone_class(arena);
+
+        if !Rc::ptr_eq(&left_class, &right_class) && right_class.is_subclass_of(&left_class) {
+            return None;  // a subclass's reflected method may go first, `binary_dispatch` decides that
+        }
+
+        let PyObject::Internal(PyInternalObject::InternalFunction(method)) = left_class.search_for_magic_method(py_magic_method)? else {
+            return None;  // python level methods aren't cached
+        };
+
+        let PyInternalFunction::BivariateFunc(func) = *method else {
+            return None;
+        };
+
+        *self.entry.borrow_mut() = Some(CachedOp { left_class, right_class, type_version: arena.type_version, func });
+        Some(func)
+    }
+}
+
+impl Debug for OpCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpCache").field("filled", &self.entry.borrow().is_some()).finish()
+    }
+}
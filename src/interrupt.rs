@@ -0,0 +1,28 @@
+use std::os::raw::c_int;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const SIGINT: c_int = 2;  // same value on every platform with a C runtime
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" {
+    fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+}
+
+extern "C" fn handle_sigint(_signum: c_int) {
+    INTERRUPTED.store(true, Ordering::Relaxed);  // only async-signal-safe work here, the evaluator raises the exception
+
+    unsafe {
+        signal(SIGINT, handle_sigint);  // some platforms reset the handler after it fires
+    }
+}
+
+pub fn install_sigint_handler() {
+    unsafe {
+        signal(SIGINT, handle_sigint);
+    }
+}
+
+pub fn take_interrupt() -> bool {  // clears the flag so each Ctrl-C raises once
+    INTERRUPTED.swap(false, Ordering::Relaxed)
+}
@@ -0,0 +1,489 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const HISTORY_FILE: &str = ".rustypython_history";
+const MAX_HISTORY: usize = 1000;
+
+#[derive(Debug, PartialEq)]
+pub enum ReadResult {
+    Line(String),
+    Interrupted,  // Ctrl-C
+    Eof,  // Ctrl-D on an empty line
+}
+
+#[derive(Debug, PartialEq)]
+enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    Up,
+    Down,
+    KillToEnd,
+    KillToStart,
+    Interrupt,
+    EndOfFile,
+    Ignored,
+}
+
+struct RawMode {
+    saved_settings: String,  // what `stty -g` printed, restored on drop
+}
+
+impl RawMode {
+    fn enable() -> Option<RawMode> {  // `stty` instead of termios bindings keeps us free of platform specific struct layouts
+        let saved = Command::new("stty").arg("-g").stdin(Stdio::inherit()).output().ok()?;
+
+        if !saved.status.success() {
+            return None;
+        }
+
+        let status = Command::new("stty").args(["-icanon", "-echo", "-isig", "-ixon", "min", "1"]).stdin(Stdio::inherit()).status().ok()?;
+
+        status.success().then(|| RawMode { saved_settings: String::from_utf8_lossy(&saved.stdout).trim().to_string() })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = Command::new("stty").arg(&self.saved_settings).stdin(Stdio::inherit()).status();
+    }
+}
+
+pub struct LineEditor {
+    history: Vec<String>,
+    history_path: Option<PathBuf>,
+}
+
+impl LineEditor {
+    pub fn new() -> LineEditor {
+        LineEditor::with_history_file(env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE)))
+    }
+
+    fn with_history_file(history_path: Option<PathBuf>) -> LineEditor {
+        let mut history: Vec<String> = history_path.as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|contents| contents.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+
+        let overflow = history.len().saturating_sub(MAX_HISTORY);
+        history.drain(..overflow);
+
+        LineEditor { history, history_path }
+    }
+
+    pub fn add_history(&mut self, line: &str) {
+        if line.trim().is_empty() || self.history.last().is_some_and(|last| last == line) {
+            return;
+        }
+
+        self.history.push(line.to_string());
+
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+
+        if let Some(path) = &self.history_path {  // appended right away so a crash doesn't lose the session
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                let _ = writeln!(file, "{line}");
+            }
+        }
+    }
+
+    pub fn read_line(&mut self, prompt: &str) -> ReadResult {
+        print!("{prompt}");
+        let _ = io::stdout().flush();
+
+        match RawMode::enable() {
+            Some(_raw_mode) => self.read_line_raw(prompt),
+            None => read_line_cooked(),
+        }
+    }
+
+    fn read_line_raw(&self, prompt: &str) -> ReadResult {
+        let mut buffer = EditBuffer::new(&self.history);
+
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+
+        loop {
+            match buffer.apply(read_key(&mut input)) {
+                Edit::Done(result) => {
+                    println!();
+                    return result;
+                }
+                Edit::Changed => redraw(prompt, &buffer.line, buffer.cursor),
+                Edit::Unchanged => {}
+            }
+        }
+    }
+}
+
+enum Edit {
+    Changed,
+    Unchanged,
+    Done(ReadResult),
+}
+
+struct EditBuffer<'a> {  // the line being typed, kept apart from the terminal so it can be tested
+    line: Vec<char>,
+    cursor: usize,
+    history: &'a [String],
+    history_index: usize,  // `history.len()` is the line being typed
+    draft: Vec<char>,  // the typed line, put back when scrolling down past the newest entry
+}
+
+impl<'a> EditBuffer<'a> {
+    fn new(history: &'a [String]) -> EditBuffer<'a> {
+        EditBuffer { line: vec![], cursor: 0, history, history_index: history.len(), draft: vec![] }
+    }
+
+    fn apply(&mut self, key: Key) -> Edit {
+        match key {
+            Key::Char(char) => {
+                self.line.insert(self.cursor, char);
+                self.cursor += 1;
+            }
+            Key::Enter => return Edit::Done(ReadResult::Line(self.line.iter().collect())),
+            Key::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.line.remove(self.cursor);
+            }
+            Key::Delete if self.cursor < self.line.len() => {
+                self.line.remove(self.cursor);
+            }
+            Key::Left => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right => self.cursor = (self.cursor + 1).min(self.line.len()),
+            Key::Home => self.cursor = 0,
+            Key::End => self.cursor = self.line.len(),
+            Key::Up if self.history_index > 0 => {
+                if self.history_index == self.history.len() {
+                    self.draft = self.line.clone();
+                }
+
+                self.history_index -= 1;
+                self.line = self.history[self.history_index].chars().collect();
+                self.cursor = self.line.len();
+            }
+            Key::Down if self.history_index < self.history.len() => {
+                self.history_index += 1;
+                self.line = match self.history.get(self.history_index) {
+                    Some(entry) => entry.chars().collect(),
+                    None => self.draft.clone(),
+                };
+                self.cursor = self.line.len();
+            }
+            Key::KillToEnd => self.line.truncate(self.cursor),
+            Key::KillToStart => {
+                self.line.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            Key::Interrupt => return Edit::Done(ReadResult::Interrupted),
+            Key::EndOfFile if self.line.is_empty() => return Edit::Done(ReadResult::Eof),
+            Key::EndOfFile if self.cursor < self.line.len() => {  // Ctrl-D inside a line deletes like emacs
+                self.line.remove(self.cursor);
+            }
+            _ => return Edit::Unchanged,
+        }
+
+        Edit::Changed
+    }
+}
+
+fn read_line_cooked() -> ReadResult {  // no terminal control available, let the terminal do the editing
+    let mut line = String::new();
+
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => ReadResult::Eof,
+        Ok(_) => ReadResult::Line(line.trim_end_matches(['\n', '\r']).to_string()),
+    }
+}
+
+fn redraw(prompt: &str, line: &[char], cursor: usize) {
+    let text: String = line.iter().collect();
+    let mut output = format!("\r{prompt}{text}\x1b[K");
+
+    if cursor < line.len() {
+        output.push_str(&format!("\x1b[{}D", line.len() - cursor));
+    }
+
+    print!("{output}");
+    let _ = io::stdout().flush();
+}
+
+fn read_byte(input: &mut impl Read) -> Option<u8> {
+    let mut byte = [0u8];
+    input.read_exact(&mut byte).ok()?;
+    Some(byte[0])
+}
+
+fn read_key(input: &mut impl Read) -> Key {
+    let Some(byte) = read_byte(input) else {
+        return Key::EndOfFile;
+    };
+
+    match byte {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        0x01 => Key::Home,
+        0x02 => Key::Left,
+        0x03 => Key::Interrupt,
+        0x04 => Key::EndOfFile,
+        0x05 => Key::End,
+        0x06 => Key::Right,
+        0x0b => Key::KillToEnd,
+        0x0e => Key::Down,
+        0x10 => Key::Up,
+        0x15 => Key::KillToStart,
+        b'\t' => Key::Char(' '),  // good enough for indenting blocks, there is no completion
+        0x1b => read_escape_sequence(input),
+        byte if byte < 0x20 => Key::Ignored,
+        byte => read_utf8_char(byte, input).map_or(Key::Ignored, Key::Char),
+    }
+}
+
+fn read_escape_sequence(input: &mut impl Read) -> Key {
+    if !matches!(read_byte(input), Some(b'[') | Some(b'O')) {
+        return Key::Ignored;
+    }
+
+    match read_byte(input) {
+        Some(b'A') => Key::Up,
+        Some(b'B') => Key::Down,
+        Some(b'C') => Key::Right,
+        Some(b'D') => Key::Left,
+        Some(b'H') => Key::Home,
+        Some(b'F') => Key::End,
+        Some(digit @ b'0'..=b'9') => {  // `ESC [ n ~` style keys
+            let mut code = vec![digit];
+
+            while let Some(byte) = read_byte(input) {
+                if byte == b'~' {
+                    break;
+                }
+                code.push(byte);
+            }
+
+            match code.as_slice() {
+                b"1" | b"7" => Key::Home,
+                b"3" => Key::Delete,
+                b"4" | b"8" => Key::End,
+                _ => Key::Ignored,
+            }
+        }
+        _ => Key::Ignored,
+    }
+}
+
+fn read_utf8_char(first: u8, input: &mut impl Read) -> Option<char> {
+    let length = match first {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return None,
+    };
+
+    let mut bytes = vec![first];
+
+    for _ in 1..length {
+        bytes.push(read_byte(input)?);
+    }
+
+    std::str::from_utf8(&bytes).ok()?.chars().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(buffer: &mut EditBuffer, text: &str) {
+        for char in text.chars() {
+            buffer.apply(Key::Char(char));
+        }
+    }
+
+    fn text(buffer: &EditBuffer) -> String {
+        buffer.line.iter().collect()
+    }
+
+    fn done(edit: Edit) -> Option<ReadResult> {
+        match edit {
+            Edit::Done(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    fn keys(bytes: &[u8]) -> Vec<Key> {
+        let mut input = bytes;
+        let mut keys = vec![];
+
+        while !input.is_empty() {
+            keys.push(read_key(&mut input));
+        }
+
+        keys
+    }
+
+    #[test]
+    fn editing_at_the_cursor() {
+        let mut buffer = EditBuffer::new(&[]);
+        type_text(&mut buffer, "prnt(1)");
+
+        buffer.apply(Key::Home);
+        buffer.apply(Key::Right);
+        buffer.apply(Key::Right);
+        type_text(&mut buffer, "i");
+        assert_eq!(text(&buffer), "print(1)");
+        assert_eq!(buffer.cursor, 3);
+
+        buffer.apply(Key::End);
+        buffer.apply(Key::Backspace);
+        buffer.apply(Key::Left);
+        buffer.apply(Key::Delete);
+        assert_eq!(text(&buffer), "print(");
+        assert_eq!(buffer.cursor, 6);
+
+        assert_eq!(done(buffer.apply(Key::Enter)), Some(ReadResult::Line("print(".to_string())));
+    }
+
+    #[test]
+    fn edits_at_the_edges_are_ignored() {
+        let mut buffer = EditBuffer::new(&[]);
+        type_text(&mut buffer, "ab");
+
+        assert!(matches!(buffer.apply(Key::Delete), Edit::Unchanged));
+        buffer.apply(Key::Right);
+        assert_eq!(buffer.cursor, 2);
+
+        buffer.apply(Key::Home);
+        assert!(matches!(buffer.apply(Key::Backspace), Edit::Unchanged));
+        buffer.apply(Key::Left);
+        assert_eq!(buffer.cursor, 0);
+        assert_eq!(text(&buffer), "ab");
+    }
+
+    #[test]
+    fn kill_to_start_and_end() {
+        let mut buffer = EditBuffer::new(&[]);
+        type_text(&mut buffer, "x = 1  # note");
+
+        for _ in 0.."  # note".len() {
+            buffer.apply(Key::Left);
+        }
+
+        buffer.apply(Key::KillToEnd);
+        assert_eq!(text(&buffer), "x = 1");
+
+        buffer.apply(Key::Left);
+        buffer.apply(Key::KillToStart);
+        assert_eq!(text(&buffer), "1");
+        assert_eq!(buffer.cursor, 0);
+    }
+
+    #[test]
+    fn end_of_file_and_interrupt() {
+        let mut buffer = EditBuffer::new(&[]);
+        type_text(&mut buffer, "ab");
+
+        buffer.apply(Key::Home);
+        buffer.apply(Key::EndOfFile);  // deletes under the cursor while the line has text
+        assert_eq!(text(&buffer), "b");
+
+        buffer.apply(Key::End);
+        assert!(matches!(buffer.apply(Key::EndOfFile), Edit::Unchanged));
+
+        buffer.apply(Key::Backspace);
+        assert_eq!(done(buffer.apply(Key::EndOfFile)), Some(ReadResult::Eof));
+
+        type_text(&mut buffer, "abandoned");
+        assert_eq!(done(buffer.apply(Key::Interrupt)), Some(ReadResult::Interrupted));
+    }
+
+    #[test]
+    fn scrolling_history_keeps_the_draft() {
+        let history = ["first".to_string(), "second".to_string()];
+        let mut buffer = EditBuffer::new(&history);
+        type_text(&mut buffer, "dra");
+
+        buffer.apply(Key::Up);
+        assert_eq!(text(&buffer), "second");
+        assert_eq!(buffer.cursor, 6);
+
+        buffer.apply(Key::Up);
+        assert_eq!(text(&buffer), "first");
+        assert!(matches!(buffer.apply(Key::Up), Edit::Unchanged));
+
+        buffer.apply(Key::Down);
+        buffer.apply(Key::Down);
+        assert_eq!(text(&buffer), "dra");
+        assert!(matches!(buffer.apply(Key::Down), Edit::Unchanged));
+
+        buffer.apply(Key::Up);
+        type_text(&mut buffer, "!");
+        assert_eq!(done(buffer.apply(Key::Enter)), Some(ReadResult::Line("second!".to_string())));
+        assert_eq!(history, ["first".to_string(), "second".to_string()]);  // recalled lines are copies
+    }
+
+    #[test]
+    fn history_skips_blanks_and_repeats() {
+        let mut editor = LineEditor::with_history_file(None);
+
+        for line in ["a", "a", "   ", "", "b", "a"] {
+            editor.add_history(line);
+        }
+
+        assert_eq!(editor.history, ["a", "b", "a"]);
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut editor = LineEditor::with_history_file(None);
+
+        for index in 0..MAX_HISTORY + 5 {
+            editor.add_history(&index.to_string());
+        }
+
+        assert_eq!(editor.history.len(), MAX_HISTORY);
+        assert_eq!(editor.history[0], "5");
+    }
+
+    #[test]
+    fn history_file_round_trip() {
+        let path = env::temp_dir().join(format!("rustypython-history-{}", std::process::id()));
+        fs::write(&path, (0..MAX_HISTORY + 3).map(|index| format!("{index}\n")).collect::<String>()).unwrap();
+
+        let mut editor = LineEditor::with_history_file(Some(path.clone()));
+        assert_eq!(editor.history.len(), MAX_HISTORY);  // only the newest entries are loaded
+        assert_eq!(editor.history[0], "3");
+
+        editor.add_history("print(1)");
+        let reloaded = LineEditor::with_history_file(Some(path.clone()));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(reloaded.history.last().map(String::as_str), Some("print(1)"));
+    }
+
+    #[test]
+    fn decoding_keys() {
+        assert_eq!(keys(b"a\r\x7f\x03\x04\x01\x05\x0b\x15\x10\x0e\t"), [
+            Key::Char('a'), Key::Enter, Key::Backspace, Key::Interrupt, Key::EndOfFile, Key::Home, Key::End,
+            Key::KillToEnd, Key::KillToStart, Key::Up, Key::Down, Key::Char(' '),
+        ]);
+
+        assert_eq!(keys(b"\x1b[A\x1b[B\x1b[C\x1b[D\x1bOH\x1bOF\x1b[3~\x1b[1~\x1b[4~\x1b[5~"), [
+            Key::Up, Key::Down, Key::Right, Key::Left, Key::Home, Key::End, Key::Delete, Key::Home, Key::End, Key::Ignored,
+        ]);
+
+        assert_eq!(keys("é→🐍".as_bytes()), [Key::Char('é'), Key::Char('→'), Key::Char('🐍')]);
+        assert_eq!(keys(b"\x07"), [Key::Ignored]);
+        assert_eq!(read_key(&mut &b""[..]), Key::EndOfFile);
+    }
+}
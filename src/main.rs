@@ -1,53 +1,81 @@
-#![feature(fn_traits)]
 // #![feature(let_chains)]
-#![feature(hash_raw_entry)]
 #![feature(get_mut_unchecked)]
 
 mod parser;
 mod evaluator;
+mod compiler;
+mod vm;
 mod pyarena;
 mod builtins;
 mod preprocessor;
+mod namespace;
+mod inline_cache;
+mod quicken;
+mod gc;
+mod interrupt;
+mod cli;
+mod repl;
+mod line_editor;
 
 use std::env;
-use std::fs::File;
-use std::io::Read;
+use std::io::{self, Write};
+use std::process;
+use std::thread;
+use crate::cli::{load_source, parse_args, Options, Source};
 use crate::evaluator::{evaluate};
-use crate::parser::{parse_code, remove_comments};
+use crate::interrupt::install_sigint_handler;
+use crate::pyarena::EVAL_STACK_SIZE;
+use crate::repl::run_repl;
+use crate::parser::{join_bracketed_lines, parse_code, remove_comments};
 
 #[macro_use]
 extern crate mopa;
 
 fn main() {
     // env::set_var("RUST_BACKTRACE", "1");
-    let args: Vec<String> = env::args().collect();
-    
-    let mut contents = String::new();
-    
-    if args.len() == 1 {
-        // contents = "  a=4\n  a + 2".to_string();
-        println!("No target file");
-        return;
-
-    } else if args.len() > 2 {
-        panic!("Expect 1 arg for the test file name, got: {}", args.len() - 1);
-    } else {
-        let filename = &args[1];
-        let mut file = File::open("tests/".to_string() + filename).unwrap_or_else(|_| panic!("file not found: {}", filename));
-
-        let _ = file.read_to_string(&mut contents);
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let options = parse_args(&args).unwrap_or_else(|status| process::exit(status));
+
+    install_sigint_handler();
+
+    let interpreter = thread::Builder::new()  // deep python recursion needs more native stack than the main thread has
+        .stack_size(EVAL_STACK_SIZE)
+        .spawn(move || run(options))
+        .expect("failed to spawn the interpreter thread");
+
+    let status = interpreter.join().expect("interpreter thread panicked");
+
+    let _ = io::stdout().flush();  // `process::exit` skips destructors
+    process::exit(status);
+}
+
+fn run(mut options: Options) -> i32 {
+    if let Source::Interactive = options.source {
+        return run_repl(options.argv);
     }
+
+    let contents = match load_source(&mut options) {
+        Ok(contents) => contents,
+        Err(status) => return status,
+    };
+
     let contents = remove_comments(&contents);
-    let contents = contents.trim();
-    
-    let parse_tree = parse_code(contents);
-    if let Ok(parse_tree) = parse_tree.0 {
-        
-        println!("{:?}", parse_tree);
-        
-        evaluate(parse_tree);
-        
-    } else if let Err(parse_tree_err) = parse_tree.0 {
-        println!("Char: \"{}\"({})\nError: {:?}", contents.chars().nth(parse_tree_err.location.offset).unwrap_or_default(), contents.bytes().nth(parse_tree_err.location.offset).unwrap_or_default(), parse_tree_err);
+
+    let parse_tree = parse_code(&contents);
+    match parse_tree.0 {
+        Ok(parse_tree) => {
+            if options.dump_parse_tree {
+                println!("{:?}", parse_tree);
+            }
+
+            evaluate(parse_tree, options.argv)
+        }
+        Err(parse_tree_err) => {
+            let contents = join_bracketed_lines(&contents);  // the error offset is into the text the grammar saw
+            let contents = contents.trim();
+            eprintln!("Char: \"{}\"({})\nError: {:?}", contents.chars().nth(parse_tree_err.location.offset).unwrap_or_default(), contents.as_bytes().get(parse_tree_err.location.offset).copied().unwrap_or_default(), parse_tree_err);
+            1
+        }
     }
 }
@@ -0,0 +1,141 @@
+use std::cell::{Cell, RefCell};
+use std::fmt::{Debug, Formatter};
+use std::hash::BuildHasher;
+use std::rc::Rc;
+use ahash::{AHashMap, RandomState};
+use hashbrown::hash_table::{Entry, HashTable};
+use crate::builtins::structure::pyobject::PyObject;
+
+const NAME_HASHER: RandomState = RandomState::with_seeds(0x243f_6a88_85a3_08d3, 0x1319_8a2e_0370_7344, 0xa409_3822_299f_31d0, 0x082e_fa98_ec4e_6c89);  // fixed so the parser can hash names ahead of time
+
+pub fn name_hash(name: &str) -> u64 {
+    BuildHasher::hash_one(&NAME_HASHER, name)
+}
+
+pub type NameCell = Rc<Cell<PyObject>>;
+
+pub fn read_cell(cell: &Cell<PyObject>) -> PyObject {  // a copy, the cell can be stored to while the value is in use
+    let value = cell.replace(PyObject::None);
+    cell.set(value.clone());
+    value
+}
+
+pub struct Namespace {  // module globals or builtins
+    entries: HashTable<(String, NameCell)>,  // keyed by `name_hash`, so sites can hash their name once
+    version: u64,  // bumped whenever a name is added or removed, a cell that stays in the map sees every store
+}
+
+impl Namespace {
+    pub fn new(entries: impl IntoIterator<Item=(String, Cell<PyObject>)>) -> Self {
+        let mut namespace = Namespace { entries: HashTable::new(), version: 0 };
+
+        for (name, value) in entries {
+            namespace.set(name_hash(&name), &name, value.into_inner());
+        }
+
+        namespace.version = 0;
+        namespace
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn lookup(&self, hash: u64, name: &str) -> Option<&NameCell> {
+        self.entries.find(hash, |(key, _cell)| key == name).map(|(_name, cell)| cell)
+    }
+
+    pub fn get(&self, name: &str) -> Option<PyObject> {
+        self.lookup(name_hash(name), name).map(|cell| read_cell(cell))
+    }
+
+    pub fn set(&mut self, hash: u64, name: &str, value: PyObject) -> &NameCell {
+        match self.entries.entry(hash, |(key, _cell)| key == name, |(key, _cell)| name_hash(key)) {
+            Entry::Occupied(entry) => {
+                entry.get().1.set(value);
+                &entry.into_mut().1
+            }
+            Entry::Vacant(entry) => {
+                self.version += 1;
+                &entry.insert((name.to_string(), Rc::new(Cell::new(value)))).into_mut().1
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        if let Ok(entry) = self.entries.find_entry(name_hash(name), |(key, _cell)| key == name) {
+            let ((_name, cell), _) = entry.remove();
+            cell.set(PyObject::None);  // stale caches may still hold the cell, they shouldn't keep the value alive
+            self.version += 1;
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ClassScope {  // a class body's names, in definition order for `__set_name__` and the namespace handed to a metaclass
+    positions: AHashMap<String, usize>,
+    entries: Vec<(String, Cell<PyObject>)>,
+}
+
+impl ClassScope {
+    pub fn get(&self, name: &str) -> Option<&Cell<PyObject>> {
+        self.positions.get(name).map(|&position| &self.entries[position].1)
+    }
+
+    pub fn set(&mut self, name: String, value: PyObject) {  // rebinding a name keeps its place, like a dict
+        match self.positions.get(&name) {
+            Some(&position) => self.entries[position].1.set(value),
+            None => {
+                self.positions.insert(name.clone(), self.entries.len());
+                self.entries.push((name, Cell::new(value)));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        let Some(position) = self.positions.remove(name) else {
+            return;
+        };
+
+        self.entries.remove(position);
+        self.positions.values_mut().filter(|later| **later > position).for_each(|later| *later -= 1);
+    }
+
+    pub fn into_entries(self) -> Vec<(String, PyObject)> {
+        self.entries.into_iter().map(|(name, value)| (name, value.into_inner())).collect()
+    }
+}
+
+pub struct GlobalCache {  // one per load or store site of a global, lives in the `CodeObject`
+    pub name: usize,
+    pub hash: u64,
+    pub entry: RefCell<Option<CachedGlobal>>,
+}
+
+pub struct CachedGlobal {
+    pub globals_version: u64,
+    pub builtins_version: u64,
+    pub cell: NameCell,
+}
+
+impl CachedGlobal {
+    pub fn is_current(&self, globals: &Namespace, builtins: &Namespace) -> bool {  // neither namespace gained or lost a name since the cell was resolved
+        self.globals_version == globals.version() && self.builtins_version == builtins.version()
+    }
+}
+
+impl GlobalCache {
+    pub fn new(name: usize, hash: u64) -> Self {
+        GlobalCache { name, hash, entry: RefCell::new(None) }
+    }
+
+    pub fn fill(&self, globals: &Namespace, builtins: &Namespace, cell: NameCell) {
+        *self.entry.borrow_mut() = Some(CachedGlobal { globals_version: globals.version(), builtins_version: builtins.version(), cell });
+    }
+}
+
+impl Debug for GlobalCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GlobalCache").field("name", &self.name).field("hash", &self.hash).finish_non_exhaustive()
+    }
+}
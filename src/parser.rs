@@ -4,6 +4,8 @@ use peg::error::ParseError;
 use peg::str::LineCol;
 use std::rc::Rc;
 use ahash::AHashMap;
+use crate::namespace::name_hash;
+use crate::preprocessor::preprocess_code;
 
 pub fn remove_comments(input: &str) -> String {
     let mut output = String::new();
@@ -23,21 +25,112 @@ pub fn remove_comments(input: &str) -> String {
     output
 }
 
+pub fn join_bracketed_lines(input: &str) -> String {  // newlines inside (), [] and {} don't end a statement
+    let mut output = String::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                output.push(c);
+                output.extend(chars.next());
+                continue;
+            }
+            Some(quote_char) if c == quote_char => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' => quote = Some(c),
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                '\n' if depth > 0 => {
+                    output.push(' ');
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        output.push(c);
+    }
+    output
+}
+
 #[derive(Debug)]
 pub enum Value {
     Integer(i64),
+    IntegerOverflow(String),  // a literal past i64, raises `OverflowError` when evaluated
     Float(f64),
+    Imaginary(f64),
     String(String),
+    Bytes(Vec<u8>),
     Boolean(bool),
     None,
 }
 
-pub fn parse_code(code: &str) -> (Result<CodeBlock, ParseError<LineCol>>, AHashMap<String, ScopeInformation>) {
+pub fn parse_code(source: &str) -> (Result<CodeBlock, ParseError<LineCol>>, AHashMap<String, ScopeInformation>) {  // `source` has its comments removed, error offsets are into its joined and trimmed text
     let variables = RefCell::new(AHashMap::new());
 
+    let joined = join_bracketed_lines(source);
+    let code = joined.trim();
+    let skipped = joined.len() - joined.trim_start().len();  // joining keeps byte offsets, so only the trimmed start shifts them
+
     let code_result = python_parser::code_traced(code, &variables);
+    let mut variables = variables.into_inner();
+
+    let code_result = code_result.map(|mut code| {
+        let line_starts: Vec<usize> = std::iter::once(0).chain(source.match_indices('\n').map(|(i, _)| i + 1)).collect();
+        number_lines(&mut code, skipped, &line_starts);
+        preprocess_code(&mut code, &mut variables);
+        code
+    });
+
+    (code_result, variables)
+}
+
+fn number_lines(code: &mut CodeBlock, skipped: usize, line_starts: &[usize]) {  // the grammar records where each statement starts, tracebacks want its line
+    for line in code.lines.iter_mut() {
+        let offset = *line + skipped;
+        *line = line_starts.partition_point(|start| *start <= offset);
+    }
+
+    for statement in code.statements.iter_mut() {
+        match statement {
+            Statement::Defn(define) => number_define_lines(define, skipped, line_starts),
+            Statement::For(_, _, body) | Statement::While(_, body) => number_lines(body, skipped, line_starts),
+            Statement::If(_, if_code, elif, else_code) => {
+                number_lines(if_code, skipped, line_starts);
+
+                for (_, elif_code) in elif {
+                    number_lines(elif_code, skipped, line_starts);
+                }
+
+                if let Some(else_code) = else_code {
+                    number_lines(else_code, skipped, line_starts);
+                }
+            }
+            Statement::Try(body, handlers, else_code, finally_code) => {
+                number_lines(body, skipped, line_starts);
+
+                for handler in handlers {
+                    number_lines(&mut handler.code, skipped, line_starts);
+                }
+
+                for code in [else_code, finally_code].into_iter().flatten() {
+                    number_lines(code, skipped, line_starts);
+                }
+            }
+            _ => {}
+        }
+    }
+}
 
-    (code_result, variables.into_inner())
+fn number_define_lines(define: &mut Define, skipped: usize, line_starts: &[usize]) {
+    match define {
+        Define::FunDefn(_, _, body, _) | Define::ClassDefn(_, _, _, body, _) => number_lines(body, skipped, line_starts),
+        Define::Decorated(_, define) => number_define_lines(define, skipped, line_starts),
+        _ => {}
+    }
 }
 
 
@@ -57,36 +150,35 @@ parser! {
         // recognizes a variable
         // rule var() -> Expr = v:id() {Expr::Var(v)}
         rule var(variables: &RefCell<AHashMap<String, ScopeInformation>>) -> Rc<Variable> = name:id() {
-            name
-            // let mut variables = variables.borrow_mut();
-            // 
-            // let scope_info_option = variables.get(&name).cloned();
-            // 
-            // let scope_info = scope_info_option.unwrap_or_else(|| {
-            //     let scope_info = ScopeInformation {
-            //         variable: Rc::new(Variable {
-            //             name: name.clone(),
-            //             fast_locals_location: None
-            //         }),
-            //         uses: 0,
-            //         has_definition: false
-            //     };
-            //     
-            //     variables.insert(name, scope_info.clone());
-            //     
-            //     scope_info
-            // });
-            // 
-            // scope_info.variable.clone()
+            let mut variables = variables.borrow_mut();  // every use of a name in one scope shares a `Variable`, so the preprocessor can assign its slot once
+
+            let scope_info = variables.entry(name.clone()).or_insert_with(|| ScopeInformation {
+                variable: Rc::new(Variable {
+                    hash: name_hash(&name),
+                    name,
+                    fast_locals_location: None
+                }),
+                uses: 0,
+                has_definition: false
+            });
+
+            scope_info.variable.clone()
         }
 
-        rule float() -> f64 = n:$("-"? ['0'..='9']* "." ['0'..='9']+) {n.parse().unwrap()} / n:$("-"? ['0'..='9']+ "." ['0'..='9']*) {n.parse().unwrap()}
-        rule integer() -> i64 = n:$("-"? ['0'..='9']+) {n.parse().unwrap()}
+        rule exponent() = ['e' | 'E'] ['+' | '-']? ['0'..='9']+
+        rule float() -> f64 = n:$((['0'..='9']* "." ['0'..='9']+ / ['0'..='9']+ "." ['0'..='9']*) exponent()? / ['0'..='9']+ exponent()) {n.parse().unwrap()}
+        rule imaginary() -> f64 = n:$(['0'..='9']* "." ['0'..='9']+ / ['0'..='9']+ "." ['0'..='9']* / ['0'..='9']+) ['j' | 'J'] {n.parse().unwrap()}
+        rule integer() -> Value = n:$(['0'..='9']+) {n.parse().map(Value::Integer).unwrap_or_else(|_| Value::IntegerOverflow(n.to_string()))}
         rule string() -> String = "\"" s:$([^('\n' | '"')]*) "\"" {s.to_string()}  // TODO make string match correct
+        rule byte_escape() -> u8 =
+            "\\x" h:$(['0'..='9' | 'a'..='f' | 'A'..='F']*<2>) {u8::from_str_radix(h, 16).unwrap()}
+            / "\\n" {b'\n'} / "\\t" {b'\t'} / "\\r" {b'\r'} / "\\0" {0} / "\\\\" {b'\\'} / "\\\"" {b'"'} / "\\'" {b'\''}
+        rule byte_char() -> u8 = byte_escape() / c:[' '..='~'] {? if c == '"' || c == '\\' { Err("byte") } else { Ok(c as u8) }}
+        rule bytes() -> Vec<u8> = "b\"" b:byte_char()* "\"" {b}  // only ASCII characters are allowed in bytes literals
         rule boolean() -> bool = $"True" {true} / $"False" {false}
         rule none() -> Value = "None" {Value::None}
 
-        rule val() -> Value = f:float() {Value::Float(f)} / i:integer() {Value::Integer(i)} / s:string() {Value::String(s)} / b:boolean() {Value::Boolean(b)} / n:none() {n}
+        rule val() -> Value = b:bytes() {Value::Bytes(b)} / j:imaginary() {Value::Imaginary(j)} / f:float() {Value::Float(f)} / i:integer() {i} / s:string() {Value::String(s)} / b:boolean() {Value::Boolean(b)} / n:none() {n}
 
         rule expr(vars: &RefCell<AHashMap<String, ScopeInformation>>) -> Expr = precedence!{
             // comparisons ==, !=, >, >=, <, <=, is, is not, in, not in
@@ -109,9 +201,14 @@ parser! {
             l:(@) sp() "+" sp() r:@ {Expr::Plus(Box::new(l), Box::new(r))}
             l:(@) sp() "-" sp() r:@ {Expr::Minus(Box::new(l), Box::new(r))}
             --
-            l:(@) sp() "*" sp() r:@ {Expr::Times(Box::new(l), Box::new(r))}  // include @, //, and %
+            l:(@) sp() "*" sp() r:@ {Expr::Times(Box::new(l), Box::new(r))}  // include @
+            l:(@) sp() "//" sp() r:@ {Expr::FloorDivide(Box::new(l), Box::new(r))}
             l:(@) sp() "/" sp() r:@ {Expr::Divide(Box::new(l), Box::new(r))}
-            -- // Unary ops here
+            l:(@) sp() "%" sp() r:@ {Expr::Modulo(Box::new(l), Box::new(r))}
+            --
+            "-" sp() v:@ {Expr::Neg(Box::new(v))}  // binds looser than **, so -2 ** 2 == -4
+            "+" sp() v:@ {Expr::Pos(Box::new(v))}
+            -- // bitwise ~ here
             l:@ sp() "**" sp() r:(@) {Expr::Pow(Box::new(l), Box::new(r))}
             --
             l:(@) sp1() "and" sp1() r:@ {Expr::And(Box::new(l), Box::new(r))}
@@ -119,32 +216,103 @@ parser! {
             --
             "not" sp1() v:expr(vars) {Expr::Not(Box::new(v))}
             --
+            f:@ sp() "(" sp() args:(expr(vars) ** (sp() "," sp())) sp() ")" {Expr::FunCall(Box::new(f), args)}
+            obj:@ "." attr:id() {Expr::GetAttr(Box::new(obj), attr)}
+            obj:@ "[" sp() key:subscript(vars) sp() "]" {Expr::Subscript(Box::new(obj), Box::new(key))}
+            --
             v:val() {Expr::Val(v)}
-            f:var(vars) sp() "(" sp() args:(expr(vars) ** (sp() "," sp())) sp() ")" {Expr::FunCall(Box::new(Expr::Var(f)), args)} // `f` should be an expression for more robuts parsing (might create loop(?))
             v:var(vars) {Expr::Var(v)}
+            "[" sp() items:(expr(vars) ** (sp() "," sp())) sp() ","? sp() "]" {Expr::List(items)}
+            "{" sp() entries:((k:expr(vars) sp() ":" sp() v:expr(vars) {(k, v)}) ** (sp() "," sp())) sp() ","? sp() "}" {Expr::Dict(entries)}
             --
+            "(" sp() ")" {Expr::Tuple(vec![])}
+            "(" sp() first:expr(vars) sp() "," sp() rest:(expr(vars) ** (sp() "," sp())) sp() ","? sp() ")" {Expr::Tuple(std::iter::once(first).chain(rest).collect())}
             "(" e:expr(vars) ")" {e}
         }
 
-        rule function_definition(depth: usize, vars: RefCell<AHashMap<String, ScopeInformation>>) -> Define =
-            "def" sp1() f:var(&vars) sp() "(" sp() args:(var(&vars) ** (sp() "," sp())) sp() ")" sp() ":" next_line() c:code(depth+1, &vars) {Define::FunDefn(f, args, c, vars.into_inner())}
+        rule subscript(vars: &RefCell<AHashMap<String, ScopeInformation>>) -> Expr =
+            start:expr(vars)? sp() ":" sp() stop:expr(vars)? sp() step:(":" sp() e:expr(vars)? {e})? {Expr::Slice(start.map(Box::new), stop.map(Box::new), step.flatten().map(Box::new))}
+            / expr(vars)
+
+        rule function_definition(depth: usize, vars: &RefCell<AHashMap<String, ScopeInformation>>) -> Define =
+            "def" sp1() f:var(vars) sp() body:function_body(depth, RefCell::new(AHashMap::new())) {let (args, c, scope) = body; Define::FunDefn(f, args, c, scope)}
+
+        rule function_body(depth: usize, vars: RefCell<AHashMap<String, ScopeInformation>>) -> (Vec<Rc<Variable>>, CodeBlock, AHashMap<String, ScopeInformation>) =  // the name is bound outside, everything else is in the function's own scope
+            "(" sp() args:(var(&vars) ** (sp() "," sp())) sp() ")" sp() ":" next_line() c:code(depth+1, &vars) {(args, c, vars.into_inner())}
+
+
+        rule class_definition(depth: usize, vars: &RefCell<AHashMap<String, ScopeInformation>>) -> Define =
+            "class" sp1() name:var(vars) sp() arguments:class_arguments(vars)? sp() ":" body:class_body(depth, RefCell::new(AHashMap::new())) {
+                let (bases, metaclass) = arguments.unwrap_or_default();
+                Define::ClassDefn(name, bases, metaclass, body.0, body.1)
+            }
+
+        rule class_arguments(vars: &RefCell<AHashMap<String, ScopeInformation>>) -> (Vec<Expr>, Option<Expr>) =  // `metaclass` is the only keyword a class accepts
+            "(" sp() bases:((!metaclass_keyword() e:expr(vars) {e}) ** (sp() "," sp())) metaclass:(sp() "," sp() m:metaclass_argument(vars) {m})? sp() ")" {(bases, metaclass)}
+            / "(" sp() m:metaclass_argument(vars) sp() ")" {(vec![], Some(m))}
+
+        rule metaclass_argument(vars: &RefCell<AHashMap<String, ScopeInformation>>) -> Expr =
+            metaclass_keyword() sp() e:expr(vars) {e}
+
+        rule metaclass_keyword() =
+            "metaclass" sp() "=" !"="
 
+        rule class_body(depth: usize, vars: RefCell<AHashMap<String, ScopeInformation>>) -> (CodeBlock, AHashMap<String, ScopeInformation>) =  // class bodies have their own namespace
+            c:suite(depth, &vars) {(c, vars.into_inner())}
+
+        rule decorated_definition(depth: usize, vars: &RefCell<AHashMap<String, ScopeInformation>>) -> Define =
+            decorators:("@" sp() e:expr(vars) next_line() indent(depth) nosp() {e})+ definition:(function_definition(depth, vars) / class_definition(depth, vars)) {Define::Decorated(decorators, Box::new(definition))}
 
         rule define(depth: usize, vars: &RefCell<AHashMap<String, ScopeInformation>>) -> Define =
-            func:function_definition(depth, RefCell::new(AHashMap::new())) {func}
+            decorated:decorated_definition(depth, vars) {decorated}
+            / func:function_definition(depth, vars) {func}
+            / class:class_definition(depth, vars) {class}
             / v:var(vars) sp() "=" sp() e:expr(vars) {Define::VarDefn(v, e)}
             / v:var(vars) sp() "+=" sp() e:expr(vars) {Define::PlusEq(v, e)}
             / v:var(vars) sp() "-=" sp() e:expr(vars) {Define::MinusEq(v, e)}
             / v:var(vars) sp() "/=" sp() e:expr(vars) {Define::DivEq(v, e)}
             / v:var(vars) sp() "*=" sp() e:expr(vars) {Define::MultEq(v, e)}
+            / target:expr(vars) sp() "=" sp() e:expr(vars) {?
+                match target {
+                    Expr::Subscript(obj, key) => Ok(Define::SetItem(*obj, *key, e)),
+                    Expr::GetAttr(obj, name) => Ok(Define::SetAttr(*obj, name, e)),
+                    _ => Err("assignable target"),
+                }
+            }
 
         rule if_(depth: usize, vars: &RefCell<AHashMap<String, ScopeInformation>>) -> Statement =
             "if" sp1() cond:expr(vars) sp() ":" next_line() if_code:code(depth + 1, vars)
             elif:(next_line() indent(depth) "elif" sp1() elif_cond:expr(vars) sp() ":" next_line() elif_code:code(depth+1, vars) {(elif_cond, elif_code)})*
             else_code:(next_line() indent(depth) "else" sp() ":" next_line() else_code:code(depth+1, vars) {else_code})? {Statement::If(cond, if_code, elif, else_code)}
 
+        rule except_handler(depth: usize, vars: &RefCell<AHashMap<String, ScopeInformation>>) -> ExceptHandler =
+            next_line() indent(depth) "except" exception:(sp1() e:expr(vars) {e})? name:(sp1() "as" sp1() v:var(vars) {v})? sp() ":" code:suite(depth, vars) {ExceptHandler {exception, name, code}}
+
+        rule try_(depth: usize, vars: &RefCell<AHashMap<String, ScopeInformation>>) -> Statement =
+            "try" sp() ":" body:suite(depth, vars)
+            handlers:except_handler(depth, vars)*
+            else_code:(next_line() indent(depth) "else" sp() ":" c:suite(depth, vars) {c})?
+            finally_code:(next_line() indent(depth) "finally" sp() ":" c:suite(depth, vars) {c})? {?
+                if handlers.is_empty() && finally_code.is_none() {
+                    return Err("except or finally block");
+                }
+                Ok(Statement::Try(body, handlers, else_code, finally_code))
+            }
+
         rule statement(depth: usize, vars: &RefCell<AHashMap<String, ScopeInformation>>) -> Statement =
             if_statement:if_(depth, vars) {if_statement}
+            / try_statement:try_(depth, vars) {try_statement}
+            / "raise" sp1() e:expr(vars) cause:(sp1() "from" sp1() c:expr(vars) {c})? {Statement::Raise(Some(e), cause)}
+            / "raise" {Statement::Raise(None, None)}  // re-raises the exception being handled
+            / "pass" {Statement::Pass}
+            / "del" sp1() targets:(expr(vars) ++ (sp() "," sp())) {?
+                if targets.iter().all(|target| matches!(target, Expr::Var(_) | Expr::GetAttr(..))) {
+                    Ok(Statement::Del(targets))
+                } else {
+                    Err("deletable target")  // subscripts need `__delitem__`, which nothing implements yet
+                }
+            }
+            / "import" sp1() modules:(var(vars) ++ (sp() "," sp())) {Statement::Import(modules)}
             / "for" sp1() v:var(vars) sp1() "in" sp1() e:expr(vars) sp() ":" next_line() c:code(depth + 1, vars) {Statement::For(v, e, c)}
             / "while" sp1() e:expr(vars) sp() ":" next_line() c:code(depth + 1, vars) {Statement::While(e, c)}
             / "assert" sp1() e1:expr(vars) e2:("," sp() e:expr(vars) {e})?  {Statement::Assert(e1, e2)}
@@ -155,9 +323,16 @@ parser! {
             / d:define(depth, vars) {Statement::Defn(d)}
             / e:expr(vars) {Statement::Expr(e)}
 
+        rule suite(depth: usize, vars: &RefCell<AHashMap<String, ScopeInformation>>) -> CodeBlock =  // an indented block, or a single statement on the same line
+            next_line() c:code(depth + 1, vars) {c}
+            / sp() p:position!() s:statement(depth + 1, vars) {CodeBlock{statements: vec![s], lines: vec![p]}}
+
         // pub rule code(depth: usize) -> CodeBlock = &" "*<{depth}> spaces:" "*<{depth},> s:(statement(depth) ** nl()) sp() {CodeBlock::Block(s)}
         pub rule code(depth: usize, vars: &RefCell<AHashMap<String, ScopeInformation>>) -> CodeBlock =
-            spaces:" "*<{depth},> statements:(statement(depth, vars) ** (next_line() indent(spaces.len()) nosp())) {CodeBlock{statements, depth: spaces.len()}}
+            spaces:" "*<{depth},> statements:((p:position!() s:statement(spaces.len(), vars) {(s, p)}) ** (next_line() indent(spaces.len()) nosp())) {
+                let (statements, lines) = statements.into_iter().unzip();
+                CodeBlock{statements, lines}
+            }
 
         rule traced<T>(e: rule<T>) -> T =
             &(input:$([_]*) {
@@ -174,7 +349,7 @@ parser! {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum Comparator {
     Equal,
     NotEqual,
@@ -190,14 +365,24 @@ pub enum Comparator {
 
 #[derive(Debug)]
 pub enum Expr {
-    Var(String),
+    Var(Rc<Variable>),
     Val(Value),
     Times(Box<Expr>, Box<Expr>),
     Divide(Box<Expr>, Box<Expr>),
+    FloorDivide(Box<Expr>, Box<Expr>),
+    Modulo(Box<Expr>, Box<Expr>),
     Plus(Box<Expr>, Box<Expr>),
     Minus(Box<Expr>, Box<Expr>),
     Pow(Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
+    Pos(Box<Expr>),
     FunCall(Box<Expr>, Vec<Expr>),
+    GetAttr(Box<Expr>, String),
+    Subscript(Box<Expr>, Box<Expr>),
+    Slice(Option<Box<Expr>>, Option<Box<Expr>>, Option<Box<Expr>>),
+    List(Vec<Expr>),
+    Tuple(Vec<Expr>),
+    Dict(Vec<(Expr, Expr)>),
     Comparison(Box<Expr>, Comparator, Box<Expr>),
     Not(Box<Expr>),
     And(Box<Expr>, Box<Expr>),
@@ -207,7 +392,8 @@ pub enum Expr {
 #[derive(Debug)]
 pub struct Variable {
     pub name: String,
-    pub fast_locals_location: Option<usize>,
+    pub hash: u64,  // for the namespace lookups, see `name_hash`
+    pub fast_locals_location: Option<usize>,  // slot in the function's frame, assigned by the preprocessor
 }
 
 #[derive(Debug, Clone)]
@@ -224,7 +410,11 @@ pub enum Define {
     DivEq(Rc<Variable>, Expr),
     MultEq(Rc<Variable>, Expr),
     VarDefn(Rc<Variable>, Expr),
+    SetItem(Expr, Expr, Expr),  // obj[key] = value
+    SetAttr(Expr, String, Expr),  // obj.name = value
+    ClassDefn(Rc<Variable>, Vec<Expr>, Option<Expr>, CodeBlock, AHashMap<String, ScopeInformation>),  // name, bases, metaclass, body
     FunDefn(Rc<Variable>, Vec<Rc<Variable>>, CodeBlock, AHashMap<String, ScopeInformation>),
+    Decorated(Vec<Expr>, Box<Define>),  // decorators, outermost first, and the function or class they wrap
 }
 
 #[derive(Debug)]
@@ -236,12 +426,24 @@ pub enum Statement {
     If(Expr, CodeBlock, Vec<(Expr, CodeBlock)>, Option<CodeBlock>), // IfCond, Code, (ElIfCond, Code), ElseCode
     Return(Expr),
     Assert(Expr, Option<Expr>),
+    Try(CodeBlock, Vec<ExceptHandler>, Option<CodeBlock>, Option<CodeBlock>),  // Code, Handlers, ElseCode, FinallyCode
+    Raise(Option<Expr>, Option<Expr>),  // Exception, Cause
+    Pass,
+    Import(Vec<Rc<Variable>>),
+    Del(Vec<Expr>),  // names and attributes
     Continue,
     Break,
 }
 
+#[derive(Debug)]
+pub struct ExceptHandler {
+    pub exception: Option<Expr>,  // a bare `except:` catches everything
+    pub name: Option<Rc<Variable>>,
+    pub code: CodeBlock,
+}
+
 #[derive(Debug)]
 pub struct CodeBlock {
     pub statements: Vec<Statement>,
-    pub depth: usize,
+    pub lines: Vec<usize>,  // the line each statement starts on
 }
\ No newline at end of file
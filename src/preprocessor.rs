@@ -1,4 +1,3 @@
-use std::env::var;
 use std::rc::Rc;
 use ahash::AHashMap;
 use crate::parser::{CodeBlock, Define, Expr, ScopeInformation, Statement, Variable};
@@ -30,13 +29,62 @@ pub fn preprocess_code(code_block: &mut CodeBlock, scope: &mut AHashMap<String,
                     preprocess_code(elif_code, scope);
                 }
 
-                    
-                
+                if let Some(else_code) = else_code {
+                    preprocess_code(else_code, scope);
+                }
+            },
+            Statement::For(iter_var, iter_exp, code) => {
+                add_var_def(iter_var, scope);
+                preprocess_expr(iter_exp, scope);
+                preprocess_code(code, scope);
+            },
+            Statement::While(condition, code) => {
+                preprocess_expr(condition, scope);
+                preprocess_code(code, scope);
+            },
+            Statement::Return(rtn_expr) => { preprocess_expr(rtn_expr, scope) },
+            Statement::Assert(expr1, expr2) => {
+                preprocess_expr(expr1, scope);
+
+                if let Some(expr2) = expr2 {
+                    preprocess_expr(expr2, scope);
+                }
+            },
+            Statement::Try(code, handlers, else_code, finally_code) => {
+                preprocess_code(code, scope);
+
+                for handler in handlers {
+                    if let Some(exception) = &mut handler.exception {
+                        preprocess_expr(exception, scope);
+                    }
+
+                    if let Some(name) = &handler.name {
+                        add_var_def(name, scope);
+                    }
+
+                    preprocess_code(&mut handler.code, scope);
+                }
+
+                for code in [else_code, finally_code].into_iter().flatten() {
+                    preprocess_code(code, scope);
+                }
+            },
+            Statement::Raise(expr, cause) => {
+                for expr in [expr, cause].into_iter().flatten() {
+                    preprocess_expr(expr, scope);
+                }
+            },
+            Statement::Pass => {},
+            Statement::Import(modules) => {
+                for module in modules {
+                    add_var_def(module, scope);
+                }
+            },
+            Statement::Del(targets) => {
+                for target in targets {
+                    preprocess_expr(target, scope);
+                }
             },
-            Statement::For(iter_var, iter_exp, code) => {},
-            Statement::While(condition, code) => {},
-            Statement::Return(rtn_expr) => {},
-            Statement::Assert(expr1, expr2) => {},
             Statement::Continue => {},
             Statement::Break => {},
         };
@@ -48,43 +96,79 @@ fn preprocess_expr(expr: &mut Expr, scope: &mut AHashMap<String, ScopeInformatio
         Expr::Var(var) => add_var_access(var, scope),
         Expr::Val(_val) => {}
         Expr::Times(expr1, expr2) => {
-            preprocess_expr(&mut **expr1, scope);
-            preprocess_expr(&mut **expr2, scope);
+            preprocess_expr(expr1, scope);
+            preprocess_expr(expr2, scope);
         }
         Expr::Divide(expr1, expr2) => {
-            preprocess_expr(&mut **expr1, scope);
-            preprocess_expr(&mut **expr2, scope);
+            preprocess_expr(expr1, scope);
+            preprocess_expr(expr2, scope);
+        }
+        Expr::FloorDivide(expr1, expr2) => {
+            preprocess_expr(expr1, scope);
+            preprocess_expr(expr2, scope);
+        }
+        Expr::Modulo(expr1, expr2) => {
+            preprocess_expr(expr1, scope);
+            preprocess_expr(expr2, scope);
         }
         Expr::Plus(expr1, expr2) => {
-            preprocess_expr(&mut **expr1, scope);
-            preprocess_expr(&mut **expr2, scope);
+            preprocess_expr(expr1, scope);
+            preprocess_expr(expr2, scope);
         }
         Expr::Minus(expr1, expr2) => {
-            preprocess_expr(&mut **expr1, scope);
-            preprocess_expr(&mut **expr2, scope);
+            preprocess_expr(expr1, scope);
+            preprocess_expr(expr2, scope);
         }
         Expr::Pow(expr1, expr2) => {
-            preprocess_expr(&mut **expr1, scope);
-            preprocess_expr(&mut **expr2, scope);
+            preprocess_expr(expr1, scope);
+            preprocess_expr(expr2, scope);
         }
+        Expr::Neg(expr) => {preprocess_expr(expr, scope)}
+        Expr::Pos(expr) => {preprocess_expr(expr, scope)}
         Expr::FunCall(expr1, args) => {
-            preprocess_expr(&mut **expr1, scope);
+            preprocess_expr(expr1, scope);
             for arg in args {
                 preprocess_expr(arg, scope);
             }
         }
+        Expr::GetAttr(expr, _attr) => {preprocess_expr(expr, scope)}
+        Expr::Subscript(expr, key) => {
+            preprocess_expr(expr, scope);
+            preprocess_expr(key, scope);
+        }
+        Expr::Slice(start, stop, step) => {
+            for bound in [start, stop, step].into_iter().flatten() {
+                preprocess_expr(bound, scope);
+            }
+        }
+        Expr::List(items) => {
+            for item in items {
+                preprocess_expr(item, scope);
+            }
+        }
+        Expr::Tuple(items) => {
+            for item in items {
+                preprocess_expr(item, scope);
+            }
+        }
+        Expr::Dict(entries) => {
+            for (key, value) in entries {
+                preprocess_expr(key, scope);
+                preprocess_expr(value, scope);
+            }
+        }
         Expr::Comparison(expr1, _comp, expr2) => {
-            preprocess_expr(&mut **expr1, scope);
-            preprocess_expr(&mut **expr2, scope);
+            preprocess_expr(expr1, scope);
+            preprocess_expr(expr2, scope);
         }
-        Expr::Not(expr) => {preprocess_expr(&mut **expr, scope)}
+        Expr::Not(expr) => {preprocess_expr(expr, scope)}
         Expr::And(expr1, expr2) => {
-            preprocess_expr(&mut **expr1, scope);
-            preprocess_expr(&mut **expr2, scope);
+            preprocess_expr(expr1, scope);
+            preprocess_expr(expr2, scope);
         }
         Expr::Or(expr1, expr2) => {
-            preprocess_expr(&mut **expr1, scope);
-            preprocess_expr(&mut **expr2, scope);
+            preprocess_expr(expr1, scope);
+            preprocess_expr(expr2, scope);
         }
     }
 }
@@ -112,14 +196,65 @@ fn preprocess_defn(defn: &mut Define, scope: &mut AHashMap<String, ScopeInformat
             add_var_def(var, scope);
             preprocess_expr(expr, scope);
         }
+        Define::SetItem(obj, key, expr) => {
+            preprocess_expr(obj, scope);
+            preprocess_expr(key, scope);
+            preprocess_expr(expr, scope);
+        }
+        Define::SetAttr(obj, _name, expr) => {
+            preprocess_expr(obj, scope);
+            preprocess_expr(expr, scope);
+        }
+        Define::ClassDefn(class, bases, metaclass, code, class_scope) => {
+            add_var_def(class, scope);
+
+            for base in bases.iter_mut().chain(metaclass) {
+                preprocess_expr(base, scope);
+            }
+
+            preprocess_code(code, class_scope);  // class bodies keep using the name lookups, so no slots are assigned
+        }
         Define::FunDefn(func, parameters, code, new_scope) => {
             add_var_def(func, scope);
             
-            for param in parameters {
+            for param in parameters.iter() {
                 add_var_def(param, new_scope);
             }
             
             preprocess_code(code, new_scope);
+            assign_fast_locals(parameters, new_scope);
+        }
+        Define::Decorated(decorators, definition) => {
+            for decorator in decorators {
+                preprocess_expr(decorator, scope);
+            }
+
+            preprocess_defn(definition, scope);
         }
     }
-}
\ No newline at end of file
+}
+
+fn assign_fast_locals(parameters: &[Rc<Variable>], scope: &AHashMap<String, ScopeInformation>) {
+    let mut locals: Vec<Rc<Variable>> = parameters.to_vec();  // arguments are copied into the first slots
+
+    let mut other_locals: Vec<&ScopeInformation> = scope.values()
+        .filter(|scope_info| scope_info.has_definition && !parameters.iter().any(|param| param.name == scope_info.variable.name))
+        .collect();
+    other_locals.sort_by(|a, b| a.variable.name.cmp(&b.variable.name));  // keeps the slot order deterministic
+
+    locals.extend(other_locals.into_iter().map(|scope_info| scope_info.variable.clone()));
+
+    for (slot, mut variable) in locals.into_iter().enumerate() {
+        // nothing is borrowing the variable while the tree is being preprocessed, every use just holds the `Rc`
+        unsafe { Rc::get_mut_unchecked(&mut variable).fast_locals_location = Some(slot) };
+    }
+}
+
+pub fn fast_local_names(scope: &AHashMap<String, ScopeInformation>) -> Vec<String> {
+    let mut names: Vec<(usize, String)> = scope.values()
+        .filter_map(|scope_info| scope_info.variable.fast_locals_location.map(|slot| (slot, scope_info.variable.name.clone())))
+        .collect();
+    names.sort();
+
+    names.into_iter().map(|(_slot, name)| name).collect()
+}
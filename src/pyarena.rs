@@ -1,14 +1,36 @@
 use std::cell::Cell;
-use std::collections::hash_map::{RawEntryMut};
 use ahash::{AHashMap};
 use crate::builtins::globals::Globals;
+use crate::builtins::modules::create_builtin_modules;
+use crate::gc::{collect_if_due, run_pending_finalizers, run_weakref_callbacks};
+use crate::interrupt::take_interrupt;
+use crate::namespace::{name_hash, read_cell, ClassScope, GlobalCache, Namespace};
 use crate::builtins::statics::Statics;
-use crate::builtins::structure::pyexception::Exceptions;
-use crate::builtins::structure::pyobject::{PyObject};
+use crate::builtins::structure::pyexception::{Exceptions, PyException};
+use crate::builtins::structure::pyobject::{EmptyFuncReturnType, PyObject};
+
+pub const EVAL_STACK_SIZE: usize = 256 * 1024 * 1024;  // the evaluator runs on its own thread with this much native stack
+const STACK_RED_ZONE: usize = 1024 * 1024;  // headroom kept free for the frames between two depth checks
+const DEFAULT_RECURSION_LIMIT: usize = 1000;
+
+fn stack_address() -> usize {
+    let marker = 0u8;
+    std::hint::black_box(&marker) as *const u8 as usize
+}
 
 // #[derive(Debug)]
 pub struct PyArena {
-    state: AHashMap<String, Cell<PyObject>>,
+    module_globals: Namespace,
+    builtins: Namespace,
+    scopes: Vec<ClassScope>,  // class bodies being executed, innermost last
+    pub exception_stack: Vec<PyException>,  // exceptions currently being handled by an `except` block
+    pub filename: String,  // names the source in tracebacks
+    pub modules: AHashMap<String, PyObject>,
+    pub recursion_depth: usize,
+    pub recursion_limit: usize,
+    pub type_version: u64,  // bumped whenever a user class is changed, inline caches compare it before trusting a resolved method
+    pub attribute_hooks_defined: bool,  // set once any user class defines `__getattribute__` and friends, until then attribute access skips looking for them
+    stack_limit: usize,  // lowest stack address the evaluator may reach, the stack grows down
     pub globals: Globals,
     pub statics: Statics,
     pub exceptions: Exceptions,
@@ -18,71 +40,128 @@ impl PyArena {
     pub fn new() -> Self {
         let globals = Globals::new();
         let statics = Statics::new();
-        let exceptions = Exceptions::new();
+        let exceptions = Exceptions::new(globals.object_class.clone());
+
+        let mut builtins = globals.create_exposed_globals();
+        builtins.extend(exceptions.create_exposed_exceptions());
+        builtins.insert("NotImplemented".to_string(), Cell::new(statics.not_implemented().clone()));
+        let modules = create_builtin_modules(&globals);
 
         PyArena {
-            state: globals.create_exposed_globals(),
+            module_globals: Namespace::new([("__name__".to_string(), Cell::new(PyObject::new_string("__main__".to_string())))]),  // both scripts and the REPL run as the main module
+            builtins: Namespace::new(builtins),
+            scopes: vec![],
+            exception_stack: vec![],
+            filename: "<stdin>".to_string(),
+            modules,
+            recursion_depth: 0,
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+            type_version: 0,
+            attribute_hooks_defined: false,
+            stack_limit: stack_address().saturating_sub(EVAL_STACK_SIZE - STACK_RED_ZONE),
             globals,
             statics,
             exceptions
         }
     }
 
-    pub fn get_hash(&self, key: &str) -> u64 {
-        let hasher = self.state.hasher();
-        hasher.hash_one(key)
+    pub fn check_interrupt(&self) -> EmptyFuncReturnType {  // polled by loops and calls so Ctrl-C surfaces as a python exception
+        if take_interrupt() {
+            return Err(self.exceptions.keyboard_interrupt.empty());
+        }
+
+        Ok(())
     }
 
-    pub fn set_occupied_from_hash(&mut self, hash: u64, key: &str, value: PyObject) {
-        let entry = self.state.raw_entry_mut().from_key_hashed_nocheck(hash, key);
+    pub fn check_safe_point(&mut self) -> EmptyFuncReturnType {  // polled by loops and calls, the only places a garbage collection starts
+        self.check_interrupt()?;
+        collect_if_due(self);
+        self.run_release_hooks();
 
-        match entry {
-            RawEntryMut::Occupied(entry) => {
-                entry.get().set(value);
-            }
-            RawEntryMut::Vacant(_entry) => {
-                panic!("Tried to set occupied from hash, but entry was vacant");
-            }
+        Ok(())
+    }
+
+    pub fn run_release_hooks(&mut self) {  // `__del__` and weakref callbacks of the objects freed since the last call, run after instructions that drop references
+        run_pending_finalizers(self);
+        run_weakref_callbacks(self);
+    }
+
+    pub fn enter_call(&mut self) -> EmptyFuncReturnType {  // must be paired with `exit_call` once the call returns
+        self.check_safe_point()?;
+
+        if self.recursion_depth >= self.recursion_limit || stack_address() < self.stack_limit {
+            return Err(self.exceptions.recursion_error.instantiate("maximum recursion depth exceeded".to_string()));
         }
+
+        self.recursion_depth += 1;
+        Ok(())
+    }
+
+    pub fn exit_call(&mut self) {
+        self.recursion_depth -= 1;
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(ClassScope::default());
+    }
+
+    pub fn pop_scope(&mut self) -> Vec<(String, PyObject)> {  // in definition order
+        self.scopes.pop().expect("popped a scope that was never pushed").into_entries()
     }
 
     pub fn set(&mut self, key: String, value: PyObject) {
-        let entry = self.state.raw_entry_mut().from_key(&key);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.set(key, value);
+            return;
+        }
 
-        match entry {
-            RawEntryMut::Occupied(entry) => {
-                entry.get().set(value);
-            }
-            RawEntryMut::Vacant(entry) => {
-                entry.insert(key, Cell::new(value));
-            }
+        self.module_globals.set(name_hash(&key), &key, value);
+    }
+
+    pub fn get(&self, key: &str) -> Option<PyObject> {
+        if let Some(cell) = self.scopes.last().and_then(|scope| scope.get(key)) {  // class bodies see their own names, then globals
+            return Some(read_cell(cell));
         }
+
+        self.get_global(key)
     }
 
-    pub fn update(&mut self, key: &str, value: PyObject) {
-        let entry = self.state.raw_entry_mut().from_key(key);
+    pub fn get_global(&self, key: &str) -> Option<PyObject> {  // skips the class bodies being executed
+        self.module_globals.get(key).or_else(|| self.builtins.get(key))
+    }
 
-        match entry {
-            RawEntryMut::Occupied(entry) => {
-                entry.get().set(value);
-            }
-            RawEntryMut::Vacant(_entry) => {
-                panic!("Tried to update, but entry was vacant");
+    pub fn load_global(&self, cache: &GlobalCache, key: &str) -> Option<PyObject> {
+        if let Some(cached) = &*cache.entry.borrow() {
+            if cached.is_current(&self.module_globals, &self.builtins) {
+                return Some(read_cell(&cached.cell));
             }
         }
+
+        let cell = self.module_globals.lookup(cache.hash, key).or_else(|| self.builtins.lookup(cache.hash, key))?.clone();
+        let value = read_cell(&cell);
+        cache.fill(&self.module_globals, &self.builtins, cell);
+
+        Some(value)
     }
 
-    pub fn get(&self, key: &str) -> Option<&PyObject> {
-        unsafe {
-            Some(&*self.state.get(key)?.as_ptr())
+    pub fn store_global(&mut self, cache: &GlobalCache, key: &str, value: PyObject) {  // a store site only ever caches a cell of the module globals
+        if let Some(cached) = &*cache.entry.borrow() {
+            if cached.is_current(&self.module_globals, &self.builtins) {
+                cached.cell.set(value);
+                return;
+            }
         }
-    }
 
-    pub fn get_cell(&self, key: &str) -> Option<&Cell<PyObject>> {
-        self.state.get(key)
+        let cell = self.module_globals.set(cache.hash, key, value).clone();
+        cache.fill(&self.module_globals, &self.builtins, cell);
     }
 
     pub fn remove(&mut self, key: &str) {
-        self.state.remove(key);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.remove(key);
+            return;
+        }
+
+        self.module_globals.remove(key);
     }
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+use crate::builtins::structure::magic_methods::PyMagicMethod;
+use crate::builtins::structure::pyobject::PyObject;
+use crate::compiler::Instruction;
+use crate::inline_cache::OperandKind;
+use crate::parser::Comparator;
+
+pub fn quicken_binary_op(op: PyMagicMethod, kind: OperandKind, cache: usize) -> Option<Instruction> {
+    match (kind, op) {
+        (OperandKind::Int, PyMagicMethod::Add {..} | PyMagicMethod::Sub {..} | PyMagicMethod::Mul {..}) => Some(Instruction::BinaryOpInt(op, cache)),
+        (OperandKind::Float, PyMagicMethod::Add {..} | PyMagicMethod::Sub {..} | PyMagicMethod::Mul {..} | PyMagicMethod::TrueDiv {..}) => Some(Instruction::BinaryOpFloat(op, cache)),
+        _ => None,
+    }
+}
+
+pub fn quicken_compare_op(comp: Comparator, kind: OperandKind, cache: usize) -> Option<Instruction> {
+    compare_ordered(comp, 0, 0)?;
+
+    match kind {
+        OperandKind::Int => Some(Instruction::CompareOpInt(comp, cache)),
+        OperandKind::Float => Some(Instruction::CompareOpFloat(comp, cache)),
+        OperandKind::Other => None,
+    }
+}
+
+pub fn int_operands(left: &PyObject, right: &PyObject) -> Option<(i64, i64)> {
+    match (left, right) {
+        (PyObject::Int(left), PyObject::Int(right)) => Some((*left, *right)),
+        _ => None,
+    }
+}
+
+pub fn float_operands(left: &PyObject, right: &PyObject) -> Option<(f64, f64)> {
+    match (left, right) {
+        (PyObject::Float(left), PyObject::Float(right)) => Some((*left, *right)),
+        _ => None,
+    }
+}
+
+pub fn int_binary_op(op: PyMagicMethod, left: i64, right: i64) -> Option<i64> {  // `None` leaves overflow to the generic path, which raises
+    match op {
+        PyMagicMethod::Add {..} => left.checked_add(right),
+        PyMagicMethod::Sub {..} => left.checked_sub(right),
+        PyMagicMethod::Mul {..} => left.checked_mul(right),
+        _ => None,
+    }
+}
+
+pub fn float_binary_op(op: PyMagicMethod, left: f64, right: f64) -> Option<f64> {  // `None` leaves division by zero to the generic path
+    match op {
+        PyMagicMethod::Add {..} => Some(left + right),
+        PyMagicMethod::Sub {..} => Some(left - right),
+        PyMagicMethod::Mul {..} => Some(left * right),
+        PyMagicMethod::TrueDiv {..} if right != 0.0 => Some(left / right),
+        _ => None,
+    }
+}
+
+pub fn compare_ordered<T: PartialOrd>(comp: Comparator, left: T, right: T) -> Option<bool> {
+    match comp {
+        Comparator::Equal => Some(left == right),
+        Comparator::NotEqual => Some(left != right),
+        Comparator::LessThan => Some(left < right),
+        Comparator::LessThanOrEqual => Some(left <= right),
+        Comparator::GreaterThan => Some(left > right),
+        Comparator::GreaterThanOrEqual => Some(left >= right),
+        _ => None,
+    }
+}
+
+pub fn as_bool(obj: &PyObject) -> Option<bool> {
+    match obj {
+        PyObject::Bool(value) => Some(*value),
+        _ => None,
+    }
+}
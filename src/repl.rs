@@ -0,0 +1,189 @@
+use std::env;
+use crate::builtins::modules::sys::set_sys_argv;
+use crate::compiler::compile_interactive;
+use crate::evaluator::system_exit_status;
+use crate::line_editor::{LineEditor, ReadResult};
+use crate::parser::{parse_code, remove_comments};
+use crate::pyarena::PyArena;
+use crate::vm::run_code;
+
+struct SourceState {
+    bracket_depth: usize,
+    in_string: bool,  // only triple quoted strings can span lines
+    opens_block: bool,  // a line ended with `:` outside of brackets
+}
+
+fn scan_source(source: &str) -> SourceState {  // tracks just enough of the tokenizer to know if the input is finished
+    let mut state = SourceState { bracket_depth: 0, in_string: false, opens_block: false };
+    let mut quote: Option<(char, bool)> = None;  // (quote char, is triple quoted)
+    let chars: Vec<char> = source.chars().collect();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let char = chars[index];
+
+        if let Some((quote_char, triple)) = quote {
+            if char == '\\' {
+                index += 2;
+                continue;
+            }
+
+            if char == quote_char && (!triple || chars[index..].starts_with(&[quote_char; 3])) {
+                quote = None;
+                index += if triple { 3 } else { 1 };
+                continue;
+            }
+
+            if char == '\n' && !triple {  // an unterminated single quoted string is a syntax error, leave it to the parser
+                quote = None;
+            }
+
+            index += 1;
+            continue;
+        }
+
+        match char {
+            '#' => {
+                while index < chars.len() && chars[index] != '\n' {
+                    index += 1;
+                }
+                continue;
+            }
+            '\'' | '"' => {
+                let triple = chars[index..].starts_with(&[char; 3]);
+                quote = Some((char, triple));
+                index += if triple { 3 } else { 1 };
+                continue;
+            }
+            '(' | '[' | '{' => state.bracket_depth += 1,
+            ')' | ']' | '}' => state.bracket_depth = state.bracket_depth.saturating_sub(1),
+            ':' if state.bracket_depth == 0 => {
+                let rest_of_line = chars[index + 1..].iter().take_while(|&&char| char != '\n').collect::<String>();
+
+                if rest_of_line.trim().is_empty() || rest_of_line.trim_start().starts_with('#') {
+                    state.opens_block = true;
+                }
+            }
+            _ => {}
+        }
+
+        index += 1;
+    }
+
+    state.in_string = quote.is_some();
+    state
+}
+
+fn needs_more_input(buffer: &str, last_line: &str) -> bool {
+    let state = scan_source(buffer);
+
+    if state.bracket_depth > 0 || state.in_string || last_line.trim_end().ends_with('\\') {
+        return true;
+    }
+
+    state.opens_block && !last_line.trim().is_empty()  // a block ends with an empty line
+}
+
+pub fn run_repl(argv: Vec<String>) -> i32 {
+    println!("RustyPython {} on {}", env!("CARGO_PKG_VERSION"), env::consts::OS);
+    println!("Type \"exit()\" or press Ctrl-D to leave.");
+
+    let mut arena = PyArena::new();
+    set_sys_argv(argv, &mut arena);
+
+    let mut editor = LineEditor::new();
+
+    'session: loop {
+        let mut buffer = String::new();
+        let mut prompt = ">>> ";
+
+        loop {
+            match editor.read_line(prompt) {
+                ReadResult::Line(line) => {
+                    editor.add_history(&line);
+
+                    buffer.push_str(&line);
+                    buffer.push('\n');
+
+                    if !needs_more_input(&buffer, &line) {
+                        break;
+                    }
+
+                    prompt = "... ";
+                }
+                ReadResult::Interrupted => {
+                    println!("KeyboardInterrupt");
+                    continue 'session;
+                }
+                ReadResult::Eof => return 0,
+            }
+        }
+
+        let contents = remove_comments(&buffer);
+
+        if contents.trim().is_empty() {
+            continue;
+        }
+
+        let code = match parse_code(&contents).0 {
+            Ok(code) => code,
+            Err(err) => {
+                eprintln!("SyntaxError: invalid syntax (line {}, column {})", err.location.line, err.location.column);
+                continue;
+            }
+        };
+
+        let code = match compile_interactive(&code, &arena) {
+            Ok(code) => code,
+            Err(message) => {
+                eprintln!("SyntaxError: {message}");
+                continue;
+            }
+        };
+
+        if let Err(err) = run_code(&code, &mut arena) {
+            if let Some(status) = system_exit_status(&err, &mut arena) {
+                return status;
+            }
+
+            eprintln!("{}", err.format(&mut arena));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn needs_more(buffer: &str) -> bool {
+        let last_line = buffer.strip_suffix('\n').unwrap().rsplit('\n').next().unwrap();  // the REPL adds a newline after every line it reads
+        needs_more_input(buffer, last_line)
+    }
+
+    #[test]
+    fn complete_statements() {
+        assert!(!needs_more("x = 1\n"));
+        assert!(!needs_more("print(\"a:\")\n"));
+        assert!(!needs_more("d = {1: 2}\n"));
+        assert!(!needs_more("if x: y = 1\n"));
+        assert!(!needs_more("s = \"(\"  # [\n"));
+    }
+
+    #[test]
+    fn open_brackets_and_strings() {
+        assert!(needs_more("print(1,\n"));
+        assert!(needs_more("x = [1, (2,\n"));
+        assert!(!needs_more("x = [1, (2,\n3)]\n"));
+        assert!(needs_more("s = \"\"\"doc\n"));
+        assert!(!needs_more("s = \"\"\"doc\nmore\"\"\"\n"));
+        assert!(needs_more("x = 1 + \\\n"));
+    }
+
+    #[test]
+    fn blocks_end_with_an_empty_line() {
+        assert!(needs_more("def f():\n"));
+        assert!(needs_more("while True:  # forever\n"));
+        assert!(needs_more("def f():\n    return 1\n"));
+        assert!(!needs_more("def f():\n    return 1\n\n"));
+    }
+}
@@ -0,0 +1,671 @@
+use std::rc::Rc;
+use crate::builtins::function_utils::{call_function, call_function_1_arg_min, call_next, eval_obj_init, get_next_method};
+use crate::builtins::functions::attributes::{delete_attribute, descriptor_get, is_plain_function, set_attribute, uses_generic_getattribute};
+use crate::builtins::functions::compare::{cached_compare_op, compare_op};
+use crate::builtins::functions::isinstance::is_instance;
+use crate::builtins::functions::math_op::cached_math_op;
+use crate::builtins::structure::magic_methods::PyMagicMethod;
+use crate::builtins::structure::pyclass::PyClass;
+use crate::builtins::structure::pyexception::PyException;
+use crate::builtins::structure::pyobject::{ClassCell, EmptyFuncReturnType, FuncReturnType, PyInternalObject, PyIteratorFlag, PyObject};
+use crate::builtins::types::function::new_function;
+use crate::builtins::types::dict::new_dict;
+use crate::builtins::types::list::new_list;
+use crate::builtins::types::pybool::convert_pyobj_to_bool;
+use crate::builtins::types::pytype::{calculate_metaclass, expect_bases, new_class};
+use crate::builtins::types::range::range_iterator_next;
+use crate::builtins::types::slice::expect_slice_bound;
+use crate::builtins::types::str::py_repr;
+use crate::builtins::types::tuple::{new_tuple, try_tuple_items};
+use crate::builtins::types::weakref::resolve_proxy;
+use crate::compiler::{CodeObject, Instruction};
+use crate::parser::Comparator;
+use crate::inline_cache::operand_kind;
+use crate::pyarena::PyArena;
+use crate::quicken::{as_bool, compare_ordered, float_binary_op, float_operands, int_binary_op, int_operands, quicken_binary_op, quicken_compare_op};
+
+struct HandlerBlock {  // pushed by `SetupExcept`
+    handler: usize,
+    stack_depth: usize,
+}
+
+struct Frame {
+    stack: Vec<PyObject>,
+    fast_locals: Vec<Option<PyObject>>,  // indexed by slot, `None` until the local is bound
+    blocks: Vec<HandlerBlock>,
+    pc: usize,
+    return_value: Option<PyObject>,  // parked here while `return` runs the enclosing `finally` bodies
+    class_cell: Option<ClassCell>,  // only set while a class body runs, handed to the functions that use zero-argument `super()`
+}
+
+impl Frame {
+    fn push(&mut self, value: PyObject) {
+        self.stack.push(value);
+    }
+
+    fn pop(&mut self) -> PyObject {
+        self.stack.pop().expect("value stack underflow")
+    }
+
+    fn pop_n(&mut self, n: usize) -> Vec<PyObject> {
+        self.stack.split_off(self.stack.len() - n)
+    }
+
+    fn top(&self) -> &PyObject {
+        self.stack.last().expect("value stack underflow")
+    }
+}
+
+pub fn run_code(code: &CodeObject, arena: &mut PyArena) -> FuncReturnType {
+    run_frame(code, vec![], None, arena)
+}
+
+fn run_frame(code: &CodeObject, fast_locals: Vec<Option<PyObject>>, class_cell: Option<ClassCell>, arena: &mut PyArena) -> FuncReturnType {
+    let mut frame = Frame { stack: Vec::with_capacity(16), fast_locals, blocks: vec![], pc: 0, return_value: None, class_cell };
+
+    loop {
+        match execute(code, &mut frame, arena) {
+            Ok(value) => return Ok(value),
+            Err(exception) => {
+                let reraised = matches!(code.instructions[frame.pc - 1].get(), Instruction::Reraise | Instruction::RaiseWithContext | Instruction::ReraiseHandled);
+
+                if !(reraised && exception.has_traceback()) {  // a reraise already recorded this frame when the exception first left it
+                    exception.add_traceback(&code.name, code.lines[frame.pc - 1], arena);
+                }
+
+                let Some(block) = frame.blocks.pop() else {
+                    return Err(exception);
+                };
+
+                frame.stack.truncate(block.stack_depth);
+                frame.push(exception.value);
+                frame.pc = block.handler;
+            }
+        }
+    }
+}
+
+pub fn call_code(code: &Rc<CodeObject>, class_cell: Option<&ClassCell>, args: &[PyObject], arena: &mut PyArena) -> FuncReturnType {  // runs the body of a python function
+    let expected = code.parameters.len();
+
+    if args.len() > expected {
+        let message = format!("{}() takes {expected} positional argument{} but {} {} given", code.name, if expected == 1 { "" } else { "s" }, args.len(), if args.len() == 1 { "was" } else { "were" });
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    if args.len() < expected {
+        let missing = &code.parameters[args.len()..];
+        let names = missing.iter().map(|name| format!("'{name}'")).collect::<Vec<_>>().join(", ");
+        let message = format!("{}() missing {} required positional argument{}: {names}", code.name, missing.len(), if missing.len() == 1 { "" } else { "s" });
+        return Err(arena.exceptions.type_error.instantiate(message));
+    }
+
+    let mut fast_locals = vec![None; code.local_names.len()];
+
+    for (slot, arg) in args.iter().enumerate() {  // the parameters own the first slots
+        fast_locals[slot] = Some(arg.clone());
+    }
+
+    if let (Some(slot), Some(class_cell)) = (code.class_slot, class_cell) {  // stays unbound if the class is already gone
+        fast_locals[slot] = class_cell.borrow().upgrade().map(PyObject::new_internal_class);
+    }
+
+    arena.enter_call()?;  // every python call is counted here, operators and attribute hooks reach it without an `Instruction::Call`
+    let result = run_frame(code, fast_locals, None, arena);
+    arena.exit_call();
+
+    result
+}
+
+fn execute(code: &CodeObject, frame: &mut Frame, arena: &mut PyArena) -> FuncReturnType {
+    loop {
+        let instruction = code.instructions[frame.pc].get();
+        frame.pc += 1;
+
+        match instruction {
+            Instruction::LoadConst(index) => frame.push(code.constants[index].clone()),
+            Instruction::LoadName(index) => {
+                let name = &code.names[index];
+                let value = arena.get(name).ok_or_else(|| arena.exceptions.name_error.instantiate(format!("name '{name}' is not defined")))?;
+                frame.push(value);
+            }
+            Instruction::StoreName(index) => {
+                let value = frame.pop();
+                arena.set(code.names[index].clone(), value);
+                arena.run_release_hooks();
+            }
+            Instruction::DeleteName(index) => {
+                arena.remove(&code.names[index]);
+                arena.run_release_hooks();
+            }
+            Instruction::LoadFast(slot) => {
+                let value = frame.fast_locals[slot].clone().ok_or_else(|| {
+                    arena.exceptions.unbound_local_error.instantiate(format!("cannot access local variable '{}' where it is not associated with a value", code.local_names[slot]))
+                })?;
+                frame.push(value);
+            }
+            Instruction::StoreFast(slot) => {
+                frame.fast_locals[slot] = Some(frame.pop());
+                arena.run_release_hooks();
+            }
+            Instruction::DeleteFast(slot) => {
+                frame.fast_locals[slot] = None;
+                arena.run_release_hooks();
+            }
+            Instruction::LoadGlobal(index) => {
+                let cache = &code.global_caches[index];
+                let name = &code.names[cache.name];
+                let value = arena.load_global(cache, name).ok_or_else(|| arena.exceptions.name_error.instantiate(format!("name '{name}' is not defined")))?;
+                frame.push(value);
+            }
+            Instruction::StoreGlobal(index) => {
+                let cache = &code.global_caches[index];
+                let value = frame.pop();
+                arena.store_global(cache, &code.names[cache.name], value);
+                arena.run_release_hooks();
+            }
+            Instruction::LoadAttr(index) => {
+                let obj = frame.pop();
+                frame.push(obj.get_attribute(&code.names[index], arena)?);
+            }
+            Instruction::StoreAttr(index) => {
+                let obj = frame.pop();
+                let value = frame.pop();
+                set_attribute(&obj, &code.names[index], value, arena)?;
+                arena.run_release_hooks();
+            }
+            Instruction::DeleteAttr(index) => {
+                let obj = frame.pop();
+                delete_attribute(&obj, &code.names[index], arena)?;
+                arena.run_release_hooks();
+            }
+            Instruction::LoadSubscript => {
+                let key = frame.pop();
+                let obj = frame.pop();
+                frame.push(get_item(&obj, key, arena)?);
+            }
+            Instruction::StoreSubscript => {
+                let key = frame.pop();
+                let obj = frame.pop();
+                let value = frame.pop();
+                set_item(&obj, key, value, arena)?;
+                arena.run_release_hooks();
+            }
+            Instruction::BuildSlice => {
+                let bounds = frame.pop_n(3);
+                let start = expect_slice_bound(&bounds[0], arena)?;
+                let stop = expect_slice_bound(&bounds[1], arena)?;
+                let step = expect_slice_bound(&bounds[2], arena)?;
+                frame.push(PyObject::new_slice(start, stop, step));
+            }
+            Instruction::IntegerOverflow(digits) => {
+                let message = format!("integer literal too large: {}", code.constants[digits].expect_immutable().expect_string());
+                return Err(arena.exceptions.overflow_error.instantiate(message));
+            }
+            Instruction::BuildList(count) => {
+                let items = frame.pop_n(count);
+                frame.push(new_list(items, arena));
+            }
+            Instruction::BuildTuple(count) => {
+                let items = frame.pop_n(count);
+                frame.push(new_tuple(items, arena));
+            }
+            Instruction::BuildMap(count) => {
+                let mut items = frame.pop_n(2 * count).into_iter();
+                let pairs = std::iter::from_fn(|| Some((items.next()?, items.next()?))).collect();
+                frame.push(new_dict(pairs, arena)?);
+            }
+            Instruction::BinaryOp(op, cache) => {
+                let right = frame.pop();
+                let left = frame.pop();
+                let op_cache = &code.op_caches[cache];
+                let kind = operand_kind(&left, &right);
+
+                if op_cache.warm_up(kind) {
+                    if let Some(quickened) = quicken_binary_op(op, kind, cache) {
+                        code.instructions[frame.pc - 1].set(quickened);
+                    }
+                }
+
+                frame.push(cached_math_op(left, right, op, op_cache, arena)?);
+            }
+            Instruction::UnaryOp(op) => {
+                let obj = frame.pop();
+                frame.push(unary_op(&obj, op, arena)?);
+            }
+            Instruction::Not => {
+                let obj = frame.pop();
+                let boolean = convert_pyobj_to_bool(&obj, arena)?;
+                frame.push(arena.statics.get_bool(!boolean).clone());
+            }
+            Instruction::CompareOp(comp, cache) => {
+                let right = frame.pop();
+                let left = frame.pop();
+                let op_cache = &code.op_caches[cache];
+                let kind = operand_kind(&left, &right);
+
+                if op_cache.warm_up(kind) {
+                    if let Some(quickened) = quicken_compare_op(comp, kind, cache) {
+                        code.instructions[frame.pc - 1].set(quickened);
+                    }
+                }
+
+                frame.push(cached_compare_op(&left, &right, &comp, op_cache, arena)?);
+            }
+            Instruction::Call(arg_count) => {
+                let args = frame.pop_n(arg_count);
+                let func = frame.pop();
+
+                frame.push(call_function(func, &args, arena)?);
+            }
+            Instruction::CallMethod(name, arg_count) => {
+                let args = frame.pop_n(arg_count);
+                let obj = frame.pop();
+                frame.push(call_method(&obj, &code.names[name], &args, arena)?);
+            }
+            Instruction::MakeFunction(index) => {
+                let function_code = code.code_objects[index].clone();
+                let class_cell = function_code.class_slot.and(frame.class_cell.clone());
+                frame.push(new_function(function_code, class_cell, arena.globals.function_class.clone()));
+            }
+            Instruction::BuildClass(index, base_count, has_metaclass) => {
+                let metaclass = if has_metaclass { Some(frame.pop()) } else { None };
+                let bases = frame.pop_n(base_count);
+                frame.push(build_class(&code.code_objects[index], bases, metaclass, arena)?);
+            }
+            Instruction::Import(index) => {
+                let name = &code.names[index];
+                let module = arena.modules.get(name).cloned().ok_or_else(|| {  // only builtin modules can be imported for now
+                    arena.exceptions.module_not_found_error.instantiate(format!("No module named '{name}'"))
+                })?;
+                frame.push(module);
+            }
+            Instruction::PopTop => {
+                frame.pop();
+                arena.run_release_hooks();
+            }
+            Instruction::DupTop => frame.push(frame.top().clone()),
+            Instruction::Jump(target) => {
+                if target < frame.pc {
+                    arena.check_safe_point()?;
+                }
+                frame.pc = target;
+            }
+            Instruction::PopJumpIfFalse(target) => {
+                let condition = frame.pop();
+
+                if as_bool(&condition).is_some() {  // conditions are nearly always comparisons
+                    code.instructions[frame.pc - 1].set(Instruction::PopJumpIfFalseBool(target));
+                }
+
+                if !convert_pyobj_to_bool(&condition, arena)? {
+                    frame.pc = target;
+                }
+            }
+            Instruction::JumpIfFalseOrPop(target) => {
+                if convert_pyobj_to_bool(frame.top(), arena)? {
+                    frame.pop();
+                } else {
+                    frame.pc = target;
+                }
+            }
+            Instruction::JumpIfTrueOrPop(target) => {
+                if convert_pyobj_to_bool(frame.top(), arena)? {
+                    frame.pc = target;
+                } else {
+                    frame.pop();
+                }
+            }
+            Instruction::GetIter => {
+                let iterable = frame.pop();
+                let (iterator, next_func) = get_iterator(&iterable, arena)?;
+                frame.push(iterator);
+                frame.push(next_func);
+            }
+            Instruction::ForIter(target) => {
+                let [iterator, next_func] = &frame.stack[frame.stack.len() - 2..] else { unreachable!() };
+
+                if matches!(iterator, PyObject::Mutable(inner) if Rc::ptr_eq(inner.borrow().get_class(), &arena.globals.range_iterator_class)) {
+                    code.instructions[frame.pc - 1].set(Instruction::ForIterRange(target));
+                    frame.pc -= 1;
+                    continue;
+                }
+
+                match call_next(next_func, iterator, arena)? {
+                    PyObject::IteratorFlag(PyIteratorFlag::StopIteration) => {
+                        frame.pop_n(2);
+                        frame.pc = target;
+                    }
+                    item => frame.push(item),
+                }
+            }
+            Instruction::SetupExcept(handler) => frame.blocks.push(HandlerBlock { handler, stack_depth: frame.stack.len() }),
+            Instruction::PopBlock => {
+                frame.blocks.pop();
+            }
+            Instruction::MatchException => {
+                let class_info = frame.pop();
+                let classes = expect_exception_classes(&class_info, arena)?;
+                let exception = PyException::new(frame.top().clone());
+
+                let matches = classes.iter().any(|pyclass| exception.is_instance_of(pyclass));
+                frame.push(arena.statics.get_bool(matches).clone());
+            }
+            Instruction::BeginHandler => {
+                let exception = PyException::new(frame.top().clone());
+
+                if let Some(handled) = arena.exception_stack.last() {  // it was raised while an outer handler was running
+                    exception.chain_context(handled);
+                }
+
+                arena.exception_stack.push(exception);
+            }
+            Instruction::EndHandler => {
+                arena.exception_stack.pop();
+                frame.pop();
+            }
+            Instruction::PopExcept => {
+                arena.exception_stack.pop();
+            }
+            Instruction::RaiseWithContext => {
+                let exception = PyException::new(frame.pop());
+                let pending = PyException::new(frame.pop());
+
+                exception.chain_context(&pending);
+                return Err(exception);
+            }
+            Instruction::Reraise => return Err(PyException::new(frame.pop())),
+            Instruction::Raise(has_cause) => {
+                let cause = has_cause.then(|| frame.pop());
+                let value = frame.pop();
+                return Err(raise(value, cause, arena)?);
+            }
+            Instruction::ReraiseHandled => {
+                return match arena.exception_stack.last() {
+                    Some(exception) => Err(exception.clone()),
+                    None => Err(arena.exceptions.runtime_error.instantiate("No active exception to reraise".to_string())),
+                };
+            }
+            Instruction::Assert => {
+                let value = frame.pop();
+
+                if !convert_pyobj_to_bool(&value, arena)? {
+                    return Err(arena.exceptions.assertion_error.empty());
+                }
+            }
+            Instruction::AssertEqual => {
+                let expected = frame.pop();
+                let value = frame.pop();
+                assert_equal(&value, &expected, arena)?;
+            }
+            Instruction::PrintExpr => {
+                let value = frame.pop();
+
+                if !value.is_none() {
+                    let repr = py_repr(&value, arena)?.expect_immutable().expect_string();
+                    println!("{repr}");
+
+                    arena.set("_".to_string(), value);
+                }
+            }
+            Instruction::BinaryOpInt(op, cache) => {
+                let result = int_operands(&frame.stack[frame.stack.len() - 2], frame.top()).map(|(left, right)| int_binary_op(op, left, right));
+
+                match result {
+                    Some(Some(value)) => {
+                        frame.stack.truncate(frame.stack.len() - 2);
+                        frame.push(PyObject::new_int(value));
+                    }
+                    Some(None) => {  // overflow, the generic path raises the error
+                        let right = frame.pop();
+                        let left = frame.pop();
+                        frame.push(cached_math_op(left, right, op, &code.op_caches[cache], arena)?);
+                    }
+                    None => despecialize(code, frame, Instruction::BinaryOp(op, cache), cache),
+                }
+            }
+            Instruction::BinaryOpFloat(op, cache) => {
+                let result = float_operands(&frame.stack[frame.stack.len() - 2], frame.top()).map(|(left, right)| float_binary_op(op, left, right));
+
+                match result {
+                    Some(Some(value)) => {
+                        frame.stack.truncate(frame.stack.len() - 2);
+                        frame.push(PyObject::new_float(value));
+                    }
+                    Some(None) => {
+                        let right = frame.pop();
+                        let left = frame.pop();
+                        frame.push(cached_math_op(left, right, op, &code.op_caches[cache], arena)?);
+                    }
+                    None => despecialize(code, frame, Instruction::BinaryOp(op, cache), cache),
+                }
+            }
+            Instruction::CompareOpInt(comp, cache) => {
+                match int_operands(&frame.stack[frame.stack.len() - 2], frame.top()).and_then(|(left, right)| compare_ordered(comp, left, right)) {
+                    Some(result) => {
+                        frame.stack.truncate(frame.stack.len() - 2);
+                        frame.push(arena.statics.get_bool(result).clone());
+                    }
+                    None => despecialize(code, frame, Instruction::CompareOp(comp, cache), cache),
+                }
+            }
+            Instruction::CompareOpFloat(comp, cache) => {
+                match float_operands(&frame.stack[frame.stack.len() - 2], frame.top()).and_then(|(left, right)| compare_ordered(comp, left, right)) {
+                    Some(result) => {
+                        frame.stack.truncate(frame.stack.len() - 2);
+                        frame.push(arena.statics.get_bool(result).clone());
+                    }
+                    None => despecialize(code, frame, Instruction::CompareOp(comp, cache), cache),
+                }
+            }
+            Instruction::ForIterRange(target) => {
+                match range_iterator_next(&frame.stack[frame.stack.len() - 2]) {
+                    Some(Some(value)) => frame.push(PyObject::new_int(value)),
+                    Some(None) => {
+                        frame.pop_n(2);
+                        frame.pc = target;
+                    }
+                    None => {  // another iterator reached the same loop
+                        code.instructions[frame.pc - 1].set(Instruction::ForIter(target));
+                        frame.pc -= 1;
+                    }
+                }
+            }
+            Instruction::PopJumpIfFalseBool(target) => {
+                match as_bool(frame.top()) {
+                    Some(condition) => {
+                        frame.pop();
+
+                        if !condition {
+                            frame.pc = target;
+                        }
+                    }
+                    None => {
+                        code.instructions[frame.pc - 1].set(Instruction::PopJumpIfFalse(target));
+                        frame.pc -= 1;
+                    }
+                }
+            }
+            Instruction::SetReturnValue => frame.return_value = Some(frame.pop()),
+            Instruction::LoadReturnValue => {
+                let value = frame.return_value.take().expect("no return value was set");
+                frame.push(value);
+            }
+            Instruction::Return => return Ok(frame.pop()),
+        }
+    }
+}
+
+fn despecialize(code: &CodeObject, frame: &mut Frame, generic: Instruction, cache: usize) {  // puts the generic instruction back and runs it again
+    code.op_caches[cache].cool_down();
+    code.instructions[frame.pc - 1].set(generic);
+    frame.pc -= 1;
+}
+
+fn call_method(obj: &PyObject, name: &str, args: &[PyObject], arena: &mut PyArena) -> FuncReturnType {
+    if let Some(referent) = resolve_proxy(obj, arena)? {
+        return call_method(&referent, name, args, arena);
+    }
+
+    let method = if uses_generic_getattribute(obj, arena) { obj.get_method(name, arena) } else { None };  // `__getattribute__` has to see every lookup
+
+    if let Some(method) = method.filter(|method| is_plain_function(method) && !has_own_field(obj, name, arena)) {  // skips creating a bound method
+        return call_function_1_arg_min(&method, obj, args, arena);
+    }
+
+    let func = obj.get_attribute(name, arena)?;
+    call_function(func, args, arena)
+}
+
+fn has_own_field(obj: &PyObject, name: &str, arena: &mut PyArena) -> bool {  // fields of the instance shadow methods of its class
+    match obj {
+        PyObject::Mutable(inner) => inner.borrow().get_field(name, arena).is_some(),
+        _ => false,
+    }
+}
+
+fn unary_op(obj: &PyObject, py_magic_method: PyMagicMethod, arena: &mut PyArena) -> FuncReturnType {
+    let unary_func = obj.get_magic_method(&py_magic_method, arena).ok_or_else(|| {
+        let message = format!("bad operand type for unary {}: '{}'", py_magic_method.as_operator(), obj.clone_class(arena).get_name());
+        arena.exceptions.type_error.instantiate(message)
+    })?;
+
+    call_function_1_arg_min(&unary_func, obj, &[], arena)
+}
+
+fn get_item(obj: &PyObject, key: PyObject, arena: &mut PyArena) -> FuncReturnType {
+    if let PyObject::Internal(PyInternalObject::InternalClass(pyclass)) = obj {
+        if obj.get_magic_method(&PyMagicMethod::GetItem, arena).is_none() {  // a metaclass's `__getitem__` wins over `__class_getitem__`
+            return class_get_item(pyclass, key, arena);
+        }
+    }
+
+    let getitem_func = obj.get_magic_method(&PyMagicMethod::GetItem, arena).ok_or_else(|| {
+        let message = format!("'{}' object is not subscriptable", obj.clone_class(arena).get_name());
+        arena.exceptions.type_error.instantiate(message)
+    })?;
+
+    call_function_1_arg_min(&getitem_func, obj, &[key], arena)
+}
+
+fn class_get_item(pyclass: &Rc<PyClass>, key: PyObject, arena: &mut PyArena) -> FuncReturnType {
+    let Some(class_getitem_func) = pyclass.search_for_attribute("__class_getitem__") else {
+        let message = format!("type '{}' is not subscriptable", pyclass.get_name());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let class_getitem_func = descriptor_get(class_getitem_func, None, pyclass, arena)?;
+    call_function(class_getitem_func, &[key], arena)
+}
+
+fn set_item(obj: &PyObject, key: PyObject, value: PyObject, arena: &mut PyArena) -> EmptyFuncReturnType {
+    let setitem_func = obj.get_magic_method(&PyMagicMethod::SetItem, arena).ok_or_else(|| {
+        let message = format!("'{}' object does not support item assignment", obj.clone_class(arena).get_name());
+        arena.exceptions.type_error.instantiate(message)
+    })?;
+
+    call_function_1_arg_min(&setitem_func, obj, &[key, value], arena)?;
+
+    Ok(())
+}
+
+fn get_iterator(iterable: &PyObject, arena: &mut PyArena) -> Result<(PyObject, PyObject), PyException> {  // the iterator and its `__next__`
+    let Some(iter_func) = iterable.get_magic_method(&PyMagicMethod::Iter, arena) else {
+        let message = format!("'{}' object is not iterable", iterable.clone_class(arena).get_name());
+        return Err(arena.exceptions.type_error.instantiate(message));
+    };
+
+    let iterator = call_function_1_arg_min(&iter_func, iterable, &[], arena)?;
+    let next_func = get_next_method(&iterator, arena)?;
+
+    Ok((iterator, next_func))
+}
+
+fn build_class(body: &CodeObject, bases: Vec<PyObject>, metaclass: Option<PyObject>, arena: &mut PyArena) -> FuncReturnType {
+    let super_classes = expect_bases(bases, arena)?;
+
+    let metaclass = match metaclass {
+        Some(PyObject::Internal(PyInternalObject::InternalClass(metaclass))) => PyObject::new_internal_class(calculate_metaclass(metaclass, &super_classes, arena)?),
+        Some(metaclass) => metaclass,  // any other callable is called as is
+        None => PyObject::new_internal_class(calculate_metaclass(arena.globals.type_class.clone(), &super_classes, arena)?),
+    };
+
+    let class_cell = ClassCell::default();
+
+    arena.push_scope();
+    let body_result = run_frame(body, vec![], Some(class_cell.clone()), arena);
+    let attributes = arena.pop_scope();
+    body_result?;
+
+    if let PyObject::Internal(PyInternalObject::InternalClass(ref metaclass)) = metaclass {
+        if Rc::ptr_eq(metaclass, &arena.globals.type_class) {  // skips building the namespace for the common case
+            return new_class(body.name.clone(), super_classes, attributes, metaclass.clone(), Some(class_cell), arena);
+        }
+    }
+
+    let namespace = new_dict(attributes.into_iter().map(|(name, value)| (PyObject::new_string(name), value)).collect(), arena)?;  // in definition order
+
+    let bases = new_tuple(super_classes.into_iter().map(PyObject::new_internal_class).collect(), arena);
+    let pyclass = call_function(metaclass, &[PyObject::new_string(body.name.clone()), bases, namespace], arena)?;
+
+    if let PyObject::Internal(PyInternalObject::InternalClass(ref pyclass)) = pyclass {
+        *class_cell.borrow_mut() = Rc::downgrade(pyclass);
+    }
+
+    Ok(pyclass)
+}
+
+fn assert_equal(value: &PyObject, expected: &PyObject, arena: &mut PyArena) -> EmptyFuncReturnType {  // `assert a, b` checks `a == b` in this interpreter
+    let is_equal = compare_op(value, expected, &Comparator::Equal, arena)?;
+
+    if !convert_pyobj_to_bool(&is_equal, arena)? {
+        let msg = py_repr(expected, arena)?.expect_immutable().expect_string();
+        return Err(arena.exceptions.assertion_error.instantiate(msg));
+    }
+
+    Ok(())
+}
+
+fn expect_exception_value(value: PyObject, arena: &mut PyArena) -> FuncReturnType {
+    let base_exception = arena.exceptions.base_exception.clone();
+
+    match value {
+        PyObject::Internal(PyInternalObject::InternalClass(ref pyclass)) if pyclass.is_subclass_of(&base_exception) => eval_obj_init(pyclass.clone(), &[], arena),
+        ref instance if is_instance(instance, &base_exception, arena) => Ok(value),
+        _ => Err(arena.exceptions.type_error.instantiate("exceptions must derive from BaseException".to_string())),
+    }
+}
+
+fn raise(value: PyObject, cause: Option<PyObject>, arena: &mut PyArena) -> Result<PyException, PyException> {  // builds the exception for `raise value from cause`
+    let exception = PyException::new(expect_exception_value(value, arena)?);
+
+    if let Some(cause) = cause {
+        let cause = match cause {
+            none if none.is_none() => None,
+            cause => match expect_exception_value(cause, arena) {
+                Ok(cause) => Some(cause),
+                Err(_) => return Err(arena.exceptions.type_error.instantiate("exception causes must derive from BaseException".to_string())),
+            },
+        };
+
+        exception.set_cause(cause);
+    }
+
+    Ok(exception)
+}
+
+fn expect_exception_classes(class_info: &PyObject, arena: &mut PyArena) -> Result<Vec<Rc<PyClass>>, PyException> {
+    let base_exception = arena.exceptions.base_exception.clone();
+    let items = try_tuple_items(class_info).unwrap_or_else(|| vec![class_info.clone()]);
+
+    let mut classes = Vec::with_capacity(items.len());
+
+    for item in items {
+        match item {
+            PyObject::Internal(PyInternalObject::InternalClass(pyclass)) if pyclass.is_subclass_of(&base_exception) => classes.push(pyclass),
+            _ => return Err(arena.exceptions.type_error.instantiate("catching classes that do not inherit from BaseException is not allowed".to_string())),
+        }
+    }
+
+    Ok(classes)
+}
@@ -0,0 +1,171 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{self, Command, Output, Stdio};
+
+const PRINT_ARGV: &str = "import sys\nprint(sys.argv)";
+
+fn run(args: &[&str]) -> Output {
+    run_with_stdin(args, "")
+}
+
+fn run_with_stdin(args: &[&str], stdin: &str) -> Output {
+    run_in(args, stdin, Path::new("."))
+}
+
+fn run_in(args: &[&str], stdin: &str, directory: &Path) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_RustyPython"))
+        .args(args)
+        .current_dir(directory)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start the interpreter");
+
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();  // dropped right away so `-` sees the end of input
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn command_argv() {
+    let output = run(&["-c", PRINT_ARGV, "a", "-c", "b"]);
+
+    assert_eq!(stdout(&output), "['-c', 'a', '-c', 'b']\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn command_attached_to_flag() {
+    let output = run(&["-dc", "print(1 + 2)"]);
+
+    assert!(stdout(&output).ends_with("3\n"));  // the parse tree comes first
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn stdin_argv() {
+    let output = run_with_stdin(&["-", "x", "y"], PRINT_ARGV);
+
+    assert_eq!(stdout(&output), "['-', 'x', 'y']\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn piped_stdin_without_arguments() {
+    let output = run_with_stdin(&[], PRINT_ARGV);
+
+    assert_eq!(stdout(&output), "['']\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn module_argv() {
+    let directory = std::env::temp_dir().join(format!("rustypython-cli-{}", process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("argv_probe.py"), PRINT_ARGV).unwrap();
+
+    let output = run_in(&["-m", "argv_probe", "z"], "", &directory);
+    let module_path = fs::canonicalize(directory.join("argv_probe.py")).unwrap();
+
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(stdout(&output), format!("['{}', 'z']\n", module_path.display()));
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn script_argv() {
+    let script = std::env::temp_dir().join(format!("rustypython-argv-{}.py", process::id()));
+    fs::write(&script, PRINT_ARGV).unwrap();
+
+    let output = run(&[script.to_str().unwrap(), "arg1"]);
+
+    fs::remove_file(&script).unwrap();
+
+    assert_eq!(stdout(&output), format!("['{}', 'arg1']\n", script.display()));  // passed through as given, unlike `-m`
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn exit_codes() {
+    assert_eq!(run(&["-c", "import sys\nsys.exit(3)"]).status.code(), Some(3));
+    assert_eq!(run(&["-c", "import sys\nsys.exit()"]).status.code(), Some(0));
+    assert_eq!(run(&["-c", "raise ValueError"]).status.code(), Some(1));
+
+    let output = run(&["-c", "import sys\nsys.exit(\"bad input\")"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "bad input\n");
+}
+
+#[test]
+fn main_module_name() {
+    let program = "if __name__ == \"__main__\":\n    print(__name__)";
+
+    assert_eq!(stdout(&run(&["-c", program])), "__main__\n");
+    assert_eq!(stdout(&run_with_stdin(&["-"], program)), "__main__\n");
+}
+
+#[test]
+fn uncaught_traceback() {
+    let output = run(&["-c", "def f():\n    return 1 / 0\n\nf()"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Traceback (most recent call last):\n  File \"<string>\", line 4, in <module>\n  File \"<string>\", line 2, in f\nZeroDivisionError: division by zero\n");
+}
+
+#[test]
+fn release_hooks_run_at_exit() {
+    let program = "import weakref\n\nclass Target:\n    pass\n\ndef report(ref):\n    print(\"freed\")\n\nrefs = []\n\ndef fail():\n    target = Target()\n    refs.append(weakref.ref(target, report))\n    raise ValueError\n\nfail()";
+    let output = run(&["-c", program]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "freed\n");
+}
+
+#[test]
+fn missing_sources() {
+    let output = run(&["no_such_script.py"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("can't open file 'no_such_script.py'"));
+
+    let output = run(&["-m", "no_such_module"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("No module named no_such_module"));
+}
+
+#[test]
+fn usage_errors() {
+    for args in [&["-c"][..], &["-m"], &["-z"], &["--bogus"]] {
+        let output = run(args);
+
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(stderr(&output).contains("usage: RustyPython"), "{args:?}");
+        assert!(stdout(&output).is_empty(), "{args:?}");
+    }
+}
+
+#[test]
+fn informational_flags() {
+    for args in [&["-h"][..], &["-?"], &["--help"]] {
+        let output = run(args);
+
+        assert_eq!(output.status.code(), Some(0), "{args:?}");
+        assert!(stdout(&output).starts_with("usage: RustyPython"), "{args:?}");
+    }
+
+    for args in [&["-V"][..], &["--version"]] {
+        let output = run(args);
+
+        assert_eq!(output.status.code(), Some(0), "{args:?}");
+        assert_eq!(stdout(&output), format!("RustyPython {}\n", env!("CARGO_PKG_VERSION")), "{args:?}");
+    }
+}
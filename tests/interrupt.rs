@@ -0,0 +1,68 @@
+#![cfg(unix)]  // sends the signal with `kill`
+
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+
+const CAUGHT: &str = r#"order = []
+try:
+    try:
+        print("ready")
+        while True:
+            pass
+    except KeyboardInterrupt:
+        order.append("except")
+    finally:
+        order.append("finally")
+except BaseException:
+    order.append("outer")
+print(order)
+"#;
+
+const UNCAUGHT: &str = r#"try:
+    print("ready")
+    while True:
+        pass
+finally:
+    print("finally")
+"#;
+
+fn interrupt_when_ready(code: &str) -> (Child, String) {  // waits for `ready` so the handler is installed before the signal
+    let mut child = Command::new(env!("CARGO_BIN_EXE_RustyPython"))
+        .args(["-c", code])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start the interpreter");
+
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    assert_eq!(line, "ready\n");
+
+    let status = Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap();
+    assert!(status.success());
+
+    let mut rest = String::new();
+    stdout.read_to_string(&mut rest).unwrap();
+
+    (child, rest)
+}
+
+#[test]
+fn keyboard_interrupt_is_catchable() {
+    let (child, stdout) = interrupt_when_ready(CAUGHT);
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(stdout, "['except', 'finally']\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn uncaught_keyboard_interrupt_exits_with_130() {
+    let (child, stdout) = interrupt_when_ready(UNCAUGHT);
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(stdout, "finally\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("KeyboardInterrupt"));
+    assert_eq!(output.status.code(), Some(130));
+}
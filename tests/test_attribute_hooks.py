@@ -0,0 +1,150 @@
+class Fallback:
+    real = 1
+
+    def __getattr__(self, name):
+        if name == "missing":
+            raise AttributeError("still missing")
+        return "generated " + name
+
+f = Fallback()
+assert f.real, 1
+assert f.color, "generated color"
+f.color = "red"
+assert f.color, "red"
+try:
+    f.missing
+except AttributeError as e:
+    assert str(e), "still missing"
+
+class Looping:
+    def __getattr__(self, name):
+        return self.missing
+
+try:
+    Looping().anything
+except RecursionError as e:
+    assert str(e), "maximum recursion depth exceeded"
+
+class Logged:
+    def __getattribute__(self, name):
+        if name == "secret":
+            return "hidden"
+        return object.__getattribute__(self, name)
+
+    def greet(self):
+        return "hello"
+
+l = Logged()
+l.value = 3
+assert l.value, 3
+assert l.secret, "hidden"
+assert l.greet(), "hello"
+try:
+    l.nothing
+except AttributeError as e:
+    assert str(e), "'Logged' object has no attribute 'nothing'"
+
+class Frozen:
+    def __setattr__(self, name, value):
+        if name == "locked":
+            raise AttributeError("locked is read-only")
+        object.__setattr__(self, name, value * 2)
+
+    def __delattr__(self, name):
+        object.__delattr__(self, name)
+        object.__setattr__(self, "deleted", name)
+
+fr = Frozen()
+fr.x = 4
+assert fr.x, 8
+try:
+    fr.locked = 1
+except AttributeError as e:
+    assert str(e), "locked is read-only"
+del fr.x
+assert fr.deleted, "x"
+assert hasattr(fr, "x"), False
+
+class Plain:
+    pass
+
+p = Plain()
+setattr(p, "a", 5)
+assert getattr(p, "a"), 5
+assert getattr(p, "b", "default"), "default"
+assert hasattr(p, "a"), True
+del p.a
+assert hasattr(p, "a"), False
+try:
+    del p.a
+except AttributeError as e:
+    assert str(e), "'Plain' object has no attribute 'a'"
+try:
+    getattr(p, 1)
+except TypeError as e:
+    assert str(e), "attribute name must be string, not 'int'"
+
+x = 10
+del x
+try:
+    x
+except NameError as e:
+    assert str(e), "name 'x' is not defined"
+
+class Point:
+    __slots__ = ["x", "y"]
+
+pt = Point()
+pt.x = 1
+pt.y = 2
+assert pt.x + pt.y, 3
+try:
+    pt.z = 3
+except AttributeError as e:
+    assert str(e), "'Point' object has no attribute 'z'"
+del pt.x
+try:
+    pt.x
+except AttributeError as e:
+    assert str(e), "'Point' object has no attribute 'x'"
+assert str(Point.x), "<member 'x' of 'Point' objects>"
+
+class Point3D(Point):
+    __slots__ = "z"
+
+p3 = Point3D()
+p3.x = 1
+p3.z = 3
+assert p3.x + p3.z, 4
+
+class Open(Point):
+    pass
+
+o = Open()
+o.y = 2
+o.anything = 5
+assert o.y + o.anything, 7
+
+class WithDict:
+    __slots__ = ("a", "__dict__")
+
+w = WithDict()
+w.a = 1
+w.b = 2
+assert w.a + w.b, 3
+
+try:
+    class Clash:
+        __slots__ = ["v"]
+        v = 1
+except ValueError as e:
+    assert str(e), "'v' in __slots__ conflicts with class variable"
+
+class Other:
+    __slots__ = ["q"]
+
+try:
+    class Both(Point, Other):
+        pass
+except TypeError as e:
+    assert str(e), "multiple bases have instance lay-out conflict"
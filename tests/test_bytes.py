@@ -0,0 +1,61 @@
+a = b"hello world"
+b = bytearray(b"abc")
+
+print(a, b)
+print(a[0], a[-1], a[1:4], a[::-1])
+print(len(a), len(b))
+
+assert a[0] == 104
+assert a[-1] == 100
+assert a[1:4] == b"ell"
+assert a[::2] == b"hlowrd"
+assert a[100:] == b""
+assert b"\x00\xff\n" == bytes([0, 255, 10])
+assert bytes(3) == b"\x00\x00\x00"
+assert bytes("hé", "utf-8") == b"h\xc3\xa9"
+assert "hé".encode("latin-1") == b"h\xe9"
+assert b"h\xc3\xa9".decode() == "hé"
+assert b"h\xff".decode("utf-8", "replace") == "h�"
+assert b"ab" + b"cd" == b"abcd"
+assert b"ab" != b"abc"
+assert b"ab" == bytearray(b"ab")
+assert not b""
+assert b"ab" * 3 == b"ababab"
+assert 2 * b"x" == b"xx"
+assert b"a" * -1 == b""
+assert bytearray(b"ab") * 2 == bytearray(b"abab")
+assert b"abc" < b"abd"
+assert b"ab" < b"abc"
+assert b"b" > b"a"
+assert b"a" <= b"a"
+assert not b"b" <= b"a"
+assert b"a" >= bytearray(b"a")
+
+assert a.hex() == "68656c6c6f20776f726c64"
+assert b"\x01\x02\x03".hex(":") == "01:02:03"
+assert bytes.fromhex("de ad be ef") == b"\xde\xad\xbe\xef"
+assert a.split() == [b"hello", b"world"]
+assert b"a,b,,c".split(b",") == [b"a", b"b", b"", b"c"]
+assert b"-".join([b"a", b"b", b"c"]) == b"a-b-c"
+assert a.find(b"world") == 6
+assert a.find(b"xyz") == -1
+
+b[0] = 120
+b.append(100)
+b.extend(b"ef")
+b[1:3] = b"YZ"
+assert b == bytearray(b"xYZdef")
+assert b[0:2] == bytearray(b"xY")
+
+total = 0
+for byte in b"abc":
+    total += byte
+assert total == 294
+
+assert list(b"hi") == [104, 105]
+assert slice(1, 5).stop == 5
+
+try:
+    b"a" < "a"
+except TypeError as e:
+    assert str(e), "'<' not supported between instances of 'bytes' and 'str'"
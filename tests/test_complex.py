@@ -0,0 +1,39 @@
+a = 1+2j
+b = complex(3, -4)
+
+print(a, b)
+print(a + b, a - b, a * b, a / b)
+print(a.real, a.imag, a.conjugate())
+print(abs(b))
+
+assert a + 1 == complex(2, 2)
+assert 1 + a == complex(2, 2)
+assert 2.5 * a == complex(2.5, 5)
+assert a ** 2 == -3+4j
+assert a == complex("1+2j")
+assert complex("-1.5e1-2j") == complex(-15, -2)
+assert complex(" (1.5j) ") == 1.5j
+assert complex("-j") == -1j
+assert complex(1, 2j) == -1+0j
+assert 1j ** 2 == -1
+assert not complex(0)
+assert (3+0j) == 3
+assert 2 != 2+1j
+
+class BadComplex:
+    def __complex__(self):
+        return "1+2j"
+
+class BadBool:
+    def __bool__(self):
+        return 1
+
+try:
+    complex(BadComplex())
+except TypeError as e:
+    assert str(e), "BadComplex.__complex__ returned non-complex (type str)"
+
+try:
+    bool(BadBool())
+except TypeError as e:
+    assert str(e), "BadBool.__bool__ should return bool, returned int"
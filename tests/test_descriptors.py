@@ -0,0 +1,186 @@
+class Celsius:
+    def get_degrees(self):
+        return self.stored
+
+    def set_degrees(self, value):
+        self.stored = value * 1.0
+
+    degrees = property(get_degrees, set_degrees)
+
+t = Celsius()
+t.degrees = 20
+assert t.degrees, 20.0
+assert t.stored, 20.0
+assert isinstance(Celsius.degrees, property), True
+
+class Circle:
+    def set_radius(self, value):
+        self.r = value
+
+    @property
+    def radius(self):
+        return self.r
+
+    @radius.setter
+    def radius(self, value):
+        if value < 0:
+            raise ValueError("negative radius")
+        self.r = value
+
+    @radius.deleter
+    def radius(self):
+        self.r = 0
+
+    @property
+    def area(self):
+        return 3 * self.r * self.r
+
+c = Circle()
+c.radius = 2
+assert c.radius, 2
+assert c.area, 12
+try:
+    c.radius = -1
+except ValueError as e:
+    assert str(e), "negative radius"
+delattr(c, "radius")
+assert c.r, 0
+
+try:
+    c.area = 5
+except AttributeError as e:
+    assert str(e), "property 'area' of 'Circle' object has no setter"
+
+class Counter:
+    created = 0
+
+    @classmethod
+    def make(cls):
+        cls.created = cls.created + 1
+        return cls()
+
+    @staticmethod
+    def double(x):
+        return x * 2
+
+    def total(self):
+        return self.created
+
+first = Counter.make()
+second = first.make()
+assert Counter.created, 2
+assert isinstance(second, Counter), True
+assert Counter.double(4), 8
+assert first.double(5), 10
+assert first.total(), 2
+
+bound = first.total
+assert bound(), 2
+assert bound.__self__ is first, True
+assert Counter.total(first), 2
+
+class Typed:  # a data descriptor wins over the instance's own fields
+    def __set_name__(self, owner, name):
+        self.name = name
+
+    def __get__(self, instance, owner):
+        if instance is None:
+            return self
+        return instance.slots[0]
+
+    def __set__(self, instance, value):
+        if not isinstance(value, int):
+            raise TypeError(self.name + " must be an int")
+        instance.slots[0] = value
+
+    def __delete__(self, instance):
+        instance.slots[0] = None
+
+class Point:
+    x = Typed()
+
+p = Point()
+p.slots = [0]
+p.x = 3
+assert p.x, 3
+assert p.slots, [3]
+assert Point.x.name, "x"
+try:
+    p.x = "three"
+except TypeError as e:
+    assert str(e), "x must be an int"
+delattr(p, "x")
+assert p.slots, [None]
+
+named = []
+
+class Field:
+    def __set_name__(self, owner, name):
+        named.append(name)
+
+class Record:  # `__set_name__` runs in definition order, not alphabetically
+    zeta = Field()
+    alpha = Field()
+    mid = Field()
+    zeta = Field()  # rebinding keeps the first position
+
+assert named, ["zeta", "alpha", "mid"]
+
+named = []
+type("Built", (), {"second": Field(), "first": Field()})
+assert named, ["second", "first"]
+
+class Default:  # a descriptor without __set__ is shadowed by the instance's own fields
+    def __get__(self, instance, owner):
+        return "default"
+
+class Config:
+    mode = Default()
+
+    def describe(self):
+        return "method"
+
+cfg = Config()
+assert cfg.mode, "default"
+cfg.mode = "custom"
+assert cfg.mode, "custom"
+assert Config.mode, "default"
+
+def replacement():
+    return "instance"
+
+cfg.describe = replacement
+assert cfg.describe(), "instance"
+delattr(cfg, "describe")
+assert cfg.describe(), "method"
+
+def tag(cls):
+    cls.tagged = True
+    return cls
+
+@tag
+class Tagged:
+    pass
+
+assert Tagged.tagged, True
+
+registry = []
+
+def register(func):
+    registry.append(func)
+    return func
+
+def label(func):
+    registry.append("label")
+    return func
+
+@register
+@label
+def inc(x):
+    return x + 1
+
+assert inc(0), 1
+assert registry[0], "label"  # the decorator closest to the function runs first
+assert registry[1] is inc, True
+
+print("descriptors ok")
@@ -0,0 +1,51 @@
+empty = {}
+assert len(empty), 0
+assert str(empty), "{}"
+
+d = {"a": 1, "b": 2,}
+assert d["a"], 1
+assert len(d), 2
+d["c"] = 3
+d["a"] = 10
+assert d["a"], 10
+assert str(d), "{'a': 10, 'b': 2, 'c': 3}"
+assert "b" in d, True
+assert "z" not in d, True
+assert d.get("z"), None
+assert d.get("z", 0), 0
+assert d.keys(), ["a", "b", "c"]
+assert d.values(), [10, 2, 3]
+assert d.items()[0], ("a", 10)
+assert d.pop("b"), 2
+assert d.pop("b", "gone"), "gone"
+assert str(d), "{'a': 10, 'c': 3}"
+
+total = 0
+for key in {1: "one", 2: "two"}:
+    total += key
+assert total, 3
+
+assert {1: 1, 2: 2} == {2: 2, 1: 1}, True
+assert {1: 1} != {1: 2}, True
+assert {1: "int"}[1.0], "int"
+assert {(1, 2): "pair"}[(1, 2)], "pair"
+assert dict([("x", 1), ("y", 2)]), {"x": 1, "y": 2}
+assert dict(d) == d, True
+assert dict(d) is d, False
+
+try:
+    d["missing"]
+except KeyError as e:
+    assert str(e), "'missing'"
+
+try:
+    {[1]: 1}
+except TypeError as e:
+    assert str(e), "unhashable type: 'list'"
+
+try:
+    hash({})
+except TypeError as e:
+    assert str(e), "unhashable type: 'dict'"
+
+print("dict ok", d)
@@ -0,0 +1,80 @@
+try:
+    try:
+        1 / 0
+    except ZeroDivisionError:
+        raise ValueError("wrapped")
+except ValueError as e:
+    assert isinstance(e.__context__, ZeroDivisionError)
+    assert e.__cause__ is None
+    assert not e.__suppress_context__
+
+try:
+    try:
+        raise KeyError("key")
+    except KeyError as inner:
+        raise RuntimeError("lookup failed") from inner
+except RuntimeError as e:
+    assert isinstance(e.__cause__, KeyError)
+    assert isinstance(e.__context__, KeyError)
+    assert e.__suppress_context__
+
+try:
+    try:
+        raise KeyError("key")
+    except KeyError:
+        raise RuntimeError("hidden") from None
+except RuntimeError as e:
+    assert e.__cause__ is None
+    assert isinstance(e.__context__, KeyError)
+    assert e.__suppress_context__
+
+try:
+    raise ValueError from IndexError
+except ValueError as e:
+    assert isinstance(e.__cause__, IndexError)
+
+try:
+    try:
+        raise TypeError("first")
+    finally:
+        raise IndexError("from finally")
+except IndexError as e:
+    assert str(e.__context__), "first"
+
+try:
+    try:
+        raise TypeError("outer")
+    except TypeError:
+        try:
+            raise IndexError("middle")
+        except IndexError:
+            raise ValueError("inner")
+except ValueError as e:
+    assert str(e.__context__), "middle"
+    assert str(e.__context__.__context__), "outer"
+
+try:
+    try:
+        raise TypeError("same")
+    except TypeError:
+        raise
+except TypeError as e:
+    assert e.__context__ is None
+
+e = ValueError()
+e.__cause__ = KeyError()
+assert e.__suppress_context__
+e.__suppress_context__ = False
+assert not e.__suppress_context__
+
+try:
+    e.__cause__ = 5
+except TypeError as err:
+    assert str(err), "exception cause must be None or derive from BaseException"
+
+try:
+    raise ValueError from 5
+except TypeError as err:
+    assert str(err), "exception causes must derive from BaseException"
+
+print("chaining ok")
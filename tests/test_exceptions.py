@@ -0,0 +1,141 @@
+caught = []
+try:
+    1 / 0
+except ZeroDivisionError as e:
+    caught.append("zero")
+    assert e.args, ("division by zero",)
+assert caught, ["zero"]
+
+try:
+    raise ValueError("bad", 2)
+except (TypeError, ValueError) as e:
+    assert e.args, ("bad", 2)
+    assert str(e), "('bad', 2)"
+
+try:
+    raise KeyError
+except LookupError as e:
+    assert isinstance(e, KeyError)
+    assert e.args, ()
+
+class AppError(Exception):
+    pass
+
+class ConfigError(AppError):
+    code = 3
+
+try:
+    raise ConfigError("missing key")
+except AppError as e:
+    assert isinstance(e, ConfigError)
+    assert isinstance(e, Exception)
+    assert not isinstance(e, ValueError)
+    assert e.code, 3
+    assert str(e), "missing key"
+    e.detail = "extra"
+    assert e.detail, "extra"
+
+assert issubclass(ConfigError, (ValueError, AppError))
+assert issubclass(ZeroDivisionError, ArithmeticError)
+assert not issubclass(ArithmeticError, ZeroDivisionError)
+assert isinstance(1, (str, int))
+
+order = []
+try:
+    order.append("try")
+except Exception:
+    order.append("except")
+else:
+    order.append("else")
+finally:
+    order.append("finally")
+assert order, ["try", "else", "finally"]
+
+order = []
+for i in range(3):
+    try:
+        if i == 1:
+            continue
+        order.append(i)
+    finally:
+        order.append("f")
+assert order, [0, "f", "f", 2, "f"]
+
+order = []
+try:
+    try:
+        raise IndexError("inner")
+    finally:
+        order.append("cleanup")
+except IndexError as e:
+    order.append(str(e))
+assert order, ["cleanup", "inner"]
+
+try:
+    try:
+        raise TypeError("first")
+    except TypeError:
+        raise
+except TypeError as e:
+    assert str(e), "first"
+
+try:
+    raise 5
+except TypeError as e:
+    assert str(e), "exceptions must derive from BaseException"
+
+try:
+    try:
+        raise ValueError
+    except int:
+        pass
+except TypeError as e:
+    assert str(e), "catching classes that do not inherit from BaseException is not allowed"
+
+try:
+    raise
+except RuntimeError as e:
+    assert str(e), "No active exception to reraise"
+
+try:
+    undefined_name
+except NameError as e:
+    assert str(e), "name 'undefined_name' is not defined"
+
+try:
+    [1, 2][5]
+except Exception as e:
+    assert isinstance(e, IndexError)
+
+e = ValueError("x")
+assert e.with_traceback(None) is e
+assert e.__traceback__ is None
+
+def fail_deep():
+    raise ValueError("deep")
+
+try:
+    fail_deep()
+except ValueError as e:
+    caught = e
+
+tb = caught.__traceback__
+assert tb.tb_lineno - tb.tb_next.tb_lineno, 3
+assert tb.tb_next.tb_next, None
+assert caught.with_traceback(tb).__traceback__ is tb
+
+try:
+    caught.__traceback__ = 1
+except TypeError as err:
+    assert str(err), "__traceback__ must be a traceback or None"
+
+class Point:
+    __eq__ = None
+
+hash(AppError)
+try:
+    hash(Point())
+except TypeError as e:
+    assert str(e), "unhashable type: 'Point'"
+
+print("exceptions ok")
@@ -0,0 +1,50 @@
+import sys
+
+try:
+    sys.exit(3)
+except SystemExit as e:
+    assert e.code, 3
+    assert e.args, (3,)
+
+try:
+    exit()
+except SystemExit as e:
+    assert e.code is None
+
+try:
+    quit("bye")
+except SystemExit as e:
+    assert e.code, "bye"
+    assert str(e), "bye"
+
+try:
+    raise SystemExit(1, 2)
+except SystemExit as e:
+    assert e.code, (1, 2)
+
+try:
+    sys.exit()
+except Exception:
+    assert False
+except BaseException as e:
+    assert isinstance(e, SystemExit)
+    assert not isinstance(e, Exception)
+
+try:
+    exit(1, 2)
+except TypeError as e:
+    assert str(e), "exit() takes at most 1 argument (2 given)"
+
+cleaned_up = []
+try:
+    try:
+        sys.exit(0)
+    finally:
+        cleaned_up.append(True)
+except SystemExit:
+    pass
+assert cleaned_up, [True]
+
+print("exit ok")
+sys.exit(0)
+print("unreachable")
@@ -0,0 +1,100 @@
+events = []
+
+def returns_through_finally():
+    try:
+        return "try"
+    finally:
+        events.append("finally")
+
+assert returns_through_finally(), "try"
+assert events, ["finally"]
+
+def finally_overrides_return():
+    try:
+        return "try"
+    finally:
+        return "finally"
+
+assert finally_overrides_return(), "finally"
+
+def finally_swallows_exception():
+    try:
+        raise KeyError("lost")
+    finally:
+        return "swallowed"
+
+assert finally_swallows_exception(), "swallowed"
+
+def return_from_handler():
+    try:
+        raise ValueError("x")
+    except ValueError as e:
+        return e.args
+    finally:
+        events.append("handler finally")
+
+assert return_from_handler(), ("x",)
+assert events, ["finally", "handler finally"]
+
+def loop_jumps():
+    seen = []
+    for i in range(5):
+        try:
+            if i == 1:
+                continue
+            if i == 3:
+                break
+            seen.append(i)
+        finally:
+            seen.append(-i)
+    return seen
+
+assert loop_jumps(), [0, 0, -1, 2, -2, -3]
+
+def nested_loops():
+    total = 0
+    for i in range(3):
+        j = 0
+        while True:
+            j += 1
+            if j > i:
+                break
+            total += j
+    return total
+
+assert nested_loops(), 4
+
+count = 0
+while True:
+    try:
+        count += 1
+        if count == 3:
+            break
+    except ValueError:
+        pass
+assert count, 3
+
+def handler_name_unbound():
+    try:
+        1 / 0
+    except ZeroDivisionError as err:
+        pass
+    try:
+        err
+    except NameError:
+        return "unbound"
+
+assert handler_name_unbound(), "unbound"
+
+def add(a, b):
+    return a + b
+
+try:
+    add(1)
+except TypeError as e:
+    assert str(e), "add() missing 1 required positional argument: 'b'"
+
+try:
+    add(1, 2, 3)
+except TypeError as e:
+    assert str(e), "add() takes 2 positional arguments but 3 were given"
@@ -0,0 +1,103 @@
+import gc
+
+finalized = []
+
+class Node:
+    def __del__(self):
+        finalized.append(self.name)
+
+a = Node()
+a.name = "a"
+b = Node()
+b.name = "b"
+a.other = b
+b.other = a
+a = None
+b = None
+assert gc.collect() >= 2, True
+assert len(finalized), 2
+assert "a" in finalized, True
+assert "b" in finalized, True
+
+items = []
+items.append(items)
+items = None
+assert gc.collect() >= 1, True
+
+kept = Node()  # reachable cycles survive
+kept.name = "kept"
+kept.me = kept
+assert gc.collect(), 0
+assert kept.me.name, "kept"
+
+saved = []
+
+class Phoenix:
+    def __del__(self):
+        saved.append(self)
+
+p = Phoenix()
+p.me = p
+p = None
+assert gc.collect(), 0
+assert len(saved), 1
+saved = None
+assert gc.collect() >= 1, True
+
+class Loud:
+    def __del__(self):
+        raise ValueError("ignored")
+
+loud = Loud()
+loud.me = loud
+loud = None
+assert gc.collect() >= 1, True
+
+assert gc.isenabled(), True
+gc.disable()
+assert gc.isenabled(), False
+gc.enable()
+assert gc.isenabled(), True
+
+assert gc.get_threshold(), (700, 10, 10)
+gc.set_threshold(100, 5)
+assert gc.get_threshold(), (100, 5, 10)
+
+i = 0
+while i < 1000:  # the automatic collections keep the young generation small
+    x = Node()
+    x.name = "x"
+    x.me = x
+    i += 1
+assert gc.get_count()[0] < 100, True
+gc.set_threshold(700, 10, 10)
+
+freed = []
+
+class Temporary:
+    def __del__(self):
+        freed.append(self.name)
+
+t = Temporary()
+t.name = "t"
+del t  # no cycle, so dropping the last reference runs `__del__`
+assert freed, ["t"]
+
+def scoped():
+    s = Temporary()
+    s.name = "s"
+
+scoped()
+assert freed, ["t", "s"]
+
+Temporary.default = Temporary()  # freeing a replaced class attribute looks up `__del__` on that same class
+Temporary.default.name = "default"
+Temporary.default = None
+assert freed, ["t", "s", "default"]
+
+try:
+    gc.collect(3)
+except ValueError as e:
+    assert str(e), "invalid generation"
+
+print("gc ok")
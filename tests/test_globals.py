@@ -0,0 +1,57 @@
+def length(items):
+    return len(items)
+
+assert length([1, 2]), 2
+
+def len(items):  # shadows the builtin, so every cached lookup of `len` has to notice
+    return "shadowed"
+
+assert length([1, 2]), "shadowed"
+
+total = 0
+i = 0
+while i < 100:
+    total += i
+    i += 1
+
+assert total, 4950
+
+def reads_total():
+    return total
+
+assert reads_total(), 4950
+total = "rebound"
+assert reads_total(), "rebound"
+
+def reads_later():
+    return defined_later
+
+try:
+    reads_later()
+    assert False, True
+except NameError as e:
+    assert e.args, ("name 'defined_later' is not defined",)
+
+defined_later = 1
+assert reads_later(), 1
+
+for attempt in [1, 2]:
+    try:
+        raise ValueError(attempt)
+    except ValueError as err:
+        pass
+
+    try:
+        err
+        assert False, True
+    except NameError:
+        pass
+
+class Namespace:
+    total = "class"
+    seen = reads_total()
+
+assert Namespace.total, "class"
+assert Namespace.seen, "rebound"
+
+print("globals ok")
@@ -0,0 +1,42 @@
+assert hash(1), 1
+assert hash(-1), -2
+assert hash(True), 1
+assert hash(1.0), hash(1)
+assert hash(1.5), 1152921504606846977
+assert hash(2 ** 61 - 1), 0
+assert hash(None), 4238894112
+
+assert hash(""), 0
+assert hash(b""), 0
+assert hash("abc"), hash(b"abc")
+
+assert hash(()), 5740354900026072187
+assert hash((1, 2)), -3550055125485641917
+assert hash(((1, 2), 3)), -333907151259015829
+assert hash((1, 2)) == hash(tuple([1, 2]))
+assert (1, 2) == (1, 2)
+assert (1, 2) != (2, 1)
+assert (1,) + (2,), (1, 2)
+assert len((1, 2, 3)), 3
+assert (1, 2, 3)[-1], 3
+assert (1, 2, 3)[1:], (2, 3)
+assert 2 in (1, 2, 3)
+
+class Minus:
+    def __hash__(self):
+        return -1
+
+class Wide:
+    def __hash__(self):
+        return 2 ** 61 + 5
+
+assert hash(Minus()), -2
+assert hash(Wide()), 6
+assert hash(Wide()) == hash(2 ** 61 + 5)
+
+o = object()
+assert hash(o) == hash(o)
+assert hash(o) != hash(object())
+assert hash(len) == hash(len)
+
+print(hash(7), hash(2.5), hash((1, "a")), (1,), ())
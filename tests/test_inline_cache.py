@@ -0,0 +1,67 @@
+def add(a, b):
+    return a + b
+
+def less(a, b):
+    return a < b
+
+results = []
+for pair in [(1, 2), (1.5, 2), (1, 2.5), ("a", "b"), (b"a", b"b"), (3, 4)]:  # one site sees several operand classes
+    results.append(add(pair[0], pair[1]))
+
+assert results, [3, 3.5, 3.5, "ab", b"ab", 7]
+
+results = []
+for pair in [(1, 2), (2.5, 1), (1, 1.5), (True, 2), (2, 2)]:
+    results.append(less(pair[0], pair[1]))
+
+assert results, [True, False, True, True, False]
+
+try:
+    add(1, "a")
+    assert False, True
+except TypeError as e:
+    assert e.args, ("unsupported operand type(s) for +: 'int' and 'str'",)
+
+assert add(2, 3), 5
+
+try:
+    less(1, "a")
+    assert False, True
+except TypeError as e:
+    assert e.args, ("'<' not supported between instances of 'int' and 'str'",)
+
+class Point:
+    x = 1
+
+def greet(self):
+    return "hello"
+
+Point.x = 2
+Point.greet = greet
+assert Point.x, 2
+assert Point().greet(), "hello"
+
+try:
+    int.x = 1
+    assert False, True
+except TypeError as e:
+    assert e.args, ("cannot set 'x' attribute of immutable type 'int'",)
+
+import weakref
+
+class Target:
+    pass
+
+class Ref(weakref.ref):
+    def __eq__(self, other):
+        return "ref eq"
+
+def equal(a, b):
+    return a == b
+
+target = Target()
+plain = weakref.ref(target)
+assert equal(plain, plain), True  # caches `weakref.__eq__` for two plain references
+assert equal(plain, Ref(target)), "ref eq"  # a subclass's reflected method still goes first
+
+print("inline cache ok")
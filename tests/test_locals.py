@@ -0,0 +1,91 @@
+count = 10
+
+def shadows_global(count):
+    count += 1
+    return count
+
+assert shadows_global(1), 2
+assert count, 10
+
+def reads_global():
+    return count * 2
+
+assert reads_global(), 20
+
+def unbound_before_assignment():
+    total = count
+    count = 5
+    return total
+
+try:
+    unbound_before_assignment()
+    assert False, True
+except UnboundLocalError as e:
+    assert e.args, ("cannot access local variable 'count' where it is not associated with a value",)
+
+try:
+    unbound_before_assignment()
+except NameError:
+    pass
+
+def unbound_in_branch(flag):
+    if flag:
+        value = "set"
+    return value
+
+assert unbound_in_branch(True), "set"
+
+try:
+    unbound_in_branch(False)
+    assert False, True
+except UnboundLocalError:
+    pass
+
+def loop_locals(items):
+    total = 0
+    for item in items:
+        total += item
+    return (total, item)
+
+assert loop_locals([1, 2, 3]), (6, 3)
+
+def handler_name_is_unbound():
+    try:
+        raise KeyError("k")
+    except KeyError as e:
+        pass
+    return e
+
+try:
+    handler_name_is_unbound()
+    assert False, True
+except UnboundLocalError:
+    pass
+
+def recursive_frames(n):
+    local = n
+    if n > 0:
+        recursive_frames(n - 1)
+    return local
+
+assert recursive_frames(5), 5
+
+def defines_inner():
+    def inner(x):
+        return x + 1
+    class Box:
+        size = 3
+    return inner(Box.size)
+
+assert defines_inner(), 4
+
+class Counter:
+    start = 1
+
+    def total(self, extra):
+        return extra + 1
+
+assert Counter().total(1), 2
+assert Counter.start, 1
+
+print("locals ok")
@@ -0,0 +1,35 @@
+nums = [1, 2, 3]
+assert 2 in nums
+assert 4 not in nums
+assert not (4 in nums)
+assert 2.0 in nums
+assert True in nums
+
+nan = float("nan")
+assert nan in [nan]
+assert nan not in [float("nan")]
+
+r = range(0, 100, 5)
+assert 10 in r
+assert 11 not in r
+assert 100 not in r
+assert -5 not in r
+assert 10.0 in r
+assert 95 in range(95, 0, -5)
+assert 0 not in range(95, 0, -5)
+assert 9223372036854775806 in range(9223372036854775800, 9223372036854775807)
+
+assert "ell" in "hello"
+assert "" in "hello"
+assert "z" not in "hello"
+assert b"ell" in b"hello"
+assert 104 in b"hello"
+assert 104 in bytearray(b"hello")
+
+evens = []
+for i in range(10):
+    if i not in [1, 3, 5, 7, 9]:
+        evens.append(i)
+assert evens, [0, 2, 4, 6, 8]
+
+print(2 in nums, 11 in r, "ell" in "hello")
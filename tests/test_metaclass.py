@@ -0,0 +1,158 @@
+class Base:
+    kind = "base"
+
+assert type(5) is int, True
+assert type("s") is str, True
+assert type(Base()) is Base, True
+assert type(Base) is type, True
+assert type(int) is type, True
+assert isinstance(Base, type), True
+assert isinstance(Base(), type), False
+assert str(Base), "<class '__main__.Base'>"
+assert str(int), "<class 'int'>"
+assert Base.__class__ is type, True
+
+Made = type("Made", (Base,), {"answer": 42})
+m = Made()
+assert m.answer, 42
+assert m.kind, "base"
+assert Made.__name__, "Made"
+assert isinstance(m, Base), True
+
+Empty = type("Empty", (), {})
+assert Empty.__bases__[0] is object, True
+
+try:
+    type("Broken", (Base,))
+except TypeError as e:
+    assert str(e), "type() takes 1 or 3 arguments"
+
+try:
+    type("Pairs", (), [("answer", 42)])
+except TypeError as e:
+    assert str(e), "type.__new__() argument 3 must be dict, not list"
+
+registry = []
+
+class PluginMeta(type):
+    def __new__(mcs, name, bases, namespace):
+        namespace["tag"] = "tag:" + name
+        assert isinstance(namespace, dict), True
+        cls = super().__new__(mcs, name, bases, namespace)
+        if len(bases) > 0:
+            registry.append(name)
+        return cls
+
+    def __init__(cls, name, bases, namespace):
+        super().__init__(name, bases, namespace)
+        cls.plugin_name = "plugin:" + name
+
+    def describe(cls):
+        return "plugin " + cls.plugin_name
+
+class Plugin(metaclass=PluginMeta):
+    pass
+
+class CsvPlugin(Plugin):
+    pass
+
+class JsonPlugin(Plugin):
+    pass
+
+assert len(registry), 2
+assert registry[0], "CsvPlugin"
+assert registry[1], "JsonPlugin"
+assert type(CsvPlugin) is PluginMeta, True
+assert isinstance(JsonPlugin, PluginMeta), True
+assert isinstance(JsonPlugin, type), True
+assert JsonPlugin.plugin_name, "plugin:JsonPlugin"
+assert JsonPlugin.tag, "tag:JsonPlugin"
+assert JsonPlugin.describe(), "plugin plugin:JsonPlugin"
+
+seen_names = []
+
+class OrderMeta(type):
+    def __new__(mcs, name, bases, namespace):
+        seen_names.append(list(namespace.keys()))
+        return super().__new__(mcs, name, bases, namespace)
+
+class Ordered(metaclass=OrderMeta):  # the namespace keeps the definition order
+    zeta = 1
+
+    def alpha(self):
+        pass
+
+    mid = 2
+
+assert seen_names, [["zeta", "alpha", "mid"]]
+assert bool(Ordered), True  # classes and builtin functions are always true
+assert bool(len), True
+assert hasattr(JsonPlugin(), "describe"), False
+
+class Singleton(type):
+    def __call__(cls):
+        if cls.instance is None:
+            cls.instance = super().__call__()
+        return cls.instance
+
+class Config(metaclass=Singleton):
+    instance = None
+
+assert Config() is Config(), True
+
+class Shape:
+    shapes = []
+
+    def __init_subclass__(cls):
+        super().__init_subclass__()
+        Shape.shapes.append(cls.__name__)
+
+class Square(Shape):
+    pass
+
+class Circle(Shape):
+    pass
+
+assert len(Shape.shapes), 2
+assert Shape.shapes[0], "Square"
+
+class Box:
+    def __class_getitem__(cls, item):
+        return cls.__name__ + " of " + item.__name__
+
+assert Box[int], "Box of int"
+
+try:
+    Base[int]
+except TypeError as e:
+    assert str(e), "type 'Base' is not subscriptable"
+
+class OtherMeta(type):
+    pass
+
+class Other(metaclass=OtherMeta):
+    pass
+
+try:
+    class Mixed(Plugin, Other):
+        pass
+except TypeError as e:
+    assert str(e), "metaclass conflict: the metaclass of a derived class must be a (non-strict) subclass of the metaclasses of all its bases"
+
+try:
+    Base.missing
+except AttributeError as e:
+    assert str(e), "type object 'Base' has no attribute 'missing'"
+
+class Point:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+class Point3D(Point):
+    pass
+
+p = Point3D(1, 2)
+assert p.x + p.y, 3
+
+print("metaclass ok")
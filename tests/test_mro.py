@@ -0,0 +1,117 @@
+class Base:
+    def who(self):
+        return "Base"
+
+    def describe(self):
+        return "base"
+
+class Left(Base):
+    def who(self):
+        return "Left " + super().who()
+
+class Right(Base):
+    def who(self):
+        return "Right " + super().who()
+
+    def describe(self):
+        return "right"
+
+class Diamond(Left, Right):
+    def who(self):
+        return "Diamond " + super().who()
+
+def names(classes):
+    joined = ""
+    for pyclass in classes:
+        joined += pyclass.__name__ + " "
+    return joined
+
+d = Diamond()
+assert d.who(), "Diamond Left Right Base"
+assert d.describe(), "right"  # depth-first would have found Base first
+assert names(Diamond.__mro__), "Diamond Left Right Base object "
+assert names(Left.__mro__), "Left Base object "
+assert names(Diamond.__bases__), "Left Right "
+assert Diamond.__name__, "Diamond"
+
+assert super(Left, d).who(), "Right Base"
+assert super(Diamond, d).describe(), "right"
+assert super(Right, d).describe(), "base"
+
+class JsonMixin:
+    def dump(self):
+        return "json:" + self.name()
+
+class ReprMixin:
+    def dump(self):
+        return "repr:" + self.name()
+
+    def name(self):
+        return "mixin"
+
+class Model:
+    def name(self):
+        return "model"
+
+class Record(JsonMixin, ReprMixin, Model):
+    pass
+
+r = Record()
+assert r.dump(), "json:mixin"
+assert names(Record.__mro__), "Record JsonMixin ReprMixin Model object "
+
+class Counted:
+    made = 0
+
+    @classmethod
+    def make(cls):
+        cls.made = cls.made + 1
+        return cls
+
+class SubCounted(Counted):
+    @classmethod
+    def make(cls):
+        return super().make()
+
+assert SubCounted.make() is SubCounted, True
+assert SubCounted.made, 1
+
+class First:
+    pass
+
+class Second(First):
+    pass
+
+try:
+    class Broken(First, Second):
+        pass
+except TypeError as e:
+    assert "order (MRO) for bases First, Second" in str(e), True
+
+try:
+    class Twice(First, First):
+        pass
+except TypeError as e:
+    assert str(e), "duplicate base class First"
+
+try:
+    super(Left, 5)
+except TypeError as e:
+    assert str(e), "super(type, obj): obj must be an instance or subtype of type"
+
+try:
+    super()
+except RuntimeError as e:
+    assert str(e), "super(): no arguments"
+
+class Greeter:
+    def greet(self):
+        return "hi"
+
+class Loud(Greeter):
+    def greet(self):
+        sup = super()
+        return sup.greet() + "!"
+
+assert Loud().greet(), "hi!"
+assert isinstance(Loud().greet, object), True
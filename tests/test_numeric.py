@@ -0,0 +1,75 @@
+assert 1 == 1.0
+assert 1.0 == True
+assert True + 1.5, 2.5
+assert True + True, 2
+assert 2 ** -1, 0.5
+assert 2 ** 10, 1024
+assert -2 ** 2, -4
+assert (-2) ** 2, 4
+assert pow(3, 4, 5), 1
+assert pow(3, -1, 7), 5
+assert pow(2, 3), 8
+
+assert 7 // 2, 3
+assert -7 // 2, -4
+assert 7 % 3, 1
+assert -7 % 3, 2
+assert 7 % -3, -2
+assert 7.5 // 2, 3.0
+assert -7.5 // 2, -4.0
+assert 7.5 % -2, -0.5
+assert 10 / 4, 2.5
+assert 1 - 0.5, 0.5
+assert 3 - 1.5, 1.5
+
+assert -(5), -5
+assert +(5), 5
+assert -(-1.5), 1.5
+assert -(1+2j), -1-2j
+assert abs(-7), 7
+
+inf = float("inf")
+nan = float("nan")
+assert inf > 10 ** 18
+assert -inf < -(10 ** 18)
+assert nan != nan
+assert not nan == nan
+assert not nan < 1
+assert not nan >= 1
+assert 2 ** 53 + 1 != float(2 ** 53 + 1)
+assert 2 ** 53 + 1 > float(2 ** 53)
+assert 0.5 < 1
+assert 1 < 1.5
+
+assert str(1.0), "1.0"
+assert str(0.1), "0.1"
+assert str(-0.0), "-0.0"
+assert str(1e16), "1e+16"
+assert str(1.5e-05), "1.5e-05"
+assert str(123456789.25), "123456789.25"
+assert str(inf), "inf"
+assert str(-inf), "-inf"
+assert str(nan), "nan"
+
+assert int(" 1_000 "), 1000
+assert int(3.9), 3
+assert int(-3.9), -3
+assert float(" 2.5 "), 2.5
+assert float("1_000.5"), 1000.5
+assert float("-inf"), -inf
+
+assert 9223372036854775807 // 2, 4611686018427387903
+try:
+    9223372036854775808
+except OverflowError as e:
+    assert str(e), "integer literal too large: 9223372036854775808"
+
+x = 10
+x -= 3
+assert x, 7
+x *= 2
+assert x, 14
+x /= 4
+assert x, 3.5
+
+print(7 // 2, -7 % 3, 2 ** -1, pow(3, -1, 7), 0.1 + 0.2, 1e300 * 10)
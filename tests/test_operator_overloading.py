@@ -0,0 +1,377 @@
+class Vector:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+    def __add__(self, other):
+        if isinstance(other, Vector):
+            return Vector(self.x + other.x, self.y + other.y)
+        return NotImplemented
+
+    def __sub__(self, other):
+        return Vector(self.x - other.x, self.y - other.y)
+
+    def __mul__(self, scale):
+        if isinstance(scale, int):
+            return Vector(self.x * scale, self.y * scale)
+        return NotImplemented
+
+    def __rmul__(self, scale):
+        return self * scale
+
+    def __neg__(self):
+        return Vector(-self.x, -self.y)
+
+    def __abs__(self):
+        return self.x * self.x + self.y * self.y
+
+    def __eq__(self, other):
+        if not isinstance(other, Vector):
+            return NotImplemented
+        return (self.x == other.x) and (self.y == other.y)
+
+    def __lt__(self, other):
+        return abs(self) < abs(other)
+
+    def __bool__(self):
+        return (self.x != 0) or (self.y != 0)
+
+    def __repr__(self):
+        return "Vector(" + str(self.x) + ", " + str(self.y) + ")"
+
+a = Vector(1, 2)
+b = Vector(3, 4)
+assert str(a + b), "Vector(4, 6)"
+assert str(b - a), "Vector(2, 2)"
+assert str(a * 3), "Vector(3, 6)"
+assert str(3 * a), "Vector(3, 6)"
+assert str(-a), "Vector(-1, -2)"
+assert abs(b), 25
+assert a == Vector(1, 2), True
+assert a != Vector(1, 2), False
+assert a != b, True
+assert a == 5, False
+assert a < b, True
+assert b > a, True
+assert bool(Vector(0, 0)), False
+assert str(a), "Vector(1, 2)"
+
+if Vector(0, 1):
+    truthy = True
+else:
+    truthy = False
+assert truthy, True
+
+try:
+    a + 1
+except TypeError as e:
+    assert str(e), "unsupported operand type(s) for +: 'Vector' and 'int'"
+
+try:
+    a * 2.5
+except TypeError as e:
+    assert str(e), "unsupported operand type(s) for *: 'Vector' and 'float'"
+
+try:
+    hash(a)
+except TypeError as e:
+    assert str(e), "unhashable type: 'Vector'"
+
+class Meters:
+    def __init__(self, value):
+        self.value = value
+
+    def __add__(self, other):
+        return Meters(self.value + other)
+
+    def __radd__(self, other):
+        return Meters(other + self.value)
+
+    def __truediv__(self, other):
+        return self.value / other
+
+    def __floordiv__(self, other):
+        return self.value // other
+
+    def __mod__(self, other):
+        return self.value % other
+
+    def __pow__(self, other):
+        return self.value ** other
+
+    def __rsub__(self, other):
+        return other - self.value
+
+assert (Meters(2) + 3).value, 5
+assert (3 + Meters(2)).value, 5
+assert Meters(7) / 2, 3.5
+assert Meters(7) // 2, 3
+assert Meters(7) % 2, 1
+assert Meters(3) ** 2, 9
+assert 10 - Meters(4), 6
+
+class Base:
+    def __add__(self, other):
+        return "base add"
+
+    def __radd__(self, other):
+        return "base radd"
+
+class Derived(Base):
+    def __radd__(self, other):
+        return "derived radd"
+
+assert Base() + Derived(), "derived radd"  # a subclass's reflected method goes first
+assert Derived() + Base(), "base add"
+
+class Strict:
+    def __add__(self, other):
+        raise NotImplementedError("no adding")
+
+    def __lt__(self, other):
+        raise NotImplementedError("no ordering")
+
+    def __eq__(self, other):
+        raise NotImplementedError("no equality")
+
+class Lenient:
+    def __radd__(self, other):
+        return "lenient radd"
+
+    def __gt__(self, other):
+        return "lenient gt"
+
+try:  # only returning NotImplemented tries the reflected method, raising NotImplementedError is an error
+    Strict() + Lenient()
+    assert False, True
+except NotImplementedError as e:
+    assert str(e), "no adding"
+
+try:
+    Strict() < Lenient()
+    assert False, True
+except NotImplementedError as e:
+    assert str(e), "no ordering"
+
+try:  # also when it comes out of a builtin comparing the items
+    [Strict()] == [Strict()]
+    assert False, True
+except NotImplementedError as e:
+    assert str(e), "no equality"
+
+class Bag:
+    def __init__(self):
+        self.items = [1, 2, 3]
+
+    def __len__(self):
+        return len(self.items)
+
+    def __contains__(self, item):
+        return item in self.items
+
+    def __getitem__(self, index):
+        return self.items[index]
+
+    def __setitem__(self, index, value):
+        self.items[index] = value
+
+    def __hash__(self):
+        return 7
+
+bag = Bag()
+assert len(bag), 3
+assert 2 in bag, True
+assert 5 not in bag, True
+assert bag[1], 2
+bag[1] = 20
+assert bag[1], 20
+assert hash(bag), 7
+assert bool(bag), True
+
+class Countdown:
+    def __init__(self, start):
+        self.current = start
+
+    def __iter__(self):
+        return self
+
+    def __next__(self):
+        if self.current == 0:
+            raise StopIteration
+        self.current = self.current - 1
+        return self.current + 1
+
+total = 0
+for n in Countdown(4):
+    total += n
+assert total, 10
+assert 3 in Countdown(4), True
+assert len(list(Countdown(3))), 3
+
+class Version:
+    def __init__(self, number):
+        self.number = number
+
+    def __le__(self, other):
+        return self.number <= other.number
+
+    def __ge__(self, other):
+        return self.number >= other.number
+
+    def __str__(self):
+        return "v" + str(self.number)
+
+    def __int__(self):
+        return self.number
+
+    def __float__(self):
+        return 0.5
+
+assert Version(1) <= Version(2), True
+assert Version(3) >= Version(2), True
+assert str(Version(4)), "v4"
+assert int(Version(5)), 5
+assert float(Version(5)), 0.5
+
+try:
+    Version(1) < Version(2)
+except TypeError as e:
+    assert str(e), "'<' not supported between instances of 'Version' and 'Version'"
+
+class Unhashable:
+    __hash__ = None
+
+try:
+    hash(Unhashable())
+except TypeError as e:
+    assert str(e), "unhashable type: 'Unhashable'"
+
+class Shadowed:
+    __add__ = 5
+    __eq__ = None
+    __len__ = None
+    __repr__ = None
+
+try:
+    Shadowed() + 1
+except TypeError as e:
+    assert str(e), "'int' object is not callable"
+
+try:
+    Shadowed() == 1
+except TypeError as e:
+    assert str(e), "'NoneType' object is not callable"
+
+try:
+    len(Shadowed())
+except TypeError as e:
+    assert str(e), "'NoneType' object is not callable"
+
+try:
+    str(Shadowed())
+except TypeError as e:
+    assert str(e), "'NoneType' object is not callable"
+
+class Audited:  # conversion dunders are free to mutate the instance they are called on
+    def __init__(self):
+        self.calls = 0
+
+    def __bool__(self):
+        self.calls = self.calls + 1
+        return True
+
+    def __int__(self):
+        self.calls = self.calls + 1
+        return 7
+
+    def __float__(self):
+        self.calls = self.calls + 1
+        return 2.5
+
+    def __len__(self):
+        self.calls = self.calls + 1
+        return 3
+
+audited = Audited()
+assert bool(audited), True
+assert int(audited), 7
+assert float(audited), 2.5
+assert len(audited), 3
+assert audited.calls, 4
+
+class Sized:
+    def __len__(self):
+        self.calls = 1
+        return 0
+
+assert bool(Sized()), False
+
+class BadIter:
+    def __iter__(self):
+        return 5
+
+try:
+    for item in BadIter():
+        pass
+    assert False, True
+except TypeError as e:
+    assert str(e), "iter() returned non-iterator of type 'int'"
+
+try:
+    list(BadIter())
+    assert False, True
+except TypeError as e:
+    assert str(e), "iter() returned non-iterator of type 'int'"
+
+try:
+    1 in BadIter()
+    assert False, True
+except TypeError as e:
+    assert str(e), "iter() returned non-iterator of type 'int'"
+
+class BadStr:
+    def __str__(self):
+        return 1
+
+    def __repr__(self):
+        return None
+
+try:
+    str(BadStr())
+    assert False, True
+except TypeError as e:
+    assert str(e), "__str__ returned non-string (type int)"
+
+try:
+    str([BadStr()])  # the items of a list go through their `__repr__`
+    assert False, True
+except TypeError as e:
+    assert str(e), "__repr__ returned non-string (type NoneType)"
+
+class BadLen:
+    def __len__(self):
+        return "3"
+
+try:
+    len(BadLen())
+    assert False, True
+except TypeError as e:
+    assert str(e), "'str' object cannot be interpreted as an integer"
+
+try:
+    bool(BadLen())
+    assert False, True
+except TypeError as e:
+    assert str(e), "'str' object cannot be interpreted as an integer"
+
+class NegativeLen:
+    def __len__(self):
+        return -1
+
+try:
+    len(NegativeLen())
+    assert False, True
+except ValueError as e:
+    assert str(e), "__len__() should return >= 0"
+
+print("operator overloading ok")
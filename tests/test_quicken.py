@@ -0,0 +1,65 @@
+def add(a, b):
+    return a + b
+
+def less(a, b):
+    return a < b
+
+i = 0
+while i < 20:  # warms both sites up with ints
+    add(i, i)
+    less(i, 10)
+    i += 1
+
+assert add(1.5, 2.0), 3.5
+assert add("a", "b"), "ab"
+assert add(True, 2), 3
+assert add(2, 3), 5
+
+assert less(1.5, 2.5), True
+assert less(3, 2), False
+
+x = 0.0
+i = 0
+while i < 20:
+    x = add(x, 0.5)
+    i += 1
+
+assert x, 10.0
+assert add(1, 2), 3
+
+def divide(a, b):
+    return a / b
+
+i = 0
+while i < 20:
+    divide(1.0, 2.0)
+    i += 1
+
+try:
+    divide(1.0, 0.0)
+    assert False, True
+except ZeroDivisionError:
+    pass
+
+def total(iterable):
+    result = 0
+    for item in iterable:
+        result += item
+    return result
+
+assert total(range(10)), 45
+assert total([1, 2, 3]), 6
+assert total(range(10, 0, -2)), 30
+assert total(range(0)), 0
+assert total((4, 5)), 9
+
+def count_truthy(values):
+    count = 0
+    for value in values:
+        if value:
+            count += 1
+    return count
+
+assert count_truthy([True, True, False, 1, 0, "", "a", None, [1]]), 5
+
+print("quicken ok")
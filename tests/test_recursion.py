@@ -0,0 +1,78 @@
+import sys
+
+assert sys.getrecursionlimit(), 1000
+sys.setrecursionlimit(50)
+assert sys.getrecursionlimit(), 50
+sys.setrecursionlimit(100000)
+assert sys.getrecursionlimit(), 100000
+
+try:
+    sys.setrecursionlimit(0)
+except ValueError as e:
+    assert str(e), "recursion limit must be greater or equal than 1"
+
+try:
+    sys.setrecursionlimit(1)
+except RecursionError as e:
+    assert str(e), "cannot set the recursion limit to 1 at the recursion depth 1: the limit is too low"
+
+try:
+    sys.setrecursionlimit("10")
+except TypeError as e:
+    assert str(e), "'str' object cannot be interpreted as an integer"
+
+assert issubclass(RecursionError, RuntimeError)
+assert sys.__name__, "sys"
+
+try:
+    import not_a_module
+except ImportError as e:
+    assert isinstance(e, ModuleNotFoundError)
+    assert str(e), "No module named 'not_a_module'"
+
+sys.setrecursionlimit(1000)
+
+class Endless:
+    def __add__(self, other):
+        return self + other
+
+    def __eq__(self, other):
+        return self == other
+
+    def __bool__(self):
+        return not self
+
+    def __iter__(self):
+        return self
+
+    def __next__(self):
+        return 1 in self
+
+try:
+    Endless() + 1
+except RecursionError as e:
+    assert str(e), "maximum recursion depth exceeded"
+
+try:
+    Endless() == 1
+except RecursionError as e:
+    assert str(e), "maximum recursion depth exceeded"
+
+try:
+    not Endless()
+except RecursionError as e:
+    assert str(e), "maximum recursion depth exceeded"
+
+try:
+    1 in Endless()
+except RecursionError as e:
+    assert str(e), "maximum recursion depth exceeded"
+
+def countdown(n):
+    if n == 0:
+        return "done"
+    return countdown(n - 1)
+
+assert countdown(500), "done"
+
+print(sys, sys.getrecursionlimit())
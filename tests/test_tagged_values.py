@@ -0,0 +1,42 @@
+a = 5
+b = 2 + 3
+assert a is b, True
+assert (a is 6), False
+assert hash(a), 5
+
+assert None is None, True
+assert True is True, True
+assert (True is 1), False
+assert (1 is 1.0), False
+assert (0 is False), False
+
+x = 1.5
+assert x is x, True
+assert x * 2, 3.0
+assert x + 1, 2.5
+assert -x, -1.5
+assert 7 // 2, 3
+assert 2 ** 10, 1024
+assert 1 + True, 2
+assert 0.5 + 1, 1.5
+
+nan = float("nan")
+assert nan in [nan], True
+assert nan in [float("nan")], False
+
+assert None == None, True
+assert not None, True
+assert bool(0.0), False
+assert int(True) + 1, 2
+assert float(3), 3.0
+
+items = [1, 2.0, None, True]
+assert 2 in items, True
+assert None in items, True
+
+total = 0
+for i in range(1000):
+    total += i
+assert total, 499500
+
+print("tagged values ok")
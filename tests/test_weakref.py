@@ -0,0 +1,78 @@
+import gc
+import weakref
+
+class Node:
+    pass
+
+a = Node()
+a.name = "a"
+r = weakref.ref(a)
+assert r() is a, True
+assert r().name, "a"
+assert isinstance(r, weakref.ref), True
+assert r == weakref.ref(a), True
+assert hash(r), hash(a)
+a = None
+assert r() is None, True
+
+called = []
+
+def on_dead(ref):
+    called.append(ref)
+
+b = Node()
+rb = weakref.ref(b, on_dead)
+b = None  # the callback runs as soon as the referent is freed
+assert len(called), 1
+assert called[0] is rb, True
+
+c = Node()
+c.me = c
+rc = weakref.ref(c, on_dead)
+c = None
+gc.collect()
+assert rc() is None, True
+assert len(called), 2
+
+try:
+    weakref.ref(5)
+except TypeError as e:
+    assert str(e), "cannot create weak reference to 'int' object"
+
+d = Node()
+d.value = 10
+p = weakref.proxy(d)
+assert p.value, 10
+p.value = 20
+assert d.value, 20
+d = None
+try:
+    p.value
+except ReferenceError as e:
+    assert str(e), "weakly-referenced object no longer exists"
+
+cache = weakref.WeakValueDictionary()
+e = Node()
+cache["e"] = e
+assert "e" in cache, True
+assert cache["e"] is e, True
+assert len(cache), 1
+e = None
+assert "e" in cache, False
+assert len(cache), 0
+assert cache.get("e"), None
+
+keys = weakref.WeakKeyDictionary()
+f = Node()
+keys[f] = "f"
+assert keys[f], "f"
+assert len(keys), 1
+f = None
+assert len(keys), 0
+
+g = Node()
+values = weakref.WeakValueDictionary([("g", g)])
+assert values.pop("g") is g, True
+assert len(values), 0
+
+print("weakref ok")